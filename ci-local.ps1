@@ -98,6 +98,7 @@ Run-Check "Format check" "cargo fmt --all -- --check"
 Run-Check "Clippy" "cargo clippy --all-targets --all-features -- -D warnings"
 Run-Check "Tests" "cargo test --verbose"
 Run-Check "Tests with dunce" "cargo test --features dunce --verbose"
+Run-Check "Tests (all features)" "cargo test --all-features --verbose"
 
 # Documentation
 $env:RUSTDOCFLAGS = "-D warnings"
@@ -82,6 +82,7 @@ echo ""
 run_check "Format check" "cargo fmt --all -- --check"
 run_check "Clippy" "cargo clippy --all-targets --all-features -- -D warnings"
 run_check "Tests" "cargo test --verbose"
+run_check "Tests (all features)" "cargo test --all-features --verbose"
 run_check "Documentation" "RUSTDOCFLAGS='-D warnings' cargo doc --no-deps --all-features"
 
 # MSRV check (optional - requires rustup)
@@ -0,0 +1,94 @@
+//! Resolving a path once into both its preserved and its host form.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The two forms of a path resolved by [`canonicalize_both`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResolvedBoth {
+    namespace: PathBuf,
+    host: PathBuf,
+}
+
+impl ResolvedBoth {
+    /// The path with its `/proc` boundary preserved, as [`canonicalize`](crate::canonicalize)
+    /// returns it.
+    pub fn namespace(&self) -> &Path {
+        &self.namespace
+    }
+
+    /// The same target as this process sees it, as [`to_host`](crate::to_host) returns it.
+    pub fn host(&self) -> &Path {
+        &self.host
+    }
+
+    /// The namespace and host forms, in that order.
+    pub fn into_parts(self) -> (PathBuf, PathBuf) {
+        (self.namespace, self.host)
+    }
+}
+
+/// [`canonicalize`](crate::canonicalize) and [`to_host`](crate::to_host) in one pass.
+///
+/// Resolving a boundary path already walks the host path to check containment, so the
+/// host form costs nothing extra; calling the two functions separately resolves
+/// everything twice. The forms are equal for paths without a boundary, and for paths
+/// that escaped their boundary under [`EscapeBehavior::ReturnHost`].
+///
+/// A bare boundary (`/proc/1234/root` itself) is the one case that takes an extra
+/// `realpath`, since [`canonicalize`](crate::canonicalize) never needs to look past it.
+/// A namespace link (`/proc/1234/ns/net`) is no directory and has no host path, so
+/// both forms are the link itself.
+///
+/// [`EscapeBehavior::ReturnHost`]: crate::EscapeBehavior::ReturnHost
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::canonicalize_both;
+///
+/// let resolved = canonicalize_both("/proc/self/root/etc")?;
+/// assert_eq!(resolved.namespace(), Path::new("/proc/self/root/etc"));
+/// assert_eq!(resolved.host(), std::fs::canonicalize("/etc")?);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Platform Behavior
+///
+/// - **Linux**: As described above
+/// - **Other platforms**: Both forms are the [`canonicalize`](crate::canonicalize) result
+///
+/// # Errors
+///
+/// Same as [`canonicalize`](crate::canonicalize).
+pub fn canonicalize_both(path: impl AsRef<Path>) -> io::Result<ResolvedBoth> {
+    #[cfg(target_os = "linux")]
+    {
+        let options = crate::CanonicalizeOptions::default();
+        let resolved = crate::resolve::Resolver::new(&options)
+            .with_host()
+            .canonicalize_both(path.as_ref())?;
+        let host = match resolved.host {
+            Some(host) => host,
+            None => resolved.namespace.clone(),
+        };
+        Ok(ResolvedBoth {
+            namespace: resolved.namespace,
+            host,
+        })
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let canonical = crate::canonicalize(path)?;
+        Ok(ResolvedBoth {
+            namespace: canonical.clone(),
+            host: canonical,
+        })
+    }
+}
@@ -0,0 +1,230 @@
+//! Resolving untrusted children of a trusted base, with the outcome as a type.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{checked_join, BoundaryEscaped, CanonicalizeOptions, EscapeBehavior};
+
+/// Where a child path resolved relative to the base it was joined onto.
+///
+/// Returned by [`join_and_canonicalize`]. Match on it to reject escapes explicitly
+/// rather than comparing prefixes by hand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Containment {
+    /// The child resolved to this path, at or below the base.
+    Inside(PathBuf),
+    /// The child left the base; this is where it points instead.
+    Escaped(PathBuf),
+}
+
+impl Containment {
+    /// The path, whichever side of the base it is on.
+    pub fn path(&self) -> &Path {
+        match self {
+            Containment::Inside(path) | Containment::Escaped(path) => path,
+        }
+    }
+
+    /// The path if it stayed inside the base, `None` if it escaped.
+    pub fn inside(self) -> Option<PathBuf> {
+        match self {
+            Containment::Inside(path) => Some(path),
+            Containment::Escaped(_) => None,
+        }
+    }
+
+    /// Whether the child stayed inside the base.
+    pub fn is_inside(&self) -> bool {
+        matches!(self, Containment::Inside(_))
+    }
+}
+
+/// Join an untrusted `child` onto an already-resolved `base` and resolve the result.
+///
+/// `base` should be the output of [`canonicalize`](crate::canonicalize), e.g.
+/// `/proc/1234/root/srv`. The child is first joined with [`checked_join`]; an absolute
+/// child or a `..` that climbs above `base` is reported as
+/// [`Escaped`](Containment::Escaped)`(base.join(child))` without touching the filesystem.
+/// Otherwise the joined path is resolved namespace-aware with
+/// [`EscapeBehavior::ClampToRoot`], so symlinks cannot carry it out of the namespace
+/// boundary: the `/proc/1234/root` prefix is kept. The result is
+/// [`Inside`](Containment::Inside) if it is still under `base` and
+/// [`Escaped`](Containment::Escaped) if a symlink led elsewhere.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use proc_canonicalize::{canonicalize, join_and_canonicalize, Containment};
+///
+/// let dir = tempfile::tempdir()?;
+/// std::fs::create_dir(dir.path().join("data"))?;
+/// let base = canonicalize(format!("/proc/self/root{}", dir.path().display()))?;
+///
+/// let inside = join_and_canonicalize(&base, "data/./")?;
+/// assert_eq!(inside, Containment::Inside(base.join("data")));
+///
+/// assert!(!join_and_canonicalize(&base, "../../etc")?.is_inside());
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Any error [`canonicalize`](crate::canonicalize) returns for the joined path, e.g.
+/// [`io::ErrorKind::NotFound`] if the child does not exist.
+pub fn join_and_canonicalize(
+    base: impl AsRef<Path>,
+    child: impl AsRef<Path>,
+) -> io::Result<Containment> {
+    let base = base.as_ref();
+    let joined = match checked_join(base, &child) {
+        Some(joined) => joined,
+        None => return Ok(Containment::Escaped(base.join(child))),
+    };
+
+    let resolved = CanonicalizeOptions::new()
+        .escape_behavior(EscapeBehavior::ClampToRoot)
+        .canonicalize(joined)?;
+    if resolved.starts_with(base) {
+        Ok(Containment::Inside(resolved))
+    } else {
+        Ok(Containment::Escaped(resolved))
+    }
+}
+
+/// Resolve `path` and fail unless it lies within one of the `allowed` boundaries.
+///
+/// Canonicalization, containment and policy in one call, for request handlers that may
+/// only touch specific containers. `path` is resolved namespace-aware with
+/// [`EscapeBehavior::Error`]; the result must then start with one of `allowed`,
+/// compared whole component by component, so `/proc/12345/root` is not inside an
+/// allowed `/proc/1234/root`. Give each allowed entry in the form
+/// [`canonicalize`](crate::canonicalize) returns, e.g. `/proc/1234/root` or
+/// `/proc/1234/root/srv`.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::assert_confined;
+///
+/// let allowed = [Path::new("/proc/self/root")];
+/// assert_eq!(
+///     assert_confined("/proc/self/root/etc/.", &allowed)?,
+///     Path::new("/proc/self/root/etc"),
+/// );
+///
+/// let err = assert_confined("/proc/self/cwd", &allowed).unwrap_err();
+/// assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// - [`io::ErrorKind::PermissionDenied`] if the path resolves outside every allowed
+///   boundary. An escape from the boundary it entered through carries the
+///   [`BoundaryEscaped`] payload, as with [`EscapeBehavior::Error`].
+/// - Any other error [`canonicalize`](crate::canonicalize) returns, e.g.
+///   [`io::ErrorKind::NotFound`].
+pub fn assert_confined(path: impl AsRef<Path>, allowed: &[&Path]) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    let resolved = resolve_confined(path)?;
+    if allowed
+        .iter()
+        .any(|boundary| resolved.starts_with(boundary))
+    {
+        Ok(resolved)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} resolves to {}, outside every allowed boundary",
+                path.display(),
+                resolved.display()
+            ),
+        ))
+    }
+}
+
+/// Resolve `path` and fail unless it lies within `root`.
+///
+/// The one-root form of [`assert_confined`], for the usual check of a user path
+/// against a container's root. Unlike there, `root` is itself canonicalized first, so
+/// it may be given in any form that names the directory: `/proc/1234/root`,
+/// `/proc/1234/root/srv/`, or a host path. `path` must then resolve, namespace-aware
+/// and with [`EscapeBehavior::Error`], to `root` or below it, compared whole component
+/// by component.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::resolve_under;
+///
+/// let etc = resolve_under("/proc/self/root/etc/./", "/proc/self/root/")?;
+/// assert_eq!(etc, Path::new("/proc/self/root/etc"));
+///
+/// let err = resolve_under("/proc/self/root/usr", "/proc/self/root/etc").unwrap_err();
+/// assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// - [`io::ErrorKind::PermissionDenied`] if `path` resolves outside `root`, with the
+///   [`BoundaryEscaped`] payload for an escape from its boundary, as in
+///   [`assert_confined`].
+/// - Any error [`canonicalize`](crate::canonicalize) returns for `root` or `path`,
+///   e.g. [`io::ErrorKind::NotFound`].
+pub fn resolve_under(path: impl AsRef<Path>, root: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    let root = crate::canonicalize(root)?;
+    let resolved = resolve_confined(path)?;
+    if resolved.starts_with(&root) {
+        Ok(resolved)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} resolves to {}, outside {}",
+                path.display(),
+                resolved.display(),
+                root.display()
+            ),
+        ))
+    }
+}
+
+/// `path` resolved with [`EscapeBehavior::Error`], an escape failing as
+/// [`io::ErrorKind::PermissionDenied`].
+fn resolve_confined(path: &Path) -> io::Result<PathBuf> {
+    match CanonicalizeOptions::new()
+        .escape_behavior(EscapeBehavior::Error)
+        .canonicalize(path)
+    {
+        Err(err) if is_escape(&err) => Err(err.into_inner().map_or_else(
+            || io::ErrorKind::PermissionDenied.into(),
+            |escaped| io::Error::new(io::ErrorKind::PermissionDenied, escaped),
+        )),
+        result => result,
+    }
+}
+
+fn is_escape(err: &io::Error) -> bool {
+    err.get_ref()
+        .is_some_and(|inner| inner.is::<BoundaryEscaped>())
+}
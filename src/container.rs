@@ -0,0 +1,104 @@
+//! Resolving paths inside a container named by ID, through a caller's lookup.
+
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::proc_root::proc_root;
+
+/// Maps a container ID to the PID of a process running in it.
+///
+/// This crate does no container discovery: the lookup is yours (a container runtime's
+/// API, a cgroup scan, a table your orchestrator keeps). [`canonicalize_in_container`]
+/// only composes its answer with [`canonicalize`](crate::canonicalize). Closures of
+/// the right shape implement it.
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use std::io;
+/// use proc_canonicalize::ContainerResolver;
+///
+/// struct Table(HashMap<String, u32>);
+///
+/// impl ContainerResolver for Table {
+///     fn pid_for(&self, container_id: &str) -> io::Result<u32> {
+///         self.0.get(container_id).copied().ok_or_else(|| io::ErrorKind::NotFound.into())
+///     }
+/// }
+/// ```
+pub trait ContainerResolver {
+    /// The PID of a process in `container_id`.
+    ///
+    /// # Errors
+    ///
+    /// Whatever the lookup fails with, e.g. [`io::ErrorKind::NotFound`] for an unknown
+    /// ID. It is returned unchanged by [`canonicalize_in_container`].
+    fn pid_for(&self, container_id: &str) -> io::Result<u32>;
+}
+
+impl<F> ContainerResolver for F
+where
+    F: Fn(&str) -> io::Result<u32>,
+{
+    fn pid_for(&self, container_id: &str) -> io::Result<u32> {
+        self(container_id)
+    }
+}
+
+/// Canonicalize `subpath` inside the container `container_id`, as
+/// `/proc/PID/root/SUBPATH` for the PID `resolver` names.
+///
+/// `subpath` is a path as the container sees it; absolute and relative spellings mean
+/// the same, so `/etc/hostname` and `etc/hostname` both resolve under the container's
+/// root. The joined path is then resolved exactly as [`canonicalize`](crate::canonicalize)
+/// would, including what happens on an escape; use [`CanonicalizeOptions`] on the
+/// joined path yourself for other behavior. The PID is looked up once per call, and
+/// the result is only meaningful while that process lives.
+///
+/// [`CanonicalizeOptions`]: crate::CanonicalizeOptions
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::io;
+/// use std::path::Path;
+/// use proc_canonicalize::canonicalize_in_container;
+///
+/// // A stand-in lookup: "me" is this process, running in the host's "container".
+/// let pid = std::process::id();
+/// let lookup = |id: &str| match id {
+///     "me" => Ok(pid),
+///     _ => Err(io::Error::from(io::ErrorKind::NotFound)),
+/// };
+///
+/// let resolved = canonicalize_in_container(&lookup, "me", "/etc")?;
+/// assert_eq!(resolved, Path::new(&format!("/proc/{pid}/root/etc")));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// - Any error from [`ContainerResolver::pid_for`], unchanged
+/// - Any error [`canonicalize`](crate::canonicalize) returns for the joined path, e.g.
+///   [`io::ErrorKind::NotFound`] with a [`ProcessGone`](crate::ProcessGone) payload if
+///   the PID has no process
+pub fn canonicalize_in_container(
+    resolver: &impl ContainerResolver,
+    container_id: &str,
+    subpath: impl AsRef<Path>,
+) -> io::Result<PathBuf> {
+    let pid = resolver.pid_for(container_id)?;
+    let mut path = proc_root().join(pid.to_string()).join("root");
+    // Not `join`: an absolute subpath would replace the boundary instead of extending it.
+    path.extend(
+        subpath
+            .as_ref()
+            .components()
+            .filter(|component| !matches!(component, Component::RootDir)),
+    );
+    crate::canonicalize(path)
+}
@@ -0,0 +1,97 @@
+//! Errors that name the input they failed on, for batch reporting.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Canonicalizing a particular input failed.
+///
+/// Carried as the payload of an [`io::Error`] of the same kind as the underlying
+/// failure by [`canonicalize_with_context`]; recover it with [`io::Error::get_ref`] and
+/// `downcast_ref::<CanonicalizeFailed>()`. Its [`Display`](fmt::Display) names the
+/// input as given, before any resolution, so a collected error can be logged on its own:
+/// `failed to canonicalize "/proc/1234/root/x": No such file or directory (os error 2)`.
+/// The underlying error, with any payload of its own such as
+/// [`ProcessGone`](crate::ProcessGone), is reached with [`original`](Self::original)
+/// or [`std::error::Error::source`].
+#[derive(Debug)]
+pub struct CanonicalizeFailed {
+    path: PathBuf,
+    original: io::Error,
+}
+
+impl CanonicalizeFailed {
+    /// The path that was passed in.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The error resolution failed with.
+    pub fn original(&self) -> &io::Error {
+        &self.original
+    }
+
+    /// The error resolution failed with, by value.
+    pub fn into_original(self) -> io::Error {
+        self.original
+    }
+}
+
+impl fmt::Display for CanonicalizeFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to canonicalize {:?}: {}",
+            self.path, self.original
+        )
+    }
+}
+
+impl std::error::Error for CanonicalizeFailed {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.original)
+    }
+}
+
+/// [`canonicalize`](crate::canonicalize), with errors that name the input.
+///
+/// For batches: an error collected away from its input still says which path failed.
+/// The error kind is unchanged, so matching on it works as with
+/// [`canonicalize`](crate::canonicalize).
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() {
+/// use proc_canonicalize::{canonicalize_with_context, CanonicalizeFailed};
+///
+/// let err = canonicalize_with_context("/proc/self/root/no/such/file").unwrap_err();
+/// assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+/// assert!(err
+///     .to_string()
+///     .starts_with(r#"failed to canonicalize "/proc/self/root/no/such/file": "#));
+///
+/// let failed = err.get_ref().and_then(|e| e.downcast_ref::<CanonicalizeFailed>()).unwrap();
+/// assert_eq!(failed.path(), std::path::Path::new("/proc/self/root/no/such/file"));
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Every error [`canonicalize`](crate::canonicalize) returns, of the same kind, with a
+/// [`CanonicalizeFailed`] payload wrapping it.
+pub fn canonicalize_with_context(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    crate::canonicalize(path).map_err(|original| {
+        io::Error::new(
+            original.kind(),
+            CanonicalizeFailed {
+                path: path.to_path_buf(),
+                original,
+            },
+        )
+    })
+}
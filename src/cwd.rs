@@ -0,0 +1,50 @@
+//! Reading the current directory, with failures attributed to it.
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// The current directory could not be read to resolve a relative path.
+///
+/// Carried as the payload of an [`io::Error`] of the same kind as the underlying
+/// failure (usually [`io::ErrorKind::NotFound`], when the directory was deleted out from
+/// under a long-running process); recover it with [`io::Error::get_ref`] and
+/// `downcast_ref::<CwdUnavailable>()`. Without it, a deleted current directory and a
+/// missing input path read the same. The underlying error is reached with
+/// [`original`](Self::original) or [`std::error::Error::source`].
+///
+/// To resolve relative paths without the current directory, set an explicit base with
+/// [`CanonicalizeOptions::base_dir`](crate::CanonicalizeOptions::base_dir).
+#[derive(Debug)]
+pub struct CwdUnavailable {
+    original: io::Error,
+}
+
+impl CwdUnavailable {
+    /// The error reading the current directory failed with.
+    pub fn original(&self) -> &io::Error {
+        &self.original
+    }
+}
+
+impl fmt::Display for CwdUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot read the current directory to resolve a relative path: {}",
+            self.original
+        )
+    }
+}
+
+impl std::error::Error for CwdUnavailable {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.original)
+    }
+}
+
+/// [`std::env::current_dir`], failing with a [`CwdUnavailable`] payload.
+pub(crate) fn current_dir() -> io::Result<PathBuf> {
+    std::env::current_dir()
+        .map_err(|original| io::Error::new(original.kind(), CwdUnavailable { original }))
+}
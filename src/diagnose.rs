@@ -0,0 +1,106 @@
+//! Per-component diagnosis of a path, reporting every problem rather than the first.
+
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::lexical::namespace_prefix_len;
+
+/// What one component of a path turned out to be, as reported by [`diagnose`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ComponentStatus {
+    /// The component exists and is not a symlink.
+    Exists,
+    /// The component is a symlink whose target exists; the raw target as stored.
+    Symlink(PathBuf),
+    /// The component is a symlink whose target does not exist; the raw target as stored.
+    BrokenSymlink(PathBuf),
+    /// The component completes a namespace boundary (e.g. `/proc/1234/root`), which
+    /// resolution preserves rather than follows.
+    MagicBoundary,
+    /// The component does not exist.
+    Missing,
+    /// The component, or a directory on the way to it, could not be searched.
+    PermissionDenied,
+    /// Any other failure, e.g. a file used as a directory.
+    Error(io::ErrorKind),
+}
+
+/// Inspect every component of `path` and report each one's status.
+///
+/// For tools that explain *why* a path is unusable. Unlike [`canonicalize`](crate::canonicalize),
+/// which stops at the first error, this keeps going: each entry pairs a prefix of `path`
+/// (`/proc`, `/proc/1234`, `/proc/1234/root`, ...) with what a lookup of that prefix
+/// found. Once one component is missing, those after it usually are too.
+///
+/// Prefixes are inspected as written, with `symlink_metadata`, so intermediate symlinks
+/// are followed by the kernel and the component itself is reported, not its target. The
+/// root and `.` are not reported; `..` is, as the directory it names.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() {
+/// use std::path::PathBuf;
+/// use proc_canonicalize::{diagnose, ComponentStatus};
+///
+/// let report = diagnose("/proc/self/root/no-such-dir/file");
+///
+/// assert_eq!(report[0], (PathBuf::from("/proc"), ComponentStatus::Exists));
+/// // report[1] is /proc/self, itself a symlink to this process's PID
+/// assert_eq!(report[2], (PathBuf::from("/proc/self/root"), ComponentStatus::MagicBoundary));
+/// assert_eq!(report[3].1, ComponentStatus::Missing);
+/// assert_eq!(report[4].1, ComponentStatus::Missing);
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+pub fn diagnose(path: impl AsRef<Path>) -> Vec<(PathBuf, ComponentStatus)> {
+    let path = path.as_ref();
+    let boundary_len = if cfg!(target_os = "linux") {
+        namespace_prefix_len(path)
+    } else {
+        None
+    };
+
+    let mut report = Vec::new();
+    let mut prefix = PathBuf::with_capacity(path.as_os_str().len());
+    for (index, component) in path.components().enumerate() {
+        prefix.push(component);
+        let status = match component {
+            Component::Prefix(_) | Component::RootDir | Component::CurDir => continue,
+            _ if boundary_len == Some(index + 1) => ComponentStatus::MagicBoundary,
+            _ => status_of(&prefix),
+        };
+        report.push((prefix.clone(), status));
+    }
+    report
+}
+
+fn status_of(path: &Path) -> ComponentStatus {
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            let target = match std::fs::read_link(path) {
+                Ok(target) => target,
+                Err(err) => return error_status(&err),
+            };
+            match std::fs::metadata(path) {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    ComponentStatus::BrokenSymlink(target)
+                }
+                _ => ComponentStatus::Symlink(target),
+            }
+        }
+        Ok(_) => ComponentStatus::Exists,
+        Err(err) => error_status(&err),
+    }
+}
+
+fn error_status(err: &io::Error) -> ComponentStatus {
+    match err.kind() {
+        io::ErrorKind::NotFound => ComponentStatus::Missing,
+        io::ErrorKind::PermissionDenied => ComponentStatus::PermissionDenied,
+        kind => ComponentStatus::Error(kind),
+    }
+}
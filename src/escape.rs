@@ -0,0 +1,72 @@
+//! What to do when a path resolves outside the boundary it entered through.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// How [`CanonicalizeOptions::escape_behavior`] handles a path that leaves its boundary.
+///
+/// A path escapes when it enters through `/proc/PID/root` or `/proc/PID/cwd` but
+/// resolves (via `..` or a symlink) to somewhere outside the directory the boundary
+/// points at, e.g. `/proc/self/cwd/..`.
+///
+/// [`CanonicalizeOptions::escape_behavior`]: crate::CanonicalizeOptions::escape_behavior
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EscapeBehavior {
+    /// Return the host path the escape actually resolves to. This is the true resolution
+    /// and matches [`canonicalize`](crate::canonicalize).
+    #[default]
+    ReturnHost,
+    /// Keep the boundary prefix and resolve the remainder as if the boundary were the
+    /// filesystem root: `..` stops at the boundary and absolute symlink targets restart
+    /// from it. This is what a process chrooted at the boundary would see, not where the
+    /// path leads from the caller's view. Also applied when the host view of the path
+    /// fails to resolve, since the clamped view may still succeed.
+    ClampToRoot,
+    /// Fail with an [`io::ErrorKind::Other`](std::io::ErrorKind::Other) error carrying a
+    /// [`BoundaryEscaped`] payload.
+    Error,
+}
+
+/// A path left the namespace boundary it entered through.
+///
+/// Returned as the payload of an [`io::Error`](std::io::Error) under
+/// [`EscapeBehavior::Error`]; recover it with `get_ref()` and
+/// `downcast_ref::<BoundaryEscaped>()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundaryEscaped {
+    boundary: PathBuf,
+    host_path: PathBuf,
+}
+
+impl BoundaryEscaped {
+    #[cfg(target_os = "linux")]
+    pub(crate) fn new(boundary: PathBuf, host_path: PathBuf) -> Self {
+        BoundaryEscaped {
+            boundary,
+            host_path,
+        }
+    }
+
+    /// The boundary the path entered through, e.g. `/proc/1234/root`.
+    pub fn boundary(&self) -> &Path {
+        &self.boundary
+    }
+
+    /// The host path the escape resolves to.
+    pub fn host_path(&self) -> &Path {
+        &self.host_path
+    }
+}
+
+impl fmt::Display for BoundaryEscaped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "path escapes namespace boundary {} to {}",
+            self.boundary.display(),
+            self.host_path.display()
+        )
+    }
+}
+
+impl std::error::Error for BoundaryEscaped {}
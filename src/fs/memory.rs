@@ -0,0 +1,437 @@
+//! [`MemoryFs`]: an in-memory tree for testing resolution without touching disk.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Component, Path, PathBuf};
+
+use super::{FileId, FileKind, FsOps};
+use crate::resolve::{Resolver, MAX_SYMLINK_FOLLOWS};
+use crate::CanonicalizeOptions;
+
+/// `ENOTDIR` on Linux: not a directory.
+const ENOTDIR: i32 = 20;
+/// `EINVAL` on Linux: what `readlink` reports for a non-symlink.
+const EINVAL: i32 = 22;
+/// `ELOOP` on Linux: too many levels of symbolic links.
+const ELOOP: i32 = 40;
+
+/// One entry in the tree.
+#[derive(Debug, Clone)]
+enum Node {
+    Dir,
+    File,
+    Symlink(PathBuf),
+    /// A `/proc/PID/ns/TYPE` link: `readlink` gives a label such as `net:[4026531840]`,
+    /// `stat` reports a file, and `realpath` fails on the label.
+    NamespaceLink(PathBuf),
+}
+
+/// A component still to be walked.
+enum Pending {
+    Parent,
+    Name(OsString),
+}
+
+/// An in-memory filesystem tree that [`canonicalize`](MemoryFs::canonicalize) resolves
+/// against instead of the real filesystem.
+///
+/// Build a topology of directories, files and symlinks, then resolve paths through it
+/// with the same namespace-aware logic as [`canonicalize`](crate::canonicalize). This
+/// lets a crate wrapping this one test crafted symlink layouts deterministically, with
+/// no temporary directories and no dependence on the host's `/proc`.
+///
+/// A process's `/proc/PID/root` and `/proc/PID/cwd` are modeled as symlinks to the
+/// directory they enter, which is what the kernel's `readlink` reports for them.
+/// Relative paths are resolved from `/`. Every missing parent of an added entry is
+/// created as a directory.
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::MemoryFs;
+///
+/// let fs = MemoryFs::new()
+///     .dir("/containers/web/etc")
+///     .symlink("/containers/web/etc/escape", "/host-secrets")
+///     .dir("/host-secrets")
+///     .symlink("/proc/1234/root", "/containers/web")
+///     .symlink("/tmp/web", "/proc/1234/root");
+///
+/// assert_eq!(fs.canonicalize("/tmp/web/etc")?, Path::new("/proc/1234/root/etc"));
+/// assert_eq!(fs.canonicalize("/proc/1234/root/etc/escape")?, Path::new("/host-secrets"));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone)]
+pub struct MemoryFs {
+    nodes: BTreeMap<PathBuf, Node>,
+}
+
+impl Default for MemoryFs {
+    fn default() -> Self {
+        let mut nodes = BTreeMap::new();
+        nodes.insert(PathBuf::from("/"), Node::Dir);
+        MemoryFs { nodes }
+    }
+}
+
+impl MemoryFs {
+    /// An empty tree: just `/`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a directory.
+    pub fn dir(self, path: impl AsRef<Path>) -> Self {
+        self.insert(path.as_ref(), Node::Dir)
+    }
+
+    /// Add a regular file.
+    pub fn file(self, path: impl AsRef<Path>) -> Self {
+        self.insert(path.as_ref(), Node::File)
+    }
+
+    /// Add a symlink at `link` whose stored target is `target`, absolute or relative.
+    pub fn symlink(self, link: impl AsRef<Path>, target: impl AsRef<Path>) -> Self {
+        self.insert(link.as_ref(), Node::Symlink(target.as_ref().to_path_buf()))
+    }
+
+    /// Add a namespace link such as `/proc/1234/ns/net` that `readlink`s to `label`
+    /// (e.g. `net:[4026531840]`).
+    pub fn namespace_link(self, link: impl AsRef<Path>, label: impl AsRef<Path>) -> Self {
+        self.insert(
+            link.as_ref(),
+            Node::NamespaceLink(label.as_ref().to_path_buf()),
+        )
+    }
+
+    /// Namespace-aware canonicalization of `path` against this tree.
+    ///
+    /// Behaves like [`canonicalize`](crate::canonicalize) would if this tree were the
+    /// real filesystem.
+    ///
+    /// # Errors
+    ///
+    /// The errors the kernel would report: [`io::ErrorKind::NotFound`] for a missing
+    /// component, `ENOTDIR` for a non-directory in the middle of a path, `ELOOP` for
+    /// more than 40 symlink hops.
+    pub fn canonicalize(&self, path: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let options = CanonicalizeOptions::default();
+        Resolver::new(&options)
+            .with_fs(self)
+            .canonicalize(&Path::new("/").join(path))
+    }
+
+    fn insert(mut self, path: &Path, node: Node) -> Self {
+        let path = Path::new("/").join(path);
+        for ancestor in path.ancestors().skip(1) {
+            self.nodes
+                .entry(ancestor.to_path_buf())
+                .or_insert(Node::Dir);
+        }
+        self.nodes.insert(path, node);
+        self
+    }
+
+    /// Walk `path` from `/`, following symlinks the way the kernel does.
+    ///
+    /// A trailing symlink is followed only if `follow_last` (or the path ends in `/`).
+    /// Namespace links are followed to their label only when `realpath` is set, matching
+    /// `realpath(3)`; otherwise they are opaque files, matching `stat(2)`.
+    fn walk(&self, path: &Path, follow_last: bool, realpath: bool) -> io::Result<(PathBuf, &Node)> {
+        if path.as_os_str().is_empty() {
+            return Err(io::ErrorKind::NotFound.into());
+        }
+        let trailing_slash = path.as_os_str().as_bytes().ends_with(b"/");
+        let follow_last = follow_last || trailing_slash;
+
+        let mut resolved = PathBuf::from("/");
+        let mut node = self.node(&resolved)?;
+        let mut pending = Vec::new();
+        push_reversed(&mut pending, path);
+        let mut follows = 0;
+
+        while let Some(next) = pending.pop() {
+            let name = match next {
+                Pending::Parent => {
+                    resolved.pop();
+                    node = self.node(&resolved)?;
+                    continue;
+                }
+                Pending::Name(name) => name,
+            };
+            if !matches!(node, Node::Dir) {
+                return Err(io::Error::from_raw_os_error(ENOTDIR));
+            }
+            resolved.push(name);
+            let child = self.node(&resolved)?;
+
+            let follow = !pending.is_empty() || follow_last;
+            let target = match child {
+                Node::Symlink(target) if follow => target,
+                Node::NamespaceLink(label) if follow && realpath => label,
+                _ => {
+                    node = child;
+                    continue;
+                }
+            };
+            if target.as_os_str().is_empty() {
+                return Err(io::ErrorKind::NotFound.into());
+            }
+            follows += 1;
+            if follows > MAX_SYMLINK_FOLLOWS {
+                return Err(io::Error::from_raw_os_error(ELOOP));
+            }
+            resolved.pop();
+            if target.has_root() {
+                resolved = PathBuf::from("/");
+            }
+            node = self.node(&resolved)?;
+            push_reversed(&mut pending, target);
+        }
+
+        if trailing_slash && !matches!(node, Node::Dir) {
+            return Err(io::Error::from_raw_os_error(ENOTDIR));
+        }
+        Ok((resolved, node))
+    }
+
+    fn node(&self, path: &Path) -> io::Result<&Node> {
+        self.nodes
+            .get(path)
+            .ok_or_else(|| io::ErrorKind::NotFound.into())
+    }
+}
+
+impl FsOps for MemoryFs {
+    fn metadata(&self, path: &Path) -> io::Result<FileKind> {
+        match self.walk(path, true, false)? {
+            // stat(2) on a namespace link reports the nsfs inode behind it.
+            (_, Node::NamespaceLink(_)) => Ok(FileKind::Other),
+            (_, node) => Ok(kind_of(node)),
+        }
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FileKind> {
+        self.walk(path, false, false).map(|(_, node)| kind_of(node))
+    }
+
+    fn file_id(&self, path: &Path) -> io::Result<FileId> {
+        // The tree never changes once built, so an entry's position is a stable inode.
+        let (resolved, _) = self.walk(path, true, false)?;
+        let ino = self
+            .nodes
+            .keys()
+            .position(|key| *key == resolved)
+            .ok_or(io::ErrorKind::NotFound)?;
+        Ok(FileId {
+            dev: 0,
+            ino: ino as u64,
+        })
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        match self.walk(path, false, false)? {
+            (_, Node::Symlink(target)) | (_, Node::NamespaceLink(target)) => Ok(target.clone()),
+            _ => Err(io::Error::from_raw_os_error(EINVAL)),
+        }
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.walk(path, true, true).map(|(resolved, _)| resolved)
+    }
+}
+
+fn kind_of(node: &Node) -> FileKind {
+    match node {
+        Node::Dir => FileKind::Dir,
+        Node::Symlink(_) | Node::NamespaceLink(_) => FileKind::Symlink,
+        Node::File => FileKind::Other,
+    }
+}
+
+fn push_reversed(pending: &mut Vec<Pending>, path: &Path) {
+    for component in path.components().rev() {
+        match component {
+            Component::Normal(name) => pending.push(Pending::Name(name.to_os_string())),
+            Component::ParentDir => pending.push(Pending::Parent),
+            Component::RootDir | Component::CurDir | Component::Prefix(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_error<T: std::fmt::Debug>(result: io::Result<T>) -> Option<i32> {
+        result.unwrap_err().raw_os_error()
+    }
+
+    // ==========================================================================
+    // TREE WALK (kernel lookup semantics)
+    // ==========================================================================
+
+    #[test]
+    fn parents_are_created_as_directories() {
+        let fs = MemoryFs::new().file("/a/b/c");
+
+        assert_eq!(fs.metadata(Path::new("/a/b")).unwrap(), FileKind::Dir);
+        assert_eq!(fs.metadata(Path::new("/a/b/c")).unwrap(), FileKind::Other);
+    }
+
+    #[test]
+    fn relative_symlink_resolves_from_its_directory() {
+        let fs = MemoryFs::new()
+            .dir("/a/real")
+            .symlink("/a/b/link", "../real");
+
+        assert_eq!(
+            FsOps::canonicalize(&fs, Path::new("/a/b/link")).unwrap(),
+            Path::new("/a/real")
+        );
+    }
+
+    #[test]
+    fn symlink_metadata_does_not_follow_last_component() {
+        let fs = MemoryFs::new().dir("/target").symlink("/link", "/target");
+
+        assert_eq!(
+            fs.symlink_metadata(Path::new("/link")).unwrap(),
+            FileKind::Symlink
+        );
+        assert_eq!(fs.metadata(Path::new("/link")).unwrap(), FileKind::Dir);
+    }
+
+    #[test]
+    fn file_in_the_middle_is_not_a_directory() {
+        let fs = MemoryFs::new().file("/etc/hostname");
+
+        assert_eq!(
+            raw_error(fs.metadata(Path::new("/etc/hostname/x"))),
+            Some(ENOTDIR)
+        );
+        assert_eq!(
+            raw_error(fs.metadata(Path::new("/etc/hostname/"))),
+            Some(ENOTDIR)
+        );
+    }
+
+    #[test]
+    fn symlink_cycle_reports_eloop() {
+        let fs = MemoryFs::new().symlink("/a", "/b").symlink("/b", "/a");
+
+        assert_eq!(raw_error(fs.metadata(Path::new("/a"))), Some(ELOOP));
+    }
+
+    #[test]
+    fn missing_component_and_empty_path_are_not_found() {
+        let fs = MemoryFs::new().dir("/etc");
+
+        let missing = fs.metadata(Path::new("/etc/missing")).unwrap_err();
+        assert_eq!(missing.kind(), io::ErrorKind::NotFound);
+        let empty = FsOps::canonicalize(&fs, Path::new("")).unwrap_err();
+        assert_eq!(empty.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn dotdot_at_root_stays_at_root() {
+        let fs = MemoryFs::new().dir("/etc");
+
+        assert_eq!(
+            FsOps::canonicalize(&fs, Path::new("/../../etc")).unwrap(),
+            Path::new("/etc")
+        );
+    }
+
+    #[test]
+    fn read_link_of_non_symlink_is_einval() {
+        let fs = MemoryFs::new().dir("/etc");
+
+        assert_eq!(raw_error(fs.read_link(Path::new("/etc"))), Some(EINVAL));
+    }
+
+    #[test]
+    fn namespace_link_stats_as_file_but_fails_realpath() {
+        let fs = MemoryFs::new().namespace_link("/proc/1/ns/net", "net:[4026531840]");
+        let link = Path::new("/proc/1/ns/net");
+
+        assert_eq!(fs.metadata(link).unwrap(), FileKind::Other);
+        assert_eq!(fs.symlink_metadata(link).unwrap(), FileKind::Symlink);
+        assert_eq!(fs.read_link(link).unwrap(), Path::new("net:[4026531840]"));
+        let err = FsOps::canonicalize(&fs, link).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    // ==========================================================================
+    // NAMESPACE-AWARE RESOLUTION AGAINST THE TREE
+    // ==========================================================================
+
+    fn container_fs() -> MemoryFs {
+        MemoryFs::new()
+            .dir("/containers/web/etc/nginx")
+            .dir("/host/secrets")
+            .symlink("/containers/web/etc/escape", "/host/secrets")
+            .symlink("/containers/web/etc/current", "nginx")
+            .symlink("/proc/1234/root", "/containers/web")
+            .symlink("/proc/1234/cwd", "/containers/web/etc")
+    }
+
+    #[test]
+    fn boundary_subpath_keeps_prefix() {
+        let fs = container_fs();
+
+        assert_eq!(
+            fs.canonicalize("/proc/1234/root/etc/current").unwrap(),
+            Path::new("/proc/1234/root/etc/nginx")
+        );
+    }
+
+    #[test]
+    fn escape_out_of_container_returns_host_path() {
+        let fs = container_fs();
+
+        assert_eq!(
+            fs.canonicalize("/proc/1234/root/etc/escape").unwrap(),
+            Path::new("/host/secrets")
+        );
+        assert_eq!(
+            fs.canonicalize("/proc/1234/cwd/..").unwrap(),
+            Path::new("/containers/web")
+        );
+    }
+
+    #[test]
+    fn indirect_link_to_boundary_is_preserved() {
+        let fs = container_fs().symlink("/srv/app", "/proc/1234/cwd");
+
+        assert_eq!(
+            fs.canonicalize("/srv/app/nginx").unwrap(),
+            Path::new("/proc/1234/cwd/nginx")
+        );
+    }
+
+    #[test]
+    fn namespace_link_is_preserved() {
+        let fs = MemoryFs::new().namespace_link("/proc/1234/ns/net", "net:[4026531840]");
+
+        assert_eq!(
+            fs.canonicalize("/proc/1234/ns/net").unwrap(),
+            Path::new("/proc/1234/ns/net")
+        );
+    }
+
+    #[test]
+    fn missing_process_is_not_found() {
+        let fs = container_fs();
+
+        let err = fs.canonicalize("/proc/99/root/etc").unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
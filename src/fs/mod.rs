@@ -0,0 +1,185 @@
+//! The filesystem operations the resolver performs, behind a swappable trait.
+
+use std::fmt;
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+#[cfg(any(test, feature = "testing"))]
+mod memory;
+
+#[cfg(feature = "testing")]
+pub use memory::MemoryFs;
+
+/// What a path refers to, as far as resolution cares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FileKind {
+    Dir,
+    Symlink,
+    /// Regular files and everything else that is neither a directory nor a symlink.
+    Other,
+}
+
+/// Which file a path refers to: its device and inode numbers, as from `stat(2)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FileId {
+    dev: u64,
+    ino: u64,
+}
+
+/// Every filesystem access made while resolving a path.
+///
+/// [`RealFs`] forwards to `std::fs`; [`MemoryFs`] models a tree in memory so resolution
+/// can be tested against crafted symlink topologies.
+pub(crate) trait FsOps {
+    /// Like [`std::fs::metadata`]: follows symlinks, including a trailing one.
+    fn metadata(&self, path: &Path) -> io::Result<FileKind>;
+
+    /// Like [`std::fs::symlink_metadata`]: a trailing symlink is reported as such.
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FileKind>;
+
+    /// The [`FileId`] of what `path` refers to, following symlinks like [`metadata`](Self::metadata).
+    fn file_id(&self, path: &Path) -> io::Result<FileId>;
+
+    /// Like [`std::fs::read_link`].
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// Like [`std::fs::canonicalize`].
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+}
+
+/// The real filesystem, through `std::fs`.
+pub(crate) struct RealFs;
+
+impl FsOps for RealFs {
+    fn metadata(&self, path: &Path) -> io::Result<FileKind> {
+        std::fs::metadata(path).map(|metadata| kind_of(&metadata.file_type()))
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FileKind> {
+        std::fs::symlink_metadata(path).map(|metadata| kind_of(&metadata.file_type()))
+    }
+
+    fn file_id(&self, path: &Path) -> io::Result<FileId> {
+        std::fs::metadata(path).map(|metadata| FileId {
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+        })
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::read_link(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::canonicalize(path)
+    }
+}
+
+/// The real filesystem, except that symlinks are read through a caller's function.
+///
+/// Set with [`CanonicalizeOptions::read_link_with`](crate::CanonicalizeOptions::read_link_with).
+#[derive(Clone)]
+pub(crate) struct ReadLinkHook(Arc<ReadLinkFn>);
+
+type ReadLinkFn = dyn Fn(&Path) -> io::Result<PathBuf> + Send + Sync;
+
+impl ReadLinkHook {
+    pub(crate) fn new(
+        read_link: impl Fn(&Path) -> io::Result<PathBuf> + Send + Sync + 'static,
+    ) -> Self {
+        ReadLinkHook(Arc::new(read_link))
+    }
+}
+
+impl fmt::Debug for ReadLinkHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ReadLinkHook(..)")
+    }
+}
+
+impl FsOps for ReadLinkHook {
+    fn metadata(&self, path: &Path) -> io::Result<FileKind> {
+        RealFs.metadata(path)
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FileKind> {
+        RealFs.symlink_metadata(path)
+    }
+
+    fn file_id(&self, path: &Path) -> io::Result<FileId> {
+        RealFs.file_id(path)
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        (self.0)(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        RealFs.canonicalize(path)
+    }
+}
+
+/// Another [`FsOps`] that refuses to start any operation once `deadline` has passed.
+///
+/// Set with [`CanonicalizeOptions::deadline`](crate::CanonicalizeOptions::deadline).
+pub(crate) struct DeadlineFs<'fs> {
+    inner: &'fs dyn FsOps,
+    deadline: Instant,
+}
+
+impl<'fs> DeadlineFs<'fs> {
+    pub(crate) fn new(inner: &'fs dyn FsOps, deadline: Instant) -> Self {
+        DeadlineFs { inner, deadline }
+    }
+
+    fn check(&self) -> io::Result<()> {
+        if Instant::now() >= self.deadline {
+            Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "path resolution exceeded its deadline",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl FsOps for DeadlineFs<'_> {
+    fn metadata(&self, path: &Path) -> io::Result<FileKind> {
+        self.check()?;
+        self.inner.metadata(path)
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FileKind> {
+        self.check()?;
+        self.inner.symlink_metadata(path)
+    }
+
+    fn file_id(&self, path: &Path) -> io::Result<FileId> {
+        self.check()?;
+        self.inner.file_id(path)
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        self.check()?;
+        self.inner.read_link(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.check()?;
+        self.inner.canonicalize(path)
+    }
+}
+
+fn kind_of(file_type: &std::fs::FileType) -> FileKind {
+    if file_type.is_dir() {
+        FileKind::Dir
+    } else if file_type.is_symlink() {
+        FileKind::Symlink
+    } else {
+        FileKind::Other
+    }
+}
@@ -0,0 +1,67 @@
+//! The inverse of resolution: naming a host path through a process's boundary.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::lexical::join_components;
+use crate::proc_root::proc_root;
+use crate::process::explain_missing_process;
+use crate::NamespaceKind;
+
+/// Name the host path `host` as seen through process `pid`'s root or cwd, if it is
+/// inside it.
+///
+/// Both `host` and `/proc/PID/<kind>` are resolved on the host, and when the first lies
+/// within the second the result is the boundary followed by the rest:
+/// `/etc/hostname` in a container whose root is `/var/lib/containers/abc/rootfs` is
+/// not inside it, while `/var/lib/containers/abc/rootfs/etc/hostname` becomes
+/// `/proc/PID/root/etc/hostname`. Returns `None` when `host` is outside. Containment
+/// compares whole components, so `.../rootfs2` is not inside `.../rootfs`.
+///
+/// The result canonicalizes to itself with [`canonicalize`](crate::canonicalize). Like
+/// any `/proc/PID` path, it is only meaningful while that process lives.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::{host_to_namespace, NamespaceKind};
+///
+/// // This process's root is the host's, so every host path is inside it.
+/// let pid = std::process::id();
+/// let named = host_to_namespace(Path::new("/etc"), pid, NamespaceKind::Root)?;
+/// assert_eq!(named.unwrap(), Path::new(&format!("/proc/{pid}/root/etc")));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// - [`io::ErrorKind::InvalidInput`] for [`NamespaceKind::Ns`], which is not a directory
+/// - [`io::ErrorKind::NotFound`] if `host` does not exist, or if the process does not,
+///   then with a [`ProcessGone`](crate::ProcessGone) payload
+/// - [`io::ErrorKind::PermissionDenied`] if either cannot be resolved
+pub fn host_to_namespace(
+    host: &Path,
+    pid: u32,
+    kind: NamespaceKind,
+) -> io::Result<Option<PathBuf>> {
+    if kind == NamespaceKind::Ns {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "a namespace link has no paths inside it",
+        ));
+    }
+    let boundary = proc_root().join(pid.to_string()).join(kind.as_str());
+    let root =
+        std::fs::canonicalize(&boundary).map_err(|err| explain_missing_process(&boundary, err))?;
+    let resolved = std::fs::canonicalize(host)?;
+    Ok(resolved
+        .strip_prefix(&root)
+        .ok()
+        .map(|inside| join_components(&boundary, inside)))
+}
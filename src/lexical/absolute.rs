@@ -0,0 +1,110 @@
+//! Lexical absolutization with `..` folded, clamped at namespace boundaries.
+
+use std::borrow::Cow;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use super::namespace_prefix_len;
+
+/// Make `path` absolute and fold `.` and `..`, without touching the filesystem.
+///
+/// Relative paths are joined onto the current directory. Then, purely lexically, `.`
+/// components and repeated or trailing separators are dropped and each `..` removes the
+/// component before it. Symlinks are not consulted, so `link/..` folds to the directory
+/// containing `link`, which the kernel may disagree with: use
+/// [`canonicalize`](crate::canonicalize) whenever the path exists.
+///
+/// A `..` directly after a namespace boundary is clamped there, as the kernel clamps
+/// `..` at a process's own root: `/proc/1234/root/..` folds to `/proc/1234/root`, never
+/// to `/proc/1234`.
+///
+/// # Compared with `std::path::absolute`
+///
+/// [`std::path::absolute`] (Rust 1.79) also joins onto the current directory and drops
+/// `.`, but on Unix it keeps `..` verbatim and keeps a trailing separator, because
+/// folding `..` is wrong in the presence of symlinks. This function folds them anyway,
+/// trading that correctness for a result with no `..` left in it:
+///
+/// | Input                    | `std::path::absolute`    | `canonicalize_lexical` |
+/// | ------------------------ | ------------------------ | ---------------------- |
+/// | `/a/./b/`                | `/a/b/`                  | `/a/b`                 |
+/// | `/a/b/../c`              | `/a/b/../c`              | `/a/c`                 |
+/// | `/..`                    | `/..`                    | `/`                    |
+/// | `/proc/self/root/../etc` | `/proc/self/root/../etc` | `/proc/self/root/etc`  |
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::canonicalize_lexical;
+///
+/// assert_eq!(canonicalize_lexical("/srv/./app/../data/")?, Path::new("/srv/data"));
+/// assert_eq!(canonicalize_lexical("/proc/1234/root/../etc")?, Path::new("/proc/1234/root/etc"));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Fails with [`io::ErrorKind::InvalidInput`] for an empty path, as
+/// `std::path::absolute` does, and with the error [`std::env::current_dir`] returns if a
+/// relative path cannot be anchored.
+pub fn canonicalize_lexical(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    if path.as_os_str().is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "cannot make an empty path absolute",
+        ));
+    }
+    let absolute = if path.is_absolute() {
+        Cow::Borrowed(path)
+    } else {
+        Cow::Owned(std::env::current_dir()?.join(path))
+    };
+
+    // Owned output: the folded path is returned to the caller.
+    let mut folded = PathBuf::with_capacity(absolute.as_os_str().len());
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if namespace_prefix_len(&folded) != Some(folded.components().count()) {
+                    folded.pop();
+                }
+            }
+            component => folded.push(component),
+        }
+    }
+    Ok(folded)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dotdot_after_boundary_is_clamped() {
+        for (input, expected) in [
+            ("/proc/self/root/..", "/proc/self/root"),
+            ("/proc/1/cwd/../../x", "/proc/1/cwd/x"),
+            ("/proc/1/task/2/root/a/../../..", "/proc/1/task/2/root"),
+            ("/proc/self/../self/root", "/proc/self/root"),
+        ] {
+            assert_eq!(
+                canonicalize_lexical(input).unwrap(),
+                Path::new(expected),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn empty_path_is_invalid_input() {
+        let err = canonicalize_lexical("").unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,177 @@
+//! Rendering a [`ParsedBoundary`] in a caller-chosen format instead of as a `/proc` path.
+
+use std::fmt;
+
+use super::ParsedBoundary;
+
+/// A way of writing out a [`ParsedBoundary`], for [`ParsedBoundary::display_with`].
+///
+/// Implemented by [`ColonFormat`] and by any
+/// `Fn(&ParsedBoundary, &mut fmt::Formatter<'_>) -> fmt::Result`, so a one-off format
+/// is a closure over the boundary's accessors.
+pub trait BoundaryFormat {
+    /// Write `boundary` to `f`.
+    fn fmt(&self, boundary: &ParsedBoundary, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl<F> BoundaryFormat for F
+where
+    F: Fn(&ParsedBoundary, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    fn fmt(&self, boundary: &ParsedBoundary, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self(boundary, f)
+    }
+}
+
+/// `LINK:PID:/REMAINDER`, e.g. `root:1234:/etc/passwd` for `/proc/1234/root/etc/passwd`.
+///
+/// A task boundary's thread follows the PID after a `/`: `cwd:1234/5678:/src`. The
+/// remainder is always written absolute, so a bare boundary ends in `:/`. Non-UTF-8
+/// bytes in the remainder are replaced as by [`Path::display`](std::path::Path::display).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColonFormat;
+
+impl BoundaryFormat for ColonFormat {
+    fn fmt(&self, boundary: &ParsedBoundary, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", boundary.link(), boundary.pid())?;
+        if let Some(tid) = boundary.tid() {
+            write!(f, "/{tid}")?;
+        }
+        write!(f, ":/{}", boundary.remainder().display())
+    }
+}
+
+/// The boundary as a `/proc` path with placeholders for its IDs, e.g.
+/// `/proc/<PID>/root/etc/passwd` for `/proc/1234/root/etc/passwd`.
+///
+/// For output that stays the same from one machine or run to the next, such as golden
+/// test files. Every selector becomes `<PID>`, `self` and `thread-self` included, and a
+/// task boundary's thread becomes `<TID>`: `/proc/<PID>/task/<TID>/cwd/src`. The proc
+/// root is always written `/proc`. A bare boundary has no trailing separator.
+/// Non-UTF-8 bytes in the remainder are replaced as by
+/// [`Path::display`](std::path::Path::display).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StableFormat;
+
+impl BoundaryFormat for StableFormat {
+    fn fmt(&self, boundary: &ParsedBoundary, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("/proc/<PID>")?;
+        if boundary.tid().is_some() {
+            f.write_str("/task/<TID>")?;
+        }
+        write!(f, "/{}", boundary.link())?;
+        if !boundary.remainder().as_os_str().is_empty() {
+            write!(f, "/{}", boundary.remainder().display())?;
+        }
+        Ok(())
+    }
+}
+
+/// A [`ParsedBoundary`] rendered by a [`BoundaryFormat`], from
+/// [`ParsedBoundary::display_with`].
+#[derive(Debug, Clone, Copy)]
+pub struct BoundaryDisplay<'a, F> {
+    boundary: &'a ParsedBoundary,
+    format: F,
+}
+
+impl<F: BoundaryFormat> fmt::Display for BoundaryDisplay<'_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.format.fmt(self.boundary, f)
+    }
+}
+
+impl ParsedBoundary {
+    /// Render the boundary and remainder with `format` rather than as a `/proc` path.
+    ///
+    /// ```rust
+    /// use std::fmt;
+    /// use proc_canonicalize::{parse_boundary, ColonFormat, ParsedBoundary};
+    ///
+    /// let boundary = parse_boundary("/proc/1234/root/etc/passwd").unwrap();
+    /// assert_eq!(boundary.display_with(ColonFormat).to_string(), "root:1234:/etc/passwd");
+    ///
+    /// let container = |b: &ParsedBoundary, f: &mut fmt::Formatter<'_>| {
+    ///     write!(f, "container:{}:/{}", b.pid(), b.remainder().display())
+    /// };
+    /// assert_eq!(
+    ///     boundary.display_with(container).to_string(),
+    ///     "container:1234:/etc/passwd"
+    /// );
+    /// ```
+    pub fn display_with<F: BoundaryFormat>(&self, format: F) -> BoundaryDisplay<'_, F> {
+        BoundaryDisplay {
+            boundary: self,
+            format,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexical::parse_boundary;
+
+    fn colon(path: &str) -> String {
+        parse_boundary(path)
+            .unwrap()
+            .display_with(ColonFormat)
+            .to_string()
+    }
+
+    #[test]
+    fn colon_format_covers_every_boundary_shape() {
+        let cases = [
+            ("/proc/1234/root/etc/passwd", "root:1234:/etc/passwd"),
+            ("/proc/self/cwd", "cwd:self:/"),
+            ("/proc/thread-self/root/a", "root:thread-self:/a"),
+            ("/proc/1234/task/5678/cwd/src", "cwd:1234/5678:/src"),
+            ("/proc/0042/ns/net", "ns/net:0042:/"),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(colon(path), expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn stable_format_replaces_every_id() {
+        let cases = [
+            ("/proc/1234/root/etc/passwd", "/proc/<PID>/root/etc/passwd"),
+            ("/proc/self/cwd", "/proc/<PID>/cwd"),
+            ("/proc/thread-self/root/a", "/proc/<PID>/root/a"),
+            (
+                "/proc/1234/task/5678/cwd/src",
+                "/proc/<PID>/task/<TID>/cwd/src",
+            ),
+            ("/proc/0042/ns/net", "/proc/<PID>/ns/net"),
+        ];
+        for (path, expected) in cases {
+            let stable = parse_boundary(path)
+                .unwrap()
+                .display_with(StableFormat)
+                .to_string();
+            assert_eq!(stable, expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn closure_format_sees_the_parsed_fields() {
+        let boundary = parse_boundary("/proc/1234/task/5678/root/etc").unwrap();
+        let fields = |b: &ParsedBoundary, f: &mut fmt::Formatter<'_>| {
+            let tid = b.tid().map_or("-", |tid| tid.as_str());
+            write!(
+                f,
+                "{} {} {} {}",
+                b.pid(),
+                tid,
+                b.link(),
+                b.remainder().display()
+            )
+        };
+
+        assert_eq!(
+            boundary.display_with(fields).to_string(),
+            "1234 5678 root etc"
+        );
+    }
+}
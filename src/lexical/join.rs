@@ -0,0 +1,113 @@
+//! Lexical joins that cannot climb out of a base directory.
+
+use std::path::{Component, Path, PathBuf};
+
+/// Join untrusted `user` input onto `boundary`, refusing anything that would leave it.
+///
+/// Purely lexical: `.` components are dropped and `..` pops the most recent component
+/// that `user` itself pushed. Returns `None` when `user`:
+/// - is absolute (a plain [`Path::join`] would silently discard `boundary`), or
+/// - contains a `..` that would climb above `boundary`.
+///
+/// The kernel clamps `..` at a process's own root, but a caller walking in through
+/// `/proc/PID/root` is not rooted there; a `..` at the boundary is treated as an escape
+/// attempt and rejected rather than clamped. `boundary` itself is used as given.
+///
+/// This builds a safe path *before* resolution. Symlinks inside the boundary can
+/// still lead elsewhere, so canonicalize the result with [`canonicalize`](crate::canonicalize)
+/// before trusting it.
+///
+/// ```rust
+/// use std::path::Path;
+/// use proc_canonicalize::checked_join;
+///
+/// let container_root = Path::new("/proc/1234/root");
+///
+/// let config = checked_join(container_root, "etc/./app/../nginx.conf").unwrap();
+/// assert_eq!(config, Path::new("/proc/1234/root/etc/nginx.conf"));
+///
+/// assert_eq!(checked_join(container_root, "../../etc/shadow"), None);
+/// assert_eq!(checked_join(container_root, "/etc/shadow"), None);
+/// ```
+pub fn checked_join(boundary: impl AsRef<Path>, user: impl AsRef<Path>) -> Option<PathBuf> {
+    let boundary = boundary.as_ref();
+    let user = user.as_ref();
+
+    // Owned output: the joined path is returned to the caller.
+    let mut joined =
+        PathBuf::with_capacity(boundary.as_os_str().len() + 1 + user.as_os_str().len());
+    joined.push(boundary);
+
+    let mut depth_below_boundary = 0usize;
+    for component in user.components() {
+        match component {
+            Component::Normal(name) => {
+                joined.push(name);
+                depth_below_boundary += 1;
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                depth_below_boundary = depth_below_boundary.checked_sub(1)?;
+                joined.pop();
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(joined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_input_is_appended_under_boundary() {
+        let joined = checked_join("/proc/1234/root", "var/log/app.log").unwrap();
+
+        assert_eq!(joined, Path::new("/proc/1234/root/var/log/app.log"));
+    }
+
+    #[test]
+    fn dotdot_within_input_stays_inside() {
+        let joined = checked_join("/proc/1234/root", "var/log/../lib/./dpkg").unwrap();
+
+        assert_eq!(joined, Path::new("/proc/1234/root/var/lib/dpkg"));
+    }
+
+    #[test]
+    fn dotdot_back_to_boundary_itself_is_allowed() {
+        let joined = checked_join("/proc/1234/cwd", "src/..").unwrap();
+
+        assert_eq!(joined, Path::new("/proc/1234/cwd"));
+    }
+
+    #[test]
+    fn dotdot_overflow_is_rejected() {
+        for escape in [
+            "..",
+            "../etc",
+            "a/../../etc",
+            "a/b/../../../..",
+            "./../root",
+        ] {
+            assert_eq!(
+                checked_join("/proc/1234/root", escape),
+                None,
+                "accepted {escape:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn absolute_input_is_rejected() {
+        assert_eq!(checked_join("/proc/1234/root", "/etc/passwd"), None);
+        assert_eq!(checked_join("/proc/1234/root", "/"), None);
+    }
+
+    #[test]
+    fn empty_input_returns_boundary() {
+        let joined = checked_join("/proc/self/root", "").unwrap();
+
+        assert_eq!(joined, Path::new("/proc/self/root"));
+    }
+}
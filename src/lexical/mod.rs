@@ -0,0 +1,547 @@
+//! Lexical `/proc` namespace-boundary detection.
+//!
+//! Pure path inspection — nothing in this module touches the filesystem. Matching is
+//! byte-exact: no case folding or Unicode normalization, so only the literal ASCII
+//! spellings of `/proc`, `self`, digits and link names are recognized. Consumers
+//! that need only these helpers can build the crate with `default-features = false`,
+//! which compiles this module and leaves out every function that resolves paths on
+//! disk:
+//!
+//! ```toml
+//! [dependencies]
+//! proc-canonicalize = { version = "0.1", default-features = false }
+//! ```
+//!
+//! Everything here is also re-exported at the crate root.
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::proc_root::proc_root;
+
+mod absolute;
+mod format;
+mod join;
+mod parsed;
+mod sensitive;
+
+pub use absolute::canonicalize_lexical;
+pub use format::{BoundaryDisplay, BoundaryFormat, ColonFormat, StableFormat};
+pub use join::checked_join;
+pub use parsed::{
+    parse_boundary, NamespaceKind, NumericPid, ParsePidError, ParsedBoundary, PidSelector,
+};
+pub use sensitive::{is_sensitive_proc_path, recognized_sensitive_suffixes};
+
+/// Per-process magic links recognized as namespace boundaries: `/proc/PID/SUFFIX`
+/// and `/proc/PID/task/TID/SUFFIX`.
+///
+/// Namespace links (`/proc/PID/ns/TYPE`) are recognized too; their types are in
+/// [`NS_TYPES`].
+pub const MAGIC_SUFFIXES: &[&str] = &["root", "cwd"];
+
+/// Namespace types recognized as `/proc/PID/ns/TYPE` boundaries.
+///
+/// Matches the kernel's set as of Linux 5.8. Useful for checking that an allow-list
+/// of your own covers (or is covered by) what this crate preserves.
+pub const NS_TYPES: &[&str] = &[
+    "cgroup",
+    "ipc",
+    "mnt",
+    "net",
+    "pid",
+    "pid_for_children",
+    "time",
+    "time_for_children",
+    "user",
+    "uts",
+];
+
+/// Count the leading components that form a `/proc` namespace-boundary prefix.
+///
+/// Returns `Some(4)` for `/proc/PID/{root,cwd}` shapes, `Some(5)` for
+/// `/proc/PID/ns/TYPE`, `Some(6)` for `/proc/PID/task/TID/{root,cwd}`, `Some(7)`
+/// for `/proc/PID/task/TID/ns/TYPE`, and `None` if the path does not begin with a
+/// valid namespace prefix. Counts grow with the depth of a relocated
+/// [`proc_root`](crate::proc_root::proc_root). Allocation-free — used on every ancestor-walk iteration
+/// in the indirect-symlink scanner, where building transient `PathBuf`s just to
+/// discard them would dominate allocator cost.
+pub(crate) fn namespace_prefix_len(path: &Path) -> Option<usize> {
+    let mut components = path.components();
+
+    let mut root_len = 0;
+    for expected in proc_root().components() {
+        if components.next()? != expected {
+            return None;
+        }
+        root_len += 1;
+    }
+
+    let pid = match components.next()? {
+        Component::Normal(s) => s,
+        _ => return None,
+    };
+    if !is_valid_pid_segment(pid) {
+        return None;
+    }
+
+    let next = match components.next()? {
+        Component::Normal(s) => s,
+        _ => return None,
+    };
+    if next != "task" {
+        return magic_suffix_len(next, &mut components).map(|len| root_len + 1 + len);
+    }
+
+    let tid = match components.next()? {
+        Component::Normal(s) => s,
+        _ => return None,
+    };
+    if !is_numeric_segment(tid) {
+        return None;
+    }
+
+    match components.next()? {
+        Component::Normal(s) => magic_suffix_len(s, &mut components).map(|len| root_len + 3 + len),
+        _ => None,
+    }
+}
+
+/// Count the components of the magic suffix starting at `first`: 1 for `root`/`cwd`,
+/// 2 for `ns/TYPE`. Consumes the `TYPE` component from `rest` when `first` is `ns`.
+fn magic_suffix_len(
+    first: &std::ffi::OsStr,
+    rest: &mut std::path::Components<'_>,
+) -> Option<usize> {
+    if MAGIC_SUFFIXES.iter().any(|suffix| first == *suffix) {
+        return Some(1);
+    }
+    if first != "ns" {
+        return None;
+    }
+    match rest.next()? {
+        Component::Normal(ns_type) if NS_TYPES.iter().any(|known| ns_type == *known) => Some(2),
+        _ => None,
+    }
+}
+
+fn is_valid_pid_segment(s: &std::ffi::OsStr) -> bool {
+    // to_str() returns a borrowed &str without allocating; to_string_lossy()
+    // would allocate a replacement String when the OsStr is not valid UTF-8.
+    match s.to_str() {
+        Some("self") | Some("thread-self") => true,
+        Some(s) => is_nonempty_ascii_digits(s),
+        None => false,
+    }
+}
+
+fn is_numeric_segment(s: &std::ffi::OsStr) -> bool {
+    match s.to_str() {
+        Some(s) => is_nonempty_ascii_digits(s),
+        None => false,
+    }
+}
+
+fn is_nonempty_ascii_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// The `/proc/PID` or `/proc/PID/task/TID` directory that `path` names a single entry
+/// of, e.g. `/proc/1234` for `/proc/1234/exe`, as the part of `path` before the entry.
+///
+/// Purely lexical: whether the entry exists, or is a link, is for the caller to check.
+#[cfg(all(feature = "fs", target_os = "linux"))]
+pub(crate) fn per_process_entry_dir(path: &Path) -> Option<&Path> {
+    let mut components = path.components();
+    for expected in proc_root().components() {
+        if components.next()? != expected {
+            return None;
+        }
+    }
+    // `Components` is fused, so reading past the end keeps returning `None`.
+    let mut segments = components.map(|component| match component {
+        Component::Normal(segment) => Some(segment),
+        _ => None,
+    });
+    let pid = segments.next()??;
+    let entry_or_task = segments.next()??;
+    let names_one_entry = match (segments.next(), segments.next()) {
+        (None, _) => true,
+        (Some(tid), Some(entry)) => {
+            entry_or_task == "task"
+                && tid.is_some_and(is_numeric_segment)
+                && entry.is_some()
+                && segments.next().is_none()
+        }
+        (Some(_), None) => false,
+    };
+    if names_one_entry && is_valid_pid_segment(pid) {
+        path.parent()
+    } else {
+        None
+    }
+}
+
+/// Find a `/proc/PID/root`, `/proc/PID/cwd`, or `/proc/PID/ns/TYPE` namespace boundary in the path.
+///
+/// Returns `Some((namespace_prefix, remainder))` if found, where:
+/// - `namespace_prefix` is the boundary path (e.g., `/proc/1234/root`)
+/// - `remainder` is the path after the boundary (e.g., `etc/passwd`)
+///
+/// Returns `None` if the path doesn't contain a namespace boundary. Only the leading
+/// components are examined, so `/proc/../proc/self/root` has none; for the typed
+/// view of the boundary, use [`parse_boundary`].
+///
+/// ```rust
+/// use std::path::Path;
+/// use proc_canonicalize::lexical::find_namespace_boundary;
+///
+/// let (prefix, rest) = find_namespace_boundary(Path::new("/proc/1234/root/etc/passwd")).unwrap();
+/// assert_eq!(prefix, Path::new("/proc/1234/root"));
+/// assert_eq!(rest, Path::new("etc/passwd"));
+/// assert!(find_namespace_boundary(Path::new("/etc/passwd")).is_none());
+/// ```
+pub fn find_namespace_boundary(path: &Path) -> Option<(PathBuf, PathBuf)> {
+    let prefix_len = namespace_prefix_len(path)?;
+    split_at_component(path, prefix_len)
+}
+
+/// Split `path` after its first `prefix_len` components into `(prefix, remainder)`.
+fn split_at_component(path: &Path, prefix_len: usize) -> Option<(PathBuf, PathBuf)> {
+    let mut components = path.components();
+    let mut prefix = PathBuf::with_capacity(path.as_os_str().len());
+    for _ in 0..prefix_len {
+        prefix.push(components.next()?.as_os_str());
+    }
+    let remainder: PathBuf = components.collect();
+    Some((prefix, remainder))
+}
+
+/// `base` followed by the components of `rest`.
+///
+/// Unlike [`Path::join`], an empty `rest` adds nothing: `join("")` appends a trailing
+/// separator, which has no place in a canonical path.
+#[cfg(all(feature = "fs", target_os = "linux"))]
+pub(crate) fn join_components(base: &Path, rest: &Path) -> PathBuf {
+    let mut joined = base.to_path_buf();
+    joined.extend(rest.components());
+    joined
+}
+
+/// Whether `path` begins with the proc root followed by a separator, e.g. `/proc/`.
+///
+/// A byte scan, cheaper than the component walk of [`find_namespace_boundary`] on the
+/// common path with no boundary at all. Repeated separators and `.` segments are
+/// skipped exactly as [`Path::components`] skips them, so `//proc/self/root` and
+/// `/./proc/self/root` still pass and are detected as before.
+#[cfg(all(feature = "fs", target_os = "linux"))]
+pub(crate) fn starts_with_proc_root(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut rest = path.as_os_str().as_bytes();
+    // The root is plain and absolute, so after "/" it is all Normal components.
+    for name in proc_root().components().skip(1) {
+        if !rest.starts_with(b"/") {
+            return false;
+        }
+        rest = skip_separators(rest);
+        rest = match rest.strip_prefix(name.as_os_str().as_bytes()) {
+            Some(rest) => rest,
+            None => return false,
+        };
+    }
+    rest.starts_with(b"/")
+}
+
+/// `bytes` without its leading run of `/` and `./`, all of which `Path::components` drops.
+#[cfg(all(feature = "fs", target_os = "linux"))]
+fn skip_separators(mut bytes: &[u8]) -> &[u8] {
+    loop {
+        if let Some(rest) = bytes.strip_prefix(b"/") {
+            bytes = rest;
+        } else if let Some(rest) = bytes.strip_prefix(b"./") {
+            bytes = rest;
+        } else {
+            return bytes;
+        }
+    }
+}
+
+/// Check if a path is a `/proc` magic path (`/proc/{pid}/root`, `/proc/{pid}/cwd`, `/proc/{pid}/ns/{type}`).
+///
+/// This checks whether the path matches patterns like:
+/// - `/proc/self/root`, `/proc/self/cwd`
+/// - `/proc/thread-self/root`, `/proc/thread-self/cwd`
+/// - `/proc/{numeric_pid}/root`, `/proc/{numeric_pid}/cwd`
+/// - `/proc/{numeric_pid}/ns/net` and the other namespace types
+///
+/// The path may have additional components after the magic suffix (e.g., `/proc/self/root/etc`).
+#[cfg(all(feature = "fs", target_os = "linux"))]
+pub(crate) fn is_proc_magic_path(path: &Path) -> bool {
+    namespace_prefix_len(path).is_some()
+}
+
+/// Lexically normalize `.` and `..` components from `path` into `out`.
+///
+/// Purely symbolic — does NOT follow symlinks. `..` at root is a no-op. `out` is
+/// cleared before use so callers can reuse a buffer across calls.
+///
+/// Used to catch namespace-boundary bypasses where `..` in the prefix defeats
+/// lexical matching in [`find_namespace_boundary`], e.g. `/proc/<PID>/../<PID>/root`
+/// lexically normalizes to `/proc/<PID>/root`.
+#[cfg(all(feature = "fs", target_os = "linux"))]
+pub(crate) fn lexical_normalize_into(path: &Path, out: &mut PathBuf) {
+    out.clear();
+    for component in path.components() {
+        match component {
+            Component::RootDir => out.push(component.as_os_str()),
+            Component::Normal(name) => out.push(name),
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::CurDir => {}
+            Component::Prefix(_) => unreachable!("Linux paths don't have prefixes"),
+        }
+    }
+}
+
+// The tests compare against `canonicalize`, so they need the resolver.
+#[cfg(all(test, feature = "fs"))]
+mod tests {
+    use super::*;
+    use crate::canonicalize;
+
+    // ==========================================================================
+    // BYTE-LEVEL PRE-CHECK (starts_with_proc_root)
+    // ==========================================================================
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn proc_root_pre_check_matches_what_components_would() {
+        assert!(starts_with_proc_root(Path::new("/proc/self/root")));
+        assert!(starts_with_proc_root(Path::new("/proc/")));
+        assert!(starts_with_proc_root(Path::new("//proc/self/root")));
+        assert!(starts_with_proc_root(Path::new("/./proc/self/root")));
+        assert!(starts_with_proc_root(Path::new("/.//./proc//self")));
+
+        assert!(!starts_with_proc_root(Path::new("/proc")));
+        assert!(!starts_with_proc_root(Path::new("/procfs/self/root")));
+        assert!(!starts_with_proc_root(Path::new("/.proc/self/root")));
+        assert!(!starts_with_proc_root(Path::new("/../proc/self/root")));
+        assert!(!starts_with_proc_root(Path::new("proc/self/root")));
+        assert!(!starts_with_proc_root(Path::new("/etc/passwd")));
+    }
+
+    // ==========================================================================
+    // NAMESPACE BOUNDARY DETECTION (find_namespace_boundary)
+    // These tests verify the lexical pattern matching that identifies
+    // /proc/PID/root and /proc/PID/cwd as namespace boundaries.
+    // ==========================================================================
+
+    #[test]
+    fn test_find_namespace_boundary_proc_pid_root() {
+        // Standard pattern: /proc/<numeric_pid>/root
+        // Used by container runtimes to access container filesystems from host
+        let (prefix, remainder) =
+            find_namespace_boundary(Path::new("/proc/1234/root/etc/passwd")).unwrap();
+        assert_eq!(prefix, PathBuf::from("/proc/1234/root"));
+        assert_eq!(remainder, PathBuf::from("etc/passwd"));
+    }
+
+    #[test]
+    fn test_find_namespace_boundary_proc_pid_cwd() {
+        // Pattern: /proc/<pid>/cwd - the process's current working directory
+        // Less common but equally needs protection
+        let (prefix, remainder) =
+            find_namespace_boundary(Path::new("/proc/5678/cwd/some/file.txt")).unwrap();
+        assert_eq!(prefix, PathBuf::from("/proc/5678/cwd"));
+        assert_eq!(remainder, PathBuf::from("some/file.txt"));
+    }
+
+    #[test]
+    fn test_find_namespace_boundary_proc_self_root() {
+        // /proc/self/root - own process's root, resolves to "/" on host
+        // Common in self-referential container tooling
+        let (prefix, remainder) =
+            find_namespace_boundary(Path::new("/proc/self/root/etc/passwd")).unwrap();
+        assert_eq!(prefix, PathBuf::from("/proc/self/root"));
+        assert_eq!(remainder, PathBuf::from("etc/passwd"));
+    }
+
+    #[test]
+    fn test_find_namespace_boundary_proc_thread_self_root() {
+        // /proc/thread-self/root - per-thread namespace, less common
+        let (prefix, remainder) =
+            find_namespace_boundary(Path::new("/proc/thread-self/root/app/config")).unwrap();
+        assert_eq!(prefix, PathBuf::from("/proc/thread-self/root"));
+        assert_eq!(remainder, PathBuf::from("app/config"));
+    }
+
+    #[test]
+    fn test_find_namespace_boundary_just_prefix_no_remainder() {
+        // Accessing just the magic path itself, no subpath
+        let (prefix, remainder) = find_namespace_boundary(Path::new("/proc/1234/root")).unwrap();
+        assert_eq!(prefix, PathBuf::from("/proc/1234/root"));
+        assert_eq!(remainder, PathBuf::from(""));
+    }
+
+    #[test]
+    fn test_find_namespace_boundary_normal_path_returns_none() {
+        // Regular paths should NOT match - no namespace treatment needed
+        assert!(find_namespace_boundary(Path::new("/home/user/file.txt")).is_none());
+    }
+
+    #[test]
+    fn test_find_namespace_boundary_proc_other_files_not_namespace() {
+        // SECURITY: /proc/PID/status, /proc/PID/exe, /proc/PID/fd are NOT namespaces
+        // Only "root" and "cwd" are magic symlinks that cross namespace boundaries
+        assert!(find_namespace_boundary(Path::new("/proc/1234/status")).is_none());
+        assert!(find_namespace_boundary(Path::new("/proc/1234/exe")).is_none());
+        assert!(find_namespace_boundary(Path::new("/proc/1234/fd/0")).is_none());
+    }
+
+    #[test]
+    fn test_find_namespace_boundary_relative_path_rejected() {
+        // SECURITY: Only absolute paths can be namespace boundaries
+        // "proc/1234/root" without leading "/" is relative, not /proc
+        assert!(find_namespace_boundary(Path::new("proc/1234/root")).is_none());
+    }
+
+    #[test]
+    fn test_find_namespace_boundary_invalid_pid_rejected() {
+        // SECURITY: PID must be numeric, "self", or "thread-self"
+        // Arbitrary strings like "abc" must not match
+        assert!(find_namespace_boundary(Path::new("/proc/abc/root")).is_none());
+        assert!(find_namespace_boundary(Path::new("/proc/123abc/root")).is_none());
+        assert!(find_namespace_boundary(Path::new("/proc//root")).is_none());
+    }
+
+    // ==========================================================================
+    // EDGE CASES FOR BOUNDARY DETECTION
+    // ==========================================================================
+
+    #[test]
+    fn boundary_detection_handles_trailing_slash() {
+        let (prefix, _remainder) = find_namespace_boundary(Path::new("/proc/1234/root/")).unwrap();
+        assert_eq!(prefix, PathBuf::from("/proc/1234/root"));
+    }
+
+    #[test]
+    fn dot_only_remainder_is_empty() {
+        // Path::components drops interior and trailing `.`, so a dot-only remainder
+        // reaches the resolver as a bare boundary and skips the canonicalize round-trip.
+        let (prefix, remainder) =
+            find_namespace_boundary(Path::new("/proc/1234/root/././.")).unwrap();
+        assert_eq!(prefix, PathBuf::from("/proc/1234/root"));
+        assert!(remainder.as_os_str().is_empty());
+    }
+
+    #[test]
+    fn boundary_detection_handles_dot_components() {
+        let (prefix, _remainder) =
+            find_namespace_boundary(Path::new("/proc/1234/root/./etc/../etc")).unwrap();
+        assert_eq!(prefix, PathBuf::from("/proc/1234/root"));
+    }
+
+    // ==========================================================================
+    // PID/TID SEGMENT VALIDATION (private API)
+    // Public-API behavior tests live in tests/public_api.rs.
+    // ==========================================================================
+
+    #[test]
+    fn missing_pid_not_namespace() {
+        assert!(find_namespace_boundary(Path::new("/proc/root")).is_none());
+    }
+
+    #[test]
+    fn invalid_special_names_not_namespace() {
+        for name in &["parent", "init", "current", "me"] {
+            let path = format!("/proc/{name}/root");
+            assert!(find_namespace_boundary(Path::new(&path)).is_none());
+        }
+    }
+
+    #[test]
+    fn long_numeric_pid_accepted() {
+        let long_pid = "9".repeat(100);
+        let path = format!("/proc/{long_pid}/root");
+        assert!(find_namespace_boundary(Path::new(&path)).is_some());
+    }
+
+    #[test]
+    fn pid_zero_syntactically_valid_but_nonexistent() {
+        assert!(find_namespace_boundary(Path::new("/proc/0/root")).is_some());
+        // But doesn't exist
+        let err = canonicalize("/proc/0/root").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn negative_pid_not_valid() {
+        assert!(find_namespace_boundary(Path::new("/proc/-1/root")).is_none());
+    }
+
+    #[test]
+    fn leading_zeros_in_pid_accepted() {
+        assert!(find_namespace_boundary(Path::new("/proc/0001234/root")).is_some());
+    }
+
+    // ==========================================================================
+    // NAMESPACE LINKS (/proc/PID/ns/TYPE)
+    // ==========================================================================
+
+    #[test]
+    fn ns_links_are_namespace_boundaries() {
+        let (prefix, remainder) = find_namespace_boundary(Path::new("/proc/1234/ns/net")).unwrap();
+        assert_eq!(prefix, PathBuf::from("/proc/1234/ns/net"));
+        assert_eq!(remainder, PathBuf::from(""));
+
+        let (prefix, _) =
+            find_namespace_boundary(Path::new("/proc/self/task/42/ns/pid_for_children")).unwrap();
+        assert_eq!(
+            prefix,
+            PathBuf::from("/proc/self/task/42/ns/pid_for_children")
+        );
+    }
+
+    #[test]
+    fn unknown_ns_types_and_bare_ns_dir_not_boundaries() {
+        assert!(find_namespace_boundary(Path::new("/proc/1234/ns")).is_none());
+        assert!(find_namespace_boundary(Path::new("/proc/1234/ns/bogus")).is_none());
+        assert!(find_namespace_boundary(Path::new("/proc/1234/ns/NET")).is_none());
+    }
+
+    #[test]
+    fn every_published_suffix_and_ns_type_is_detected() {
+        for suffix in MAGIC_SUFFIXES {
+            let path = format!("/proc/1234/{suffix}");
+            assert_eq!(namespace_prefix_len(Path::new(&path)), Some(4), "{path}");
+        }
+        for ns_type in NS_TYPES {
+            let path = format!("/proc/1234/task/5/ns/{ns_type}");
+            assert_eq!(namespace_prefix_len(Path::new(&path)), Some(7), "{path}");
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn per_process_entry_dir_takes_exactly_one_entry() {
+        fn dir(path: &str) -> Option<&Path> {
+            per_process_entry_dir(Path::new(path))
+        }
+
+        assert_eq!(dir("/proc/1234/exe"), Some(Path::new("/proc/1234")));
+        assert_eq!(dir("/proc/self/exe"), Some(Path::new("/proc/self")));
+        assert_eq!(
+            dir("/proc/thread-self/task/5/exe"),
+            Some(Path::new("/proc/thread-self/task/5"))
+        );
+        assert_eq!(dir("//proc/./1234//exe"), Some(Path::new("/proc/1234")));
+
+        assert_eq!(dir("/proc/1234"), None);
+        assert_eq!(dir("/proc/1234/fd/3"), None);
+        assert_eq!(dir("/proc/1234/exe/x"), None);
+        assert_eq!(dir("/proc/1234/../exe"), None);
+        assert_eq!(dir("/proc/sys/exe"), None);
+        assert_eq!(dir("/proc/1234/task/x/exe"), None);
+    }
+}
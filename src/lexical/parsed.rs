@@ -0,0 +1,417 @@
+//! Typed view of a `/proc` namespace boundary: [`PidSelector`], [`NamespaceKind`],
+//! [`ParsedBoundary`].
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use super::{is_nonempty_ascii_digits, namespace_prefix_len, split_at_component};
+use crate::proc_root::proc_root_len;
+
+/// The process selector in a `/proc/<selector>/...` namespace boundary.
+///
+/// Renders back to the exact `/proc` component via [`Display`](fmt::Display), so
+/// `format!("/proc/{selector}/root")` reproduces the original spelling.
+///
+/// ```rust
+/// use proc_canonicalize::PidSelector;
+///
+/// let selector: PidSelector = "0042".parse().unwrap();
+/// assert!(matches!(selector, PidSelector::Numeric(_)));
+/// assert_eq!(selector.to_string(), "0042");
+///
+/// assert_eq!("self".parse::<PidSelector>().unwrap(), PidSelector::SelfProcess);
+/// assert!("Self".parse::<PidSelector>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PidSelector {
+    /// `/proc/self` — the calling process.
+    SelfProcess,
+    /// `/proc/thread-self` — the calling thread.
+    ThreadSelf,
+    /// `/proc/<digits>` — an explicit process ID.
+    Numeric(NumericPid),
+}
+
+/// Which per-process magic link a boundary goes through.
+///
+/// Lets a policy treat a container's filesystem (`root`) differently from a working
+/// directory (`cwd`) without matching strings. More kinds may be recognized as
+/// boundaries in future, so match with a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NamespaceKind {
+    /// `/proc/PID/root`: the process's root directory.
+    Root,
+    /// `/proc/PID/cwd`: the process's current working directory.
+    Cwd,
+    /// `/proc/PID/ns/TYPE`: one of the process's namespaces; the type is
+    /// [`ParsedBoundary::ns_type`].
+    Ns,
+}
+
+impl NamespaceKind {
+    /// The entry's name under `/proc/PID`: `"root"`, `"cwd"` or `"ns"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NamespaceKind::Root => "root",
+            NamespaceKind::Cwd => "cwd",
+            NamespaceKind::Ns => "ns",
+        }
+    }
+}
+
+/// A numeric `/proc` PID or TID component, kept exactly as spelled.
+///
+/// Always a non-empty string of ASCII digits. Leading zeros are preserved, because
+/// `/proc/0042` and `/proc/42` are different paths even though the kernel resolves both.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NumericPid(String);
+
+/// Error returned when a string is not a valid [`PidSelector`] or [`NumericPid`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePidError(());
+
+/// A `/proc` namespace boundary split out of a path.
+///
+/// Produced by [`parse_boundary`]. Purely lexical: the process is not required to exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedBoundary {
+    pid: PidSelector,
+    tid: Option<NumericPid>,
+    kind: NamespaceKind,
+    link: String,
+    prefix: PathBuf,
+    prefix_components: usize,
+    remainder: PathBuf,
+}
+
+impl NumericPid {
+    /// The digits exactly as they appeared in the path.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for NumericPid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for NumericPid {
+    type Err = ParsePidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if is_nonempty_ascii_digits(s) {
+            Ok(NumericPid(s.to_owned()))
+        } else {
+            Err(ParsePidError(()))
+        }
+    }
+}
+
+impl fmt::Display for PidSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PidSelector::SelfProcess => f.write_str("self"),
+            PidSelector::ThreadSelf => f.write_str("thread-self"),
+            PidSelector::Numeric(pid) => pid.fmt(f),
+        }
+    }
+}
+
+impl FromStr for PidSelector {
+    type Err = ParsePidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "self" => Ok(PidSelector::SelfProcess),
+            "thread-self" => Ok(PidSelector::ThreadSelf),
+            digits => digits.parse().map(PidSelector::Numeric),
+        }
+    }
+}
+
+impl fmt::Display for ParsePidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("expected `self`, `thread-self`, or an ASCII-digit PID")
+    }
+}
+
+impl std::error::Error for ParsePidError {}
+
+impl ParsedBoundary {
+    /// The process selector (`self`, `thread-self`, or a numeric PID).
+    pub fn pid(&self) -> &PidSelector {
+        &self.pid
+    }
+
+    /// The numeric PID as a `u32`, the type [`std::process::id`] returns.
+    ///
+    /// `None` for `self` and `thread-self`, and for digits too large for a `u32`, which
+    /// no process can have. Leading zeros are read as the kernel reads them, so
+    /// `/proc/0042/root` gives `42`.
+    ///
+    /// ```rust
+    /// use proc_canonicalize::parse_boundary;
+    ///
+    /// assert_eq!(parse_boundary("/proc/1234/root").unwrap().pid_u32(), Some(1234));
+    /// assert_eq!(parse_boundary("/proc/self/root").unwrap().pid_u32(), None);
+    /// assert_eq!(parse_boundary("/proc/99999999999/root").unwrap().pid_u32(), None);
+    /// ```
+    pub fn pid_u32(&self) -> Option<u32> {
+        match &self.pid {
+            PidSelector::Numeric(pid) => pid.as_str().parse().ok(),
+            PidSelector::SelfProcess | PidSelector::ThreadSelf => None,
+        }
+    }
+
+    /// The thread ID for `/proc/PID/task/TID/...` boundaries, `None` otherwise.
+    pub fn tid(&self) -> Option<&NumericPid> {
+        self.tid.as_ref()
+    }
+
+    /// Whether the boundary is a `root`, a `cwd` or a namespace link.
+    ///
+    /// ```rust
+    /// use proc_canonicalize::{parse_boundary, NamespaceKind};
+    ///
+    /// let boundary = parse_boundary("/proc/1234/task/5678/cwd/src").unwrap();
+    /// assert_eq!(boundary.kind(), NamespaceKind::Cwd);
+    /// ```
+    pub fn kind(&self) -> NamespaceKind {
+        self.kind
+    }
+
+    /// The namespace type of a `/proc/PID/ns/TYPE` boundary, e.g. `net`; `None` otherwise.
+    pub fn ns_type(&self) -> Option<&str> {
+        self.link.strip_prefix("ns/")
+    }
+
+    /// The magic link the boundary ends in: `root`, `cwd`, or `ns/TYPE`.
+    pub fn link(&self) -> &str {
+        &self.link
+    }
+
+    /// The boundary itself, e.g. `/proc/1234/root`.
+    pub fn prefix(&self) -> &Path {
+        &self.prefix
+    }
+
+    /// Everything after the boundary, e.g. `etc/passwd`. Empty for a bare boundary.
+    pub fn remainder(&self) -> &Path {
+        &self.remainder
+    }
+
+    /// Number of segments the boundary spans, not counting the leading `/`.
+    ///
+    /// `/proc/1234/root` is 3; `/proc/1234/task/5678/root` is 5.
+    pub fn boundary_component_count(&self) -> usize {
+        self.prefix_components
+    }
+
+    /// Length of [`prefix`](Self::prefix) in bytes, for truncating or aligning displays.
+    ///
+    /// ```rust
+    /// let boundary = proc_canonicalize::parse_boundary("/proc/1234/root/etc/hosts").unwrap();
+    /// let display = "/proc/1234/root/etc/hosts";
+    /// assert_eq!(&display[boundary.boundary_byte_len()..], "/etc/hosts");
+    /// ```
+    pub fn boundary_byte_len(&self) -> usize {
+        self.prefix.as_os_str().len()
+    }
+}
+
+/// Split a path into its `/proc` namespace boundary and the remainder, if it has one.
+///
+/// Recognizes the same shapes that [`canonicalize`](crate::canonicalize) preserves. The
+/// check is lexical; nothing is read from the filesystem.
+///
+/// ```rust
+/// use std::path::Path;
+/// use proc_canonicalize::{parse_boundary, PidSelector};
+///
+/// let boundary = parse_boundary("/proc/self/task/77/root/etc/hosts").unwrap();
+/// assert_eq!(boundary.pid(), &PidSelector::SelfProcess);
+/// assert_eq!(boundary.tid().unwrap().as_str(), "77");
+/// assert_eq!(boundary.prefix(), Path::new("/proc/self/task/77/root"));
+/// assert_eq!(boundary.remainder(), Path::new("etc/hosts"));
+///
+/// assert!(parse_boundary("/proc/self/status").is_none());
+/// ```
+pub fn parse_boundary(path: impl AsRef<Path>) -> Option<ParsedBoundary> {
+    let path = path.as_ref();
+    let prefix_len = namespace_prefix_len(path)?;
+    let (prefix, remainder) = split_at_component(path, prefix_len)?;
+
+    // Skip the proc root; the selector segments were validated by namespace_prefix_len.
+    let mut segments = prefix
+        .components()
+        .skip(proc_root_len())
+        .map(|c| c.as_os_str().to_str());
+    let pid = segments.next()??.parse().ok()?;
+    let rest = segments.collect::<Option<Vec<_>>>()?;
+    let (tid, link) = match rest.as_slice() {
+        ["task", tid, link @ ..] => (Some(tid.parse().ok()?), link),
+        link => (None, link),
+    };
+    let kind = match link {
+        ["root"] => NamespaceKind::Root,
+        ["cwd"] => NamespaceKind::Cwd,
+        ["ns", _] => NamespaceKind::Ns,
+        _ => return None,
+    };
+
+    Some(ParsedBoundary {
+        pid,
+        tid,
+        kind,
+        link: link.join("/"),
+        prefix,
+        // The leading "/" is a component to Path but not a segment of the boundary.
+        prefix_components: prefix_len - 1,
+        remainder,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_boundary_exposes_typed_pid_selector() {
+        let self_boundary = parse_boundary("/proc/self/root/etc").unwrap();
+        let thread_boundary = parse_boundary("/proc/thread-self/cwd").unwrap();
+        let numeric_boundary = parse_boundary("/proc/1234/root").unwrap();
+
+        assert_eq!(self_boundary.pid(), &PidSelector::SelfProcess);
+        assert_eq!(thread_boundary.pid(), &PidSelector::ThreadSelf);
+        assert_eq!(
+            numeric_boundary.pid(),
+            &PidSelector::Numeric("1234".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn parse_boundary_task_form_exposes_tid() {
+        let boundary = parse_boundary("/proc/1234/task/5678/cwd/src").unwrap();
+
+        assert_eq!(boundary.pid().to_string(), "1234");
+        assert_eq!(boundary.tid().map(NumericPid::as_str), Some("5678"));
+        assert_eq!(boundary.prefix(), Path::new("/proc/1234/task/5678/cwd"));
+        assert_eq!(boundary.remainder(), Path::new("src"));
+    }
+
+    #[test]
+    fn parse_boundary_exposes_link_name() {
+        let links = [
+            ("/proc/1234/root/etc", "root"),
+            ("/proc/self/cwd", "cwd"),
+            ("/proc/1234/task/5678/ns/mnt", "ns/mnt"),
+        ];
+        for (path, expected) in links {
+            assert_eq!(parse_boundary(path).unwrap().link(), expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn parse_boundary_classifies_kind() {
+        let kinds = [
+            ("/proc/1234/root/etc", NamespaceKind::Root, None),
+            ("/proc/self/cwd", NamespaceKind::Cwd, None),
+            ("/proc/thread-self/task/1/root", NamespaceKind::Root, None),
+            ("/proc/1234/ns/net", NamespaceKind::Ns, Some("net")),
+            (
+                "/proc/1234/task/5678/ns/pid_for_children",
+                NamespaceKind::Ns,
+                Some("pid_for_children"),
+            ),
+        ];
+        for (path, kind, ns_type) in kinds {
+            let boundary = parse_boundary(path).unwrap();
+            assert_eq!(boundary.kind(), kind, "{path}");
+            assert_eq!(boundary.ns_type(), ns_type, "{path}");
+        }
+    }
+
+    #[test]
+    fn parse_boundary_rejects_non_boundaries() {
+        assert!(parse_boundary("/proc/self/status").is_none());
+        assert!(parse_boundary("/proc/abc/root").is_none());
+        assert!(parse_boundary("proc/self/root").is_none());
+    }
+
+    #[test]
+    fn pid_selector_display_round_trips_exact_spelling() {
+        for spelling in ["self", "thread-self", "1", "0001234"] {
+            let selector: PidSelector = spelling.parse().unwrap();
+            assert_eq!(selector.to_string(), spelling);
+        }
+    }
+
+    #[test]
+    fn pid_selector_rejects_invalid_spellings() {
+        for spelling in ["", "Self", "-1", "12a", "thread_self", " 1"] {
+            assert!(
+                spelling.parse::<PidSelector>().is_err(),
+                "accepted {spelling:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn leading_zero_pid_selector_distinct_from_plain_pid() {
+        let padded: PidSelector = "042".parse().unwrap();
+        let plain: PidSelector = "42".parse().unwrap();
+
+        assert_ne!(padded, plain);
+    }
+
+    #[test]
+    fn pid_u32_reads_numeric_pids_by_value() {
+        for (path, expected) in [
+            ("/proc/1234/root", Some(1234)),
+            ("/proc/0042/cwd", Some(42)),
+            ("/proc/4294967295/root", Some(u32::MAX)),
+            ("/proc/1234/task/5678/root", Some(1234)),
+            ("/proc/self/root", None),
+            ("/proc/thread-self/ns/net", None),
+        ] {
+            assert_eq!(parse_boundary(path).unwrap().pid_u32(), expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn pid_u32_is_none_on_overflow() {
+        let long = format!("/proc/{}/root", "9".repeat(100));
+        for path in ["/proc/4294967296/root", long.as_str()] {
+            let boundary = parse_boundary(path).unwrap();
+
+            assert_eq!(boundary.pid_u32(), None, "{path}");
+        }
+    }
+
+    #[test]
+    fn boundary_component_count_excludes_leading_slash() {
+        let counts = [
+            ("/proc/1234/root/etc", 3),
+            ("/proc/self/cwd", 3),
+            ("/proc/1234/ns/net", 4),
+            ("/proc/1234/task/5678/root", 5),
+            ("/proc/thread-self/task/1/ns/mnt", 6),
+        ];
+        for (path, expected) in counts {
+            let boundary = parse_boundary(path).unwrap();
+            assert_eq!(boundary.boundary_component_count(), expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn boundary_byte_len_matches_prefix_even_with_messy_input() {
+        let boundary = parse_boundary("//proc//0042/./root///etc").unwrap();
+
+        assert_eq!(boundary.prefix(), Path::new("/proc/0042/root"));
+        assert_eq!(boundary.boundary_byte_len(), "/proc/0042/root".len());
+    }
+}
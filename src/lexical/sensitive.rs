@@ -0,0 +1,164 @@
+//! Recognizing `/proc/PID` entries that expose a process's memory or secrets.
+
+use std::path::{Component, Path};
+
+use crate::proc_root::proc_root;
+
+use super::{is_numeric_segment, is_valid_pid_segment};
+
+/// Per-process entries treated as sensitive, in `/proc/PID/SUFFIX` and
+/// `/proc/PID/task/TID/SUFFIX` form.
+const SENSITIVE_SUFFIXES: &[&str] = &[
+    // Auxiliary vector: load addresses, AT_RANDOM bytes.
+    "auxv",
+    // Environment at exec time: tokens and passwords passed through the environment.
+    "environ",
+    // Open file descriptors, as magic links that reopen the files.
+    "fd",
+    // Memory-mapped files, as magic links that reopen them.
+    "map_files",
+    // Address-space layout.
+    "maps",
+    // The process's memory itself.
+    "mem",
+    // Address-space layout with NUMA placement.
+    "numa_maps",
+    // Virtual-to-physical page mappings.
+    "pagemap",
+    // Address-space layout with per-mapping memory usage.
+    "smaps",
+    "smaps_rollup",
+    // Kernel stack.
+    "stack",
+    // Current system call and its arguments.
+    "syscall",
+];
+
+/// The entries [`is_sensitive_proc_path`] recognizes, e.g. `mem` and `environ`.
+///
+/// Sorted, so an allow- or deny-list of your own can be diffed against it.
+///
+/// ```rust
+/// use proc_canonicalize::recognized_sensitive_suffixes;
+///
+/// assert!(recognized_sensitive_suffixes().contains(&"environ"));
+/// ```
+pub fn recognized_sensitive_suffixes() -> &'static [&'static str] {
+    SENSITIVE_SUFFIXES
+}
+
+/// Whether `path` names a sensitive per-process `/proc` entry or something below it.
+///
+/// Matches `/proc/PID/SUFFIX` and `/proc/PID/task/TID/SUFFIX`, where `PID` is numeric,
+/// `self` or `thread-self`, and `SUFFIX` is one of [`recognized_sensitive_suffixes`].
+///
+/// Purely lexical, like [`parse_boundary`](crate::parse_boundary): a path that reaches
+/// such an entry through `..`, a symlink, or another process's root (e.g.
+/// `/proc/1234/root/proc/1/mem`) is not recognized until it is resolved. Check the
+/// output of [`canonicalize`](crate::canonicalize) as well as the input.
+///
+/// ```rust
+/// use proc_canonicalize::is_sensitive_proc_path;
+///
+/// assert!(is_sensitive_proc_path("/proc/1234/environ"));
+/// assert!(is_sensitive_proc_path("/proc/self/task/5678/mem"));
+/// assert!(is_sensitive_proc_path("/proc/self/fd/3"));
+///
+/// assert!(!is_sensitive_proc_path("/proc/1234/status"));
+/// assert!(!is_sensitive_proc_path("/proc/1234/root/etc/environ"));
+/// ```
+pub fn is_sensitive_proc_path(path: impl AsRef<Path>) -> bool {
+    let mut components = path.as_ref().components();
+
+    for expected in proc_root().components() {
+        if components.next() != Some(expected) {
+            return false;
+        }
+    }
+
+    let entry = match (components.next(), components.next()) {
+        (Some(Component::Normal(pid)), Some(Component::Normal(entry)))
+            if is_valid_pid_segment(pid) =>
+        {
+            entry
+        }
+        _ => return false,
+    };
+    if entry != "task" {
+        return is_sensitive_suffix(entry);
+    }
+
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(tid)), Some(Component::Normal(entry)))
+            if is_numeric_segment(tid) =>
+        {
+            is_sensitive_suffix(entry)
+        }
+        _ => false,
+    }
+}
+
+fn is_sensitive_suffix(entry: &std::ffi::OsStr) -> bool {
+    SENSITIVE_SUFFIXES.iter().any(|suffix| entry == *suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==========================================================================
+    // RECOGNIZED SHAPES
+    // ==========================================================================
+
+    #[test]
+    fn every_listed_suffix_is_sensitive_under_pid_and_task() {
+        for suffix in recognized_sensitive_suffixes() {
+            assert!(
+                is_sensitive_proc_path(format!("/proc/1/{suffix}")),
+                "{suffix}"
+            );
+            assert!(
+                is_sensitive_proc_path(format!("/proc/self/{suffix}")),
+                "{suffix}"
+            );
+            assert!(
+                is_sensitive_proc_path(format!("/proc/thread-self/task/2/{suffix}")),
+                "{suffix}"
+            );
+        }
+    }
+
+    #[test]
+    fn entries_below_a_sensitive_directory_are_sensitive() {
+        assert!(is_sensitive_proc_path("/proc/42/fd/0"));
+        assert!(is_sensitive_proc_path("/proc/42/map_files/7f00-7f01"));
+    }
+
+    #[test]
+    fn suffix_list_is_sorted_and_unique() {
+        let suffixes = recognized_sensitive_suffixes();
+        assert!(suffixes.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    // ==========================================================================
+    // NOT SENSITIVE
+    // ==========================================================================
+
+    #[test]
+    fn harmless_or_malformed_paths_are_not_sensitive() {
+        for path in [
+            "/proc/1/status",
+            "/proc/1/root",
+            "/proc/mem",
+            "/proc/abc/mem",
+            "/proc/1/task/self/mem",
+            "/proc/1/task/2",
+            "proc/1/mem",
+            "/proc",
+            "/etc/environ",
+            "/proc/1/root/proc/1/mem",
+        ] {
+            assert!(!is_sensitive_proc_path(path), "{path}");
+        }
+    }
+}
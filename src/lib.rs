@@ -11,7 +11,7 @@
 //! prefixes:
 //!
 //! ```rust
-//! # #[cfg(target_os = "linux")]
+//! # #[cfg(all(feature = "fs", target_os = "linux"))]
 //! # fn main() -> std::io::Result<()> {
 //! use std::path::Path;
 //!
@@ -24,7 +24,7 @@
 //! assert_eq!(resolved, Path::new("/proc/self/root/etc"));
 //! # Ok(())
 //! # }
-//! # #[cfg(not(target_os = "linux"))]
+//! # #[cfg(not(all(feature = "fs", target_os = "linux")))]
 //! # fn main() {}
 //! ```
 //!
@@ -39,22 +39,144 @@
 //!
 //! ## Optional Features
 //!
+//! - `fs` (default): Everything that resolves paths on disk. Build with
+//!   `default-features = false` to get only the pure [`lexical`] helpers, which never
+//!   touch the filesystem.
 //! - `dunce` (Windows only): Simplifies Windows extended-length paths by removing the `\\?\` prefix
 //!   when possible (e.g., `\\?\C:\foo` becomes `C:\foo`). Automatically preserves the prefix when
 //!   needed (e.g., for paths longer than 260 characters). Enable with `features = ["dunce"]`.
+//! - `env-config`: Honors `PROC_CANONICALIZE_PROC_ROOT` as the location of `/proc`, for
+//!   tests and hosts that mount procfs elsewhere. Off by default: anyone who controls the
+//!   environment could point the crate at a directory of ordinary symlinks posing as
+//!   `/proc`, so only enable it where the environment is trusted.
+//! - `metrics`: Exposes `metrics()`, a snapshot of process-wide counters (calls,
+//!   boundaries detected, escapes, symlink loops) kept in relaxed atomics, for scraping
+//!   into a monitoring system. Off by default so the default build counts nothing.
+//! - `testing`: Exposes `canonicalize_with_root`, which simulates a container whose
+//!   `/proc/PID/root` resolves to an arbitrary directory, and `MemoryFs`, an in-memory
+//!   tree to resolve crafted symlink topologies against. Intended for test suites only.
 
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+#[cfg(feature = "fs")]
+use std::borrow::Cow;
+#[cfg(feature = "fs")]
 use std::io;
+#[cfg(feature = "fs")]
 use std::path::{Path, PathBuf};
 
-#[cfg(target_os = "linux")]
-use std::path::Component;
-
-/// Maximum number of symlinks to follow before giving up (matches kernel MAXSYMLINKS).
-#[cfg(target_os = "linux")]
-const MAX_SYMLINK_FOLLOWS: u32 = 40;
+#[cfg(feature = "fs")]
+mod both;
+#[cfg(feature = "fs")]
+mod contain;
+#[cfg(all(feature = "fs", target_os = "linux"))]
+mod container;
+#[cfg(feature = "fs")]
+mod context;
+#[cfg(feature = "fs")]
+mod cwd;
+#[cfg(feature = "fs")]
+mod diagnose;
+#[cfg(feature = "fs")]
+mod escape;
+#[cfg(all(feature = "fs", target_os = "linux"))]
+mod fs;
+#[cfg(all(feature = "fs", target_os = "linux"))]
+mod inverse;
+pub mod lexical;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "fs")]
+mod open;
+#[cfg(feature = "fs")]
+mod options;
+#[cfg(all(feature = "fs", target_os = "linux"))]
+mod pidfd;
+#[cfg(all(feature = "fs", target_os = "linux"))]
+mod probe;
+mod proc_root;
+#[cfg(all(feature = "fs", target_os = "linux"))]
+mod process;
+#[cfg(feature = "fs")]
+mod read_link;
+#[cfg(all(feature = "fs", target_os = "linux"))]
+mod resolve;
+#[cfg(feature = "fs")]
+mod same_file;
+#[cfg(feature = "fs")]
+mod sanitize;
+#[cfg(feature = "fs")]
+mod scratch;
+#[cfg(feature = "fs")]
+mod split;
+#[cfg(feature = "fs")]
+mod strict;
+#[cfg(all(feature = "fs", target_os = "linux"))]
+mod subpaths;
+#[cfg(feature = "fs")]
+mod trace;
+#[cfg(feature = "fs")]
+mod typed;
+
+#[cfg(feature = "fs")]
+pub use both::{canonicalize_both, ResolvedBoth};
+#[cfg(feature = "fs")]
+pub use contain::{assert_confined, join_and_canonicalize, resolve_under, Containment};
+#[cfg(all(feature = "fs", target_os = "linux"))]
+pub use container::{canonicalize_in_container, ContainerResolver};
+#[cfg(feature = "fs")]
+pub use context::{canonicalize_with_context, CanonicalizeFailed};
+#[cfg(feature = "fs")]
+pub use cwd::CwdUnavailable;
+#[cfg(feature = "fs")]
+pub use diagnose::{diagnose, ComponentStatus};
+#[cfg(feature = "fs")]
+pub use escape::{BoundaryEscaped, EscapeBehavior};
+#[cfg(all(target_os = "linux", feature = "testing"))]
+pub use fs::MemoryFs;
+#[cfg(all(feature = "fs", target_os = "linux"))]
+pub use inverse::host_to_namespace;
+pub use lexical::{
+    canonicalize_lexical, checked_join, is_sensitive_proc_path, parse_boundary,
+    recognized_sensitive_suffixes, BoundaryDisplay, BoundaryFormat, ColonFormat, NamespaceKind,
+    NumericPid, ParsePidError, ParsedBoundary, PidSelector, StableFormat, MAGIC_SUFFIXES, NS_TYPES,
+};
+#[cfg(feature = "metrics")]
+pub use metrics::{metrics, Metrics};
+#[cfg(all(feature = "fs", target_os = "linux"))]
+pub use open::canonicalize_cwd_snapshot;
+#[cfg(feature = "fs")]
+pub use open::{open, open_nofollow_last};
+#[cfg(feature = "fs")]
+pub use options::{CanonicalizeOptions, FileTypeSet};
+#[cfg(all(feature = "fs", target_os = "linux"))]
+pub use pidfd::canonicalize_pidfd;
+#[cfg(all(feature = "fs", target_os = "linux"))]
+pub use probe::{boundary_is_current_namespace, can_access_boundary};
+#[cfg(all(feature = "fs", target_os = "linux"))]
+pub use process::{ProcessGone, ProcessIdentity, ProcessReused};
+#[cfg(feature = "fs")]
+pub use read_link::read_link_preserving;
+#[cfg(feature = "fs")]
+pub use same_file::same_file;
+#[cfg(feature = "fs")]
+pub use sanitize::{canonicalize_sanitized, Inaccessible};
+#[cfg(feature = "fs")]
+pub use scratch::{canonicalize_arc, canonicalize_with_scratch, Scratch};
+#[cfg(feature = "fs")]
+pub use split::{canonicalize_split, canonicalize_stable};
+#[cfg(feature = "fs")]
+pub use strict::canonicalize_reject_symlinks;
+#[cfg(all(feature = "fs", target_os = "linux"))]
+pub use subpaths::resolve_subpaths_under;
+#[cfg(feature = "fs")]
+pub use trace::{canonicalize_trace_to_writer, canonicalize_verbose, Step};
+#[cfg(feature = "fs")]
+pub use typed::{canonicalize_dir, canonicalize_file, canonicalize_parent};
+
+#[cfg(all(feature = "fs", target_os = "linux"))]
+use resolve::canonicalize_impl;
 
 /// Canonicalize a path, preserving Linux `/proc/PID/root` and `/proc/PID/cwd` boundaries.
 ///
@@ -64,6 +186,7 @@ const MAX_SYMLINK_FOLLOWS: u32 = 40;
 /// - `/proc/PID/task/TID/root`, `/proc/PID/task/TID/cwd`
 /// - `/proc/self/root`, `/proc/self/cwd`
 /// - `/proc/thread-self/root`, `/proc/thread-self/cwd`
+/// - `/proc/PID/ns/TYPE` namespace links (e.g. `/proc/self/ns/net`)
 ///
 /// # Examples
 ///
@@ -83,6 +206,35 @@ const MAX_SYMLINK_FOLLOWS: u32 = 40;
 /// # fn main() {}
 /// ```
 ///
+/// # Inputs
+///
+/// Like every path-taking function in this crate, `canonicalize` accepts anything that
+/// is [`AsRef<Path>`], which includes `&OsStr` and `OsString` as well as `&str` and
+/// `String`, so raw OS strings need no `Path::new`. On Unix a path is bytes: names that
+/// are not valid UTF-8 are resolved and returned unchanged, with no lossy conversion.
+///
+/// Nor is any Unicode normalization applied. `é` written as one code point (NFC) and as
+/// `e` plus a combining accent (NFD) are two different names, as they are to the
+/// kernel, and each comes back exactly as the filesystem spells it. Likewise a
+/// lookalike such as `/proc/ѕelf/root` (with a Cyrillic `ѕ`) is an ordinary path, not a
+/// boundary.
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::ffi::OsStr;
+/// use std::os::unix::ffi::OsStrExt;
+/// use proc_canonicalize::canonicalize;
+///
+/// // e.g. a `*const c_char` from FFI, viewed as bytes
+/// let raw = OsStr::from_bytes(b"/proc/self/root/etc");
+/// assert_eq!(canonicalize(raw)?.as_os_str(), raw);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
 /// # Why This Matters
 ///
 /// `std::fs::canonicalize("/proc/1234/root")` returns `/` because the kernel's
@@ -91,519 +243,358 @@ const MAX_SYMLINK_FOLLOWS: u32 = 40;
 ///
 /// # Platform Behavior
 ///
-/// - **Linux**: Preserves `/proc/PID/root` and `/proc/PID/cwd` prefixes
+/// - **Linux**: Preserves `/proc/PID/root` and `/proc/PID/cwd` prefixes, and
+///   `/proc/PID/fd/N` when its file has no name (unlinked, a pipe or a socket)
 /// - **Other platforms**: Identical to `std::fs::canonicalize`
 ///
+/// # Escapes
+///
+/// A boundary path does not always come back with its prefix. If `..` or a symlink
+/// leads outside the namespace (e.g. `/proc/1234/root` is `/var/lib/c1/rootfs` and the
+/// path reaches `/var/lib/c1`), the prefix cannot be kept truthfully and the plain
+/// *host* path is returned instead. Code that treats the result as "inside the
+/// container" must check `starts_with` on the prefix, or opt out of this default with
+/// [`CanonicalizeOptions::escape_behavior`] or [`join_and_canonicalize`].
+///
+/// Whether anything *can* escape depends on where the boundary points. On the host,
+/// `/proc/self/root` is `/`: every path lies under it, so nothing escapes it and the
+/// prefix always comes back. A container's `/proc/PID/root` is a subdirectory of the
+/// host, and `..` or absolute symlinks inside it are resolved against *your* root, not
+/// the container's, so they can land outside it. `/proc/PID/cwd` is almost never `/`,
+/// which makes `..` escapes through it common even without containers.
+///
+/// Only the first boundary counts. A symlink below it to another magic link
+/// (`/proc/self/root/run/link`, `link -> /proc/self/cwd`) is followed by the kernel
+/// like any other: the result is where it lands, under the first prefix
+/// (`/proc/self/root/home/me`), or an escape if that is outside it. It never switches
+/// to the second boundary's prefix. The process's own `/proc/self` right below its root
+/// (`/proc/1234/root/proc/self/status`) is kept as written: it names *you* as that
+/// procfs numbers you, which only the kernel knows, so it is only checked to exist.
+///
+/// # Bind mounts
+///
+/// The kernel names a directory by the mount it was reached through, never by the
+/// source of a bind mount. With `/mnt/disk/shared` bind-mounted at `/srv/shared`:
+///
+/// | Input                                   | Result                                  |
+/// |-----------------------------------------|-----------------------------------------|
+/// | `/proc/self/root/srv/shared/report`     | `/proc/self/root/srv/shared/report`     |
+/// | `/srv/shared/report`                    | `/srv/shared/report`                    |
+/// | `/proc/self/root/mnt/disk/shared/report`| `/proc/self/root/mnt/disk/shared/report`|
+///
+/// The same file reached two ways keeps both names, as with
+/// [`std::fs::canonicalize`]; compare results with [`same_file`] when that matters. A
+/// boundary that is itself a bind mount (a container root mounted from an image
+/// directory) is named by its mount point too, so subpaths of it stay inside it.
+///
 /// # Errors
 ///
 /// Returns an error if:
-/// - The path does not exist
+/// - The path does not exist; when that is because the process behind a `/proc/PID`
+///   boundary has exited, the [`io::ErrorKind::NotFound`] error carries a
+///   [`ProcessGone`] payload
 /// - The process lacks permission to access the path
+/// - The result would be longer than `PATH_MAX`, as an `ENAMETOOLONG` OS error
 /// - An I/O error occurs during resolution
+#[cfg(feature = "fs")]
+#[must_use = "a boundary path may resolve to a host path outside the namespace; see the Escapes section"]
 pub fn canonicalize(path: impl AsRef<Path>) -> io::Result<PathBuf> {
     canonicalize_impl(path.as_ref())
 }
 
-#[cfg(target_os = "linux")]
-fn canonicalize_impl(path: &Path) -> io::Result<PathBuf> {
-    // Check if path contains a /proc namespace boundary
-    if let Some((namespace_prefix, remainder)) = find_namespace_boundary(path) {
-        // Verify the namespace prefix exists and is accessible
-        // We use metadata() to check existence and permissions, which gives better error messages
-        // than exists() (e.g. PermissionDenied vs NotFound)
-        std::fs::metadata(&namespace_prefix)?;
-
-        if remainder.as_os_str().is_empty() {
-            // Path IS the namespace boundary (e.g., "/proc/1234/root")
-            Ok(namespace_prefix)
-        } else {
-            // Path goes through namespace boundary (e.g., "/proc/1234/root/etc/passwd")
-
-            // 1. Resolve the namespace prefix to its absolute path on the host.
-            // This is necessary because /proc/PID/root might not be "/" (e.g. in containers),
-            // and /proc/PID/cwd is almost certainly not "/".
-            let resolved_prefix = std::fs::canonicalize(&namespace_prefix)?;
-
-            // 2. Canonicalize the full path.
-            // This traverses the magic link and resolves everything.
-            let full_path = namespace_prefix.join(&remainder);
-            let canonicalized = std::fs::canonicalize(full_path)?;
-
-            // 3. Try to re-base the canonicalized path onto the namespace prefix.
-            // We do this by stripping the resolved prefix from the canonicalized path.
-            if let Ok(suffix) = canonicalized.strip_prefix(&resolved_prefix) {
-                // The path is within the namespace. Re-attach the prefix.
-                Ok(namespace_prefix.join(suffix))
-            } else {
-                // The path escaped the namespace (e.g. via ".." or symlinks to outside).
-                // In this case, we cannot preserve the prefix while being correct.
-                // We return the fully resolved path (absolute path on host).
-                Ok(canonicalized)
-            }
-        }
-    } else {
-        // Check for indirect symlinks to /proc magic paths BEFORE calling std::fs::canonicalize.
-        //
-        // This handles cases like:
-        //   symlink("/proc/self/root", "/tmp/container_link")
-        //   canonicalize("/tmp/container_link")        -> should return /proc/self/root, not /
-        //   canonicalize("/tmp/container_link/etc")    -> should return /proc/self/root/etc, not /etc
-        //
-        // We detect symlinks in the path that point to /proc magic paths and handle them
-        // the same way we handle direct /proc paths.
-        if let Some(magic_path) = detect_indirect_proc_magic_link(path)? {
-            // Found an indirect symlink to a /proc magic path
-            // Use our namespace-aware canonicalization on the reconstructed path
-            return canonicalize_impl(&magic_path);
-        }
+/// Resolve `path` against the current working directory, returning a plain host path.
+///
+/// This is what a shell does with a relative path: join it onto the cwd and resolve.
+/// The cwd is read once, at call time, and the result does not mention it symbolically.
+/// Contrast with `canonicalize("/proc/self/cwd/...")`, which keeps the `/proc/self/cwd`
+/// prefix and therefore tracks wherever the process's cwd is when the path is *used*.
+///
+/// Absolute paths are resolved as-is. Namespace boundaries reached through the path
+/// are still preserved exactly as in [`canonicalize`].
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use proc_canonicalize::{canonicalize, canonicalize_cwd_relative};
+///
+/// let cwd = std::env::current_dir()?.canonicalize()?;
+///
+/// // Snapshot of the cwd: a plain host path
+/// assert_eq!(canonicalize_cwd_relative(".")?, cwd);
+///
+/// // Live view of the cwd: the magic prefix is kept
+/// assert_eq!(canonicalize("/proc/self/cwd")?, std::path::Path::new("/proc/self/cwd"));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Returns an error with a [`CwdUnavailable`] payload if the current directory cannot
+/// be read (e.g. it was deleted), or any error [`canonicalize`] returns for the joined
+/// path.
+#[cfg(feature = "fs")]
+pub fn canonicalize_cwd_relative(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    canonicalize_impl(&cwd::current_dir()?.join(path))
+}
 
-        // Normal path - use std::fs::canonicalize directly
-        std::fs::canonicalize(path)
-    }
+/// [`canonicalize`], failing with [`io::ErrorKind::TimedOut`] once `deadline` passes.
+///
+/// Shorthand for `CanonicalizeOptions::new().deadline(deadline).canonicalize(path)`; see
+/// [`CanonicalizeOptions::deadline`] for the granularity of the check.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::time::{Duration, Instant};
+/// use proc_canonicalize::canonicalize_with_deadline;
+///
+/// let deadline = Instant::now() + Duration::from_millis(100);
+/// let resolved = canonicalize_with_deadline("/proc/self/root/etc", deadline)?;
+/// assert_eq!(resolved, std::path::Path::new("/proc/self/root/etc"));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Same as [`canonicalize`], plus [`io::ErrorKind::TimedOut`].
+#[cfg(feature = "fs")]
+pub fn canonicalize_with_deadline(
+    path: impl AsRef<Path>,
+    deadline: std::time::Instant,
+) -> io::Result<PathBuf> {
+    CanonicalizeOptions::new()
+        .deadline(deadline)
+        .canonicalize(path)
 }
 
-#[cfg(not(target_os = "linux"))]
-fn canonicalize_impl(path: &Path) -> io::Result<PathBuf> {
-    // On non-Linux platforms, just use std::fs::canonicalize
-    #[cfg(all(feature = "dunce", windows))]
-    {
-        dunce::canonicalize(path)
-    }
-    #[cfg(not(all(feature = "dunce", windows)))]
+/// Resolve `path` all the way to a plain host path, for handing to another process.
+///
+/// The opposite of [`canonicalize`]: every boundary, leading or nested, direct or
+/// through a symlink, is replaced by the directory it points at, so
+/// `/proc/1234/root/etc` becomes e.g. `/var/lib/containers/c1/rootfs/etc`. This is
+/// exactly [`std::fs::canonicalize`], named for the intent. Use it when the consumer
+/// (an external command, a config file for another daemon) would either not understand
+/// `/proc` magic links or resolve them relative to its *own* process.
+///
+/// The result names the target only for processes that share this process's mount
+/// namespace and root. Another namespace may have nothing, or something else, there.
+/// Namespace links (`/proc/PID/ns/TYPE`) have no host path and fail to resolve.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::to_host;
+///
+/// assert_eq!(to_host("/proc/self/root/etc")?, std::fs::canonicalize("/etc")?);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Same as [`std::fs::canonicalize`].
+#[cfg(feature = "fs")]
+pub fn to_host(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    #[cfg(target_os = "linux")]
     {
         std::fs::canonicalize(path)
     }
-}
-
-/// Count the leading components that form a `/proc` namespace-boundary prefix.
-///
-/// Returns `Some(4)` for `/proc/PID/{root,cwd}` shapes, `Some(6)` for
-/// `/proc/PID/task/TID/{root,cwd}` shapes, and `None` if the path does not
-/// begin with a valid namespace prefix. Allocation-free — used on every
-/// ancestor-walk iteration in the indirect-symlink scanner, where building
-/// transient `PathBuf`s just to discard them would dominate allocator cost.
-#[cfg(target_os = "linux")]
-fn namespace_prefix_len(path: &Path) -> Option<usize> {
-    let mut components = path.components();
-
-    if components.next()? != Component::RootDir {
-        return None;
-    }
-    match components.next()? {
-        Component::Normal(s) if s == "proc" => {}
-        _ => return None,
-    }
-
-    let pid = match components.next()? {
-        Component::Normal(s) => s,
-        _ => return None,
-    };
-    if !is_valid_pid_segment(pid) {
-        return None;
-    }
-
-    let next = match components.next()? {
-        Component::Normal(s) => s,
-        _ => return None,
-    };
-    if next == "root" || next == "cwd" {
-        return Some(4);
-    }
-    if next != "task" {
-        return None;
-    }
-
-    let tid = match components.next()? {
-        Component::Normal(s) => s,
-        _ => return None,
-    };
-    if !is_numeric_segment(tid) {
-        return None;
-    }
-
-    match components.next()? {
-        Component::Normal(s) if s == "root" || s == "cwd" => Some(6),
-        _ => None,
-    }
-}
-
-#[cfg(target_os = "linux")]
-fn is_valid_pid_segment(s: &std::ffi::OsStr) -> bool {
-    // to_str() returns a borrowed &str without allocating; to_string_lossy()
-    // would allocate a replacement String when the OsStr is not valid UTF-8.
-    match s.to_str() {
-        Some("self") | Some("thread-self") => true,
-        Some(s) => is_nonempty_ascii_digits(s),
-        None => false,
-    }
-}
-
-#[cfg(target_os = "linux")]
-fn is_numeric_segment(s: &std::ffi::OsStr) -> bool {
-    match s.to_str() {
-        Some(s) => is_nonempty_ascii_digits(s),
-        None => false,
+    #[cfg(not(target_os = "linux"))]
+    {
+        canonicalize_impl(path.as_ref())
     }
 }
 
-#[cfg(target_os = "linux")]
-fn is_nonempty_ascii_digits(s: &str) -> bool {
-    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+/// [`canonicalize`] for a path held as a [`Cow`], without copying it first.
+///
+/// Resolution only ever borrows its input: an absolute path with no boundary is scanned
+/// for indirect `/proc` links in place, without copying it and without reading the
+/// current directory. New paths are built only where the answer differs from the input:
+/// a relative path joined onto the cwd, a followed symlink, and the returned path. So a
+/// [`Cow::Borrowed`] is never cloned, and a [`Cow::Owned`] is dropped once resolved.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::borrow::Cow;
+/// use std::path::Path;
+/// use proc_canonicalize::canonicalize_cow;
+///
+/// let borrowed: Cow<'_, Path> = Cow::Borrowed(Path::new("/proc/self/root/etc"));
+/// assert_eq!(canonicalize_cow(borrowed)?, Path::new("/proc/self/root/etc"));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Same as [`canonicalize`].
+#[cfg(feature = "fs")]
+pub fn canonicalize_cow(path: Cow<'_, Path>) -> io::Result<PathBuf> {
+    canonicalize_impl(&path)
 }
 
-/// Find a `/proc/PID/root` or `/proc/PID/cwd` namespace boundary in the path.
+/// Canonicalize `path` namespace-aware only if it names a `/proc` boundary directly.
 ///
-/// Returns `Some((namespace_prefix, remainder))` if found, where:
-/// - `namespace_prefix` is the boundary path (e.g., `/proc/1234/root`)
-/// - `remainder` is the path after the boundary (e.g., `etc/passwd`)
+/// Paths that contain a boundary (e.g. `/proc/1234/root/etc`) are resolved exactly as
+/// [`canonicalize`] resolves them. Every other path goes straight to
+/// [`std::fs::canonicalize`], skipping the walk that looks for symlinks leading into
+/// `/proc`. That walk costs an `lstat` per component; skipping it means a symlink such
+/// as `/tmp/link -> /proc/1234/root` resolves to the host path (`/`), losing the
+/// boundary. Use this only where such links cannot occur or do not matter.
 ///
-/// Returns `None` if the path doesn't contain a namespace boundary.
-#[cfg(target_os = "linux")]
-fn find_namespace_boundary(path: &Path) -> Option<(PathBuf, PathBuf)> {
-    let prefix_len = namespace_prefix_len(path)?;
-
-    let mut components = path.components();
-    let mut prefix = PathBuf::with_capacity(path.as_os_str().len());
-    for _ in 0..prefix_len {
-        prefix.push(components.next()?.as_os_str());
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::canonicalize_if_proc;
+///
+/// assert_eq!(canonicalize_if_proc("/proc/self/root")?, Path::new("/proc/self/root"));
+/// assert_eq!(canonicalize_if_proc("/")?, Path::new("/"));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Same as [`canonicalize`] for boundary paths and [`std::fs::canonicalize`] otherwise.
+#[cfg(feature = "fs")]
+pub fn canonicalize_if_proc(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    match lexical::find_namespace_boundary(path) {
+        Some(_) => canonicalize_impl(path),
+        #[cfg(target_os = "linux")]
+        None => std::fs::canonicalize(path),
+        // Elsewhere there is no scan to skip; keep the `dunce` handling.
+        #[cfg(not(target_os = "linux"))]
+        None => canonicalize_impl(path),
     }
-    let remainder: PathBuf = components.collect();
-    Some((prefix, remainder))
 }
 
-/// Check if a path is a `/proc` magic path (`/proc/{pid}/root` or `/proc/{pid}/cwd`).
+/// Canonicalize `path` as if every namespace boundary in it resolved to `fake_root`.
 ///
-/// This checks whether the path matches patterns like:
-/// - `/proc/self/root`, `/proc/self/cwd`
-/// - `/proc/thread-self/root`, `/proc/thread-self/cwd`
-/// - `/proc/{numeric_pid}/root`, `/proc/{numeric_pid}/cwd`
+/// On a host, `/proc/self/root` resolves to `/`, so paths through it can never escape and
+/// the container code paths (re-basing onto a non-`/` root, escape detection) stay
+/// unexercised. This function accesses every detected boundary through `fake_root`
+/// instead of through the magic link, so a temporary directory can play the part of a
+/// container's root filesystem. The returned path carries the boundary prefix exactly as
+/// [`canonicalize`] would.
 ///
-/// The path may have additional components after the magic suffix (e.g., `/proc/self/root/etc`).
-#[cfg(target_os = "linux")]
-fn is_proc_magic_path(path: &Path) -> bool {
-    namespace_prefix_len(path).is_some()
-}
-
-/// Lexically normalize `.` and `..` components from `path` into `out`.
-///
-/// Purely symbolic — does NOT follow symlinks. `..` at root is a no-op. `out` is
-/// cleared before use so callers can reuse a buffer across calls.
-///
-/// Used to catch namespace-boundary bypasses where `..` in the prefix defeats
-/// lexical matching in [`find_namespace_boundary`], e.g. `/proc/<PID>/../<PID>/root`
-/// lexically normalizes to `/proc/<PID>/root`.
-#[cfg(target_os = "linux")]
-fn lexical_normalize_into(path: &Path, out: &mut PathBuf) {
-    out.clear();
-    for component in path.components() {
-        match component {
-            Component::RootDir => out.push(component.as_os_str()),
-            Component::Normal(name) => out.push(name),
-            Component::ParentDir => {
-                out.pop();
-            }
-            Component::CurDir => {}
-            Component::Prefix(_) => unreachable!("Linux paths don't have prefixes"),
-        }
+/// The boundary's process does not need to exist; only `fake_root` is checked.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::canonicalize_with_root;
+///
+/// let container_root = tempfile::tempdir()?;
+/// std::fs::create_dir(container_root.path().join("etc"))?;
+/// std::os::unix::fs::symlink("/tmp", container_root.path().join("etc/escape"))?;
+///
+/// // Inside the fake root: the namespace prefix is preserved
+/// let inside = canonicalize_with_root(container_root.path(), "/proc/1234/root/etc")?;
+/// assert_eq!(inside, Path::new("/proc/1234/root/etc"));
+///
+/// // Escaping the fake root: the host path is returned
+/// let escaped = canonicalize_with_root(container_root.path(), "/proc/1234/root/etc/escape")?;
+/// assert_eq!(escaped, std::fs::canonicalize("/tmp")?);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Platform Behavior
+///
+/// - **Linux**: Boundaries are resolved through `fake_root`
+/// - **Other platforms**: `fake_root` is ignored; identical to [`canonicalize`]
+///
+/// # Errors
+///
+/// Same as [`canonicalize`], except that boundary existence and permission errors come
+/// from `fake_root` rather than from `/proc`.
+#[cfg(feature = "testing")]
+pub fn canonicalize_with_root(
+    fake_root: impl AsRef<Path>,
+    path: impl AsRef<Path>,
+) -> io::Result<PathBuf> {
+    #[cfg(target_os = "linux")]
+    {
+        CanonicalizeOptions::new()
+            .stand_in_root(fake_root)
+            .canonicalize(path)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = fake_root;
+        canonicalize_impl(path.as_ref())
     }
 }
 
-/// Detect if a path contains an indirect symlink to a `/proc` magic path.
-///
-/// This walks the ancestor chain of the input path looking for symlinks that
-/// point to `/proc/.../root` or `/proc/.../cwd`.
-///
-/// Returns `Some(magic_path)` with any remaining suffix if found, or `None` otherwise.
-#[cfg(target_os = "linux")]
-fn detect_indirect_proc_magic_link(path: &Path) -> io::Result<Option<PathBuf>> {
-    // One-time owned copy at entry: the scan mutates `current_path` across
-    // symlink follows, so we must own it; borrowing `&Path` is not viable here.
-    let mut current_path = if path.is_absolute() {
-        path.to_path_buf()
-    } else {
-        std::env::current_dir()?.join(path)
-    };
-
-    // Scratch buffers reused across scan iterations so the hot loop does no
-    // per-iteration heap allocation. Sized to the input path up-front; push/pop
-    // may still grow on longer symlink targets, but most cases fit.
-    let cap = current_path.as_os_str().len();
-    let mut accumulated = PathBuf::with_capacity(cap);
-    let mut normalized = PathBuf::with_capacity(cap);
-
-    let mut iterations = 0;
-
-    // We restart the scan whenever we resolve a symlink
-    'scan: loop {
-        if iterations >= MAX_SYMLINK_FOLLOWS {
-            return Ok(None);
-        }
-
-        // We CANNOT blindly normalize_path() here because if we have "symlink/..",
-        // normalize_path() will remove "symlink" and "..", completely missing the fact
-        // that "symlink" might point to a magic path.
-        //
-        // Instead, we must walk the components one by one. If we hit a symlink, we resolve it.
-        // If we hit "..", we pop from our accumulated path.
-
-        // Check if the path's lexical normalization is magic. This catches two
-        // distinct bypasses that plain `is_proc_magic_path(&current_path)` misses:
-        //
-        // 1. `..` in the PREFIX:  /proc/<PID>/../<PID>/root lexically normalizes
-        //    to /proc/<PID>/root. Without this check, the component walk below
-        //    reaches /proc/<PID>/root after the `..` pop, sees it is a symlink,
-        //    and follows it via `read_link` — which returns "/", dropping the
-        //    namespace boundary entirely.
-        //
-        // 2. `..` in the REMAINDER that lands back on a boundary: after resolving
-        //    an indirect symlink to /proc/<PID>, a path like .../cwd/../root has
-        //    current_path = /proc/<PID>/cwd/../root, which matches the cwd branch
-        //    with remainder `../root`. Returning it as-is sends the caller down
-        //    the host-resolution path in canonicalize_impl, which loses the
-        //    boundary. Lexical normalization gives /proc/<PID>/root directly.
-        lexical_normalize_into(&current_path, &mut normalized);
-        if is_proc_magic_path(&normalized) {
-            return Ok(Some(std::mem::take(&mut normalized)));
-        }
-
-        accumulated.clear();
-        let mut components = current_path.components().peekable();
-
-        if let Some(Component::RootDir) = components.peek() {
-            accumulated.push("/");
-            components.next();
-        }
-
-        while let Some(component) = components.next() {
-            match component {
-                Component::RootDir => {
-                    accumulated.push("/");
-                }
-                Component::CurDir => {}
-                Component::ParentDir => {
-                    accumulated.pop();
-                    // After popping, we might be at a magic path (e.g. /proc/self/root/etc/..)
-                    if is_proc_magic_path(&accumulated) {
-                        // Append remaining components in place to preserve the suffix.
-                        accumulated.extend(components);
-                        return Ok(Some(std::mem::take(&mut accumulated)));
-                    }
-                }
-                Component::Normal(name) => {
-                    // Push first, then probe. On symlink we pop back to the parent
-                    // before resolving so relative targets rebase correctly.
-                    accumulated.push(name);
-
-                    let metadata = match std::fs::symlink_metadata(&accumulated) {
-                        Ok(m) => m,
-                        Err(_) => continue,
-                    };
-
-                    if metadata.is_symlink() {
-                        iterations += 1;
-                        // std::fs::read_link returns an owned PathBuf; no borrowing API exists.
-                        let target = std::fs::read_link(&accumulated)?;
-                        accumulated.pop(); // drop the symlink name
-                                           // PathBuf::push replaces when target is absolute, appends when relative.
-                        accumulated.push(target);
-                        accumulated.extend(components);
-                        std::mem::swap(&mut current_path, &mut accumulated);
-                        continue 'scan;
-                    }
-                }
-                Component::Prefix(_) => unreachable!("Linux paths don't have prefixes"),
-            }
-        }
-
-        // Scanned the whole path, no symlinks remain and the normalized form
-        // wasn't magic. One final check on the accumulated path.
-        if is_proc_magic_path(&accumulated) {
-            return Ok(Some(std::mem::take(&mut accumulated)));
-        }
-
-        return Ok(None);
+#[cfg(all(feature = "fs", not(target_os = "linux")))]
+fn canonicalize_impl(path: &Path) -> io::Result<PathBuf> {
+    // On non-Linux platforms, just use std::fs::canonicalize
+    #[cfg(all(feature = "dunce", windows))]
+    {
+        dunce::canonicalize(path)
+    }
+    #[cfg(not(all(feature = "dunce", windows)))]
+    {
+        std::fs::canonicalize(path)
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "fs", not(target_os = "linux")))]
 mod tests {
     use super::*;
 
-    #[cfg(target_os = "linux")]
-    mod linux {
-        use super::*;
-
-        // ==========================================================================
-        // NAMESPACE BOUNDARY DETECTION (find_namespace_boundary)
-        // These tests verify the lexical pattern matching that identifies
-        // /proc/PID/root and /proc/PID/cwd as namespace boundaries.
-        // ==========================================================================
-
-        #[test]
-        fn test_find_namespace_boundary_proc_pid_root() {
-            // Standard pattern: /proc/<numeric_pid>/root
-            // Used by container runtimes to access container filesystems from host
-            let (prefix, remainder) =
-                find_namespace_boundary(Path::new("/proc/1234/root/etc/passwd")).unwrap();
-            assert_eq!(prefix, PathBuf::from("/proc/1234/root"));
-            assert_eq!(remainder, PathBuf::from("etc/passwd"));
-        }
-
-        #[test]
-        fn test_find_namespace_boundary_proc_pid_cwd() {
-            // Pattern: /proc/<pid>/cwd - the process's current working directory
-            // Less common but equally needs protection
-            let (prefix, remainder) =
-                find_namespace_boundary(Path::new("/proc/5678/cwd/some/file.txt")).unwrap();
-            assert_eq!(prefix, PathBuf::from("/proc/5678/cwd"));
-            assert_eq!(remainder, PathBuf::from("some/file.txt"));
-        }
-
-        #[test]
-        fn test_find_namespace_boundary_proc_self_root() {
-            // /proc/self/root - own process's root, resolves to "/" on host
-            // Common in self-referential container tooling
-            let (prefix, remainder) =
-                find_namespace_boundary(Path::new("/proc/self/root/etc/passwd")).unwrap();
-            assert_eq!(prefix, PathBuf::from("/proc/self/root"));
-            assert_eq!(remainder, PathBuf::from("etc/passwd"));
-        }
-
-        #[test]
-        fn test_find_namespace_boundary_proc_thread_self_root() {
-            // /proc/thread-self/root - per-thread namespace, less common
-            let (prefix, remainder) =
-                find_namespace_boundary(Path::new("/proc/thread-self/root/app/config")).unwrap();
-            assert_eq!(prefix, PathBuf::from("/proc/thread-self/root"));
-            assert_eq!(remainder, PathBuf::from("app/config"));
-        }
-
-        #[test]
-        fn test_find_namespace_boundary_just_prefix_no_remainder() {
-            // Accessing just the magic path itself, no subpath
-            let (prefix, remainder) =
-                find_namespace_boundary(Path::new("/proc/1234/root")).unwrap();
-            assert_eq!(prefix, PathBuf::from("/proc/1234/root"));
-            assert_eq!(remainder, PathBuf::from(""));
+    #[test]
+    fn test_canonicalize_is_std_on_non_linux() {
+        // On non-Linux, we just wrap std::fs::canonicalize
+        let tmp = std::env::temp_dir();
+        let our_result = canonicalize(&tmp).expect("should succeed");
+        let std_result = std::fs::canonicalize(&tmp).expect("should succeed");
+        // With dunce feature on Windows, our result is simplified but std returns UNC
+        #[cfg(all(feature = "dunce", windows))]
+        {
+            let our_str = our_result.to_string_lossy();
+            let std_str = std_result.to_string_lossy();
+            // dunce should simplify the path
+            assert!(!our_str.starts_with(r"\\?\"), "dunce should simplify path");
+            assert!(std_str.starts_with(r"\\?\"), "std returns UNC format");
+            // They should match except for the UNC prefix
+            assert_eq!(our_str.as_ref(), std_str.trim_start_matches(r"\\?\"));
         }
-
-        #[test]
-        fn test_find_namespace_boundary_normal_path_returns_none() {
-            // Regular paths should NOT match - no namespace treatment needed
-            assert!(find_namespace_boundary(Path::new("/home/user/file.txt")).is_none());
-        }
-
-        #[test]
-        fn test_find_namespace_boundary_proc_other_files_not_namespace() {
-            // SECURITY: /proc/PID/status, /proc/PID/exe, /proc/PID/fd are NOT namespaces
-            // Only "root" and "cwd" are magic symlinks that cross namespace boundaries
-            assert!(find_namespace_boundary(Path::new("/proc/1234/status")).is_none());
-            assert!(find_namespace_boundary(Path::new("/proc/1234/exe")).is_none());
-            assert!(find_namespace_boundary(Path::new("/proc/1234/fd/0")).is_none());
-        }
-
-        #[test]
-        fn test_find_namespace_boundary_relative_path_rejected() {
-            // SECURITY: Only absolute paths can be namespace boundaries
-            // "proc/1234/root" without leading "/" is relative, not /proc
-            assert!(find_namespace_boundary(Path::new("proc/1234/root")).is_none());
-        }
-
-        #[test]
-        fn test_find_namespace_boundary_invalid_pid_rejected() {
-            // SECURITY: PID must be numeric, "self", or "thread-self"
-            // Arbitrary strings like "abc" must not match
-            assert!(find_namespace_boundary(Path::new("/proc/abc/root")).is_none());
-            assert!(find_namespace_boundary(Path::new("/proc/123abc/root")).is_none());
-            assert!(find_namespace_boundary(Path::new("/proc//root")).is_none());
-        }
-
-        // ==========================================================================
-        // EDGE CASES FOR BOUNDARY DETECTION
-        // ==========================================================================
-
-        #[test]
-        fn boundary_detection_handles_trailing_slash() {
-            let (prefix, _remainder) =
-                find_namespace_boundary(Path::new("/proc/1234/root/")).unwrap();
-            assert_eq!(prefix, PathBuf::from("/proc/1234/root"));
-        }
-
-        #[test]
-        fn boundary_detection_handles_dot_components() {
-            let (prefix, _remainder) =
-                find_namespace_boundary(Path::new("/proc/1234/root/./etc/../etc")).unwrap();
-            assert_eq!(prefix, PathBuf::from("/proc/1234/root"));
-        }
-
-        // ==========================================================================
-        // PID/TID SEGMENT VALIDATION (private API)
-        // Public-API behavior tests live in tests/public_api.rs.
-        // ==========================================================================
-
-        #[test]
-        fn missing_pid_not_namespace() {
-            assert!(find_namespace_boundary(Path::new("/proc/root")).is_none());
-        }
-
-        #[test]
-        fn invalid_special_names_not_namespace() {
-            for name in &["parent", "init", "current", "me"] {
-                let path = format!("/proc/{name}/root");
-                assert!(find_namespace_boundary(Path::new(&path)).is_none());
-            }
-        }
-
-        #[test]
-        fn long_numeric_pid_accepted() {
-            let long_pid = "9".repeat(100);
-            let path = format!("/proc/{long_pid}/root");
-            assert!(find_namespace_boundary(Path::new(&path)).is_some());
-        }
-
-        #[test]
-        fn pid_zero_syntactically_valid_but_nonexistent() {
-            assert!(find_namespace_boundary(Path::new("/proc/0/root")).is_some());
-            assert!(canonicalize("/proc/0/root").is_err()); // But doesn't exist
-        }
-
-        #[test]
-        fn negative_pid_not_valid() {
-            assert!(find_namespace_boundary(Path::new("/proc/-1/root")).is_none());
-        }
-
-        #[test]
-        fn leading_zeros_in_pid_accepted() {
-            assert!(find_namespace_boundary(Path::new("/proc/0001234/root")).is_some());
-        }
-    }
-
-    #[cfg(not(target_os = "linux"))]
-    mod non_linux {
-        use super::*;
-
-        #[test]
-        fn test_canonicalize_is_std_on_non_linux() {
-            // On non-Linux, we just wrap std::fs::canonicalize
-            let tmp = std::env::temp_dir();
-            let our_result = canonicalize(&tmp).expect("should succeed");
-            let std_result = std::fs::canonicalize(&tmp).expect("should succeed");
-            // With dunce feature on Windows, our result is simplified but std returns UNC
-            #[cfg(all(feature = "dunce", windows))]
-            {
-                let our_str = our_result.to_string_lossy();
-                let std_str = std_result.to_string_lossy();
-                // dunce should simplify the path
-                assert!(!our_str.starts_with(r"\\?\"), "dunce should simplify path");
-                assert!(std_str.starts_with(r"\\?\"), "std returns UNC format");
-                // They should match except for the UNC prefix
-                assert_eq!(our_str.as_ref(), std_str.trim_start_matches(r"\\?\"));
-            }
-            // Without dunce (or on non-Windows), they should match exactly
-            #[cfg(not(all(feature = "dunce", windows)))]
-            {
-                assert_eq!(our_result, std_result);
-            }
+        // Without dunce (or on non-Windows), they should match exactly
+        #[cfg(not(all(feature = "dunce", windows)))]
+        {
+            assert_eq!(our_result, std_result);
         }
     }
 }
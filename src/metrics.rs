@@ -0,0 +1,91 @@
+//! Process-wide resolution counters, behind the `metrics` feature.
+
+#[cfg(target_os = "linux")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(target_os = "linux")]
+pub(crate) static CALLS: AtomicU64 = AtomicU64::new(0);
+#[cfg(target_os = "linux")]
+pub(crate) static BOUNDARIES: AtomicU64 = AtomicU64::new(0);
+#[cfg(target_os = "linux")]
+pub(crate) static ESCAPES: AtomicU64 = AtomicU64::new(0);
+#[cfg(target_os = "linux")]
+pub(crate) static LOOPS: AtomicU64 = AtomicU64::new(0);
+
+/// A snapshot of the counters, as returned by [`metrics`].
+///
+/// Counters only ever grow; subtract two snapshots to get a rate. Each counter is read
+/// atomically, but the snapshot as a whole is not: a resolution finishing concurrently
+/// may be counted in one field and not yet in another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Metrics {
+    calls: u64,
+    boundaries: u64,
+    escapes: u64,
+    loops: u64,
+}
+
+impl Metrics {
+    /// Resolutions started, by any entry point.
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// Namespace boundaries detected, directly or through an indirect symlink.
+    pub fn boundaries(&self) -> u64 {
+        self.boundaries
+    }
+
+    /// Paths that resolved outside their boundary, whatever the
+    /// [`EscapeBehavior`](crate::EscapeBehavior) then did about it.
+    pub fn escapes(&self) -> u64 {
+        self.escapes
+    }
+
+    /// Resolutions that failed with `ELOOP` (too many levels of symbolic links).
+    pub fn loops(&self) -> u64 {
+        self.loops
+    }
+}
+
+/// Read the process-wide counters.
+///
+/// Counting is a relaxed atomic increment per event, so it is cheap enough to leave on
+/// in production. On platforms other than Linux no resolution is namespace-aware and
+/// every counter stays at zero.
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// let before = proc_canonicalize::metrics();
+/// proc_canonicalize::canonicalize("/proc/self/root/etc")?;
+/// let after = proc_canonicalize::metrics();
+///
+/// assert!(after.calls() > before.calls());
+/// assert!(after.boundaries() > before.boundaries());
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+pub fn metrics() -> Metrics {
+    #[cfg(target_os = "linux")]
+    {
+        Metrics {
+            calls: CALLS.load(Ordering::Relaxed),
+            boundaries: BOUNDARIES.load(Ordering::Relaxed),
+            escapes: ESCAPES.load(Ordering::Relaxed),
+            loops: LOOPS.load(Ordering::Relaxed),
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Metrics::default()
+    }
+}
+
+/// Add one to `counter`.
+#[cfg(target_os = "linux")]
+pub(crate) fn bump(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
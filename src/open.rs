@@ -0,0 +1,239 @@
+//! Namespace-aware open helpers.
+
+use std::fs::{File, OpenOptions};
+use std::io;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+use std::path::{Component, Path};
+
+/// Canonicalize `path` namespace-aware, then open the result with `options`.
+///
+/// The open goes through the preserved path (e.g. `/proc/1234/root/etc/hosts`), so the
+/// kernel re-enters the process's namespace through the magic link and the returned
+/// [`File`] refers to the file *inside* that namespace. Opening the result of
+/// [`std::fs::canonicalize`] instead would open the host's `/etc/hosts`.
+///
+/// # Residual race
+///
+/// Resolution and open are two steps. A component swapped for a symlink between them is
+/// followed by the open. This narrows the window compared with calling
+/// [`canonicalize`](crate::canonicalize) and opening separately, but it does not close it.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::fs::OpenOptions;
+/// use std::io::Read;
+///
+/// let dir = tempfile::tempdir()?;
+/// std::fs::write(dir.path().join("app.conf"), "port=8080")?;
+/// let through_namespace = format!("/proc/self/root{}/app.conf", dir.path().display());
+///
+/// let mut file = proc_canonicalize::open(&through_namespace, OpenOptions::new().read(true))?;
+/// let mut contents = String::new();
+/// file.read_to_string(&mut contents)?;
+/// assert_eq!(contents, "port=8080");
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Returns any error from [`canonicalize`](crate::canonicalize) (so the path must already
+/// exist) or from [`OpenOptions::open`].
+pub fn open(path: impl AsRef<Path>, options: &OpenOptions) -> io::Result<File> {
+    let canonical = crate::canonicalize(path)?;
+    options.open(canonical)
+}
+
+/// `O_NOFOLLOW` on Linux, where its value depends on the architecture.
+///
+/// Only architectures whose value is known are listed; any other fails to build rather
+/// than open with a flag that may mean something else there.
+#[cfg(all(
+    target_os = "linux",
+    any(
+        target_arch = "aarch64",
+        target_arch = "arm",
+        target_arch = "m68k",
+        target_arch = "powerpc",
+        target_arch = "powerpc64"
+    )
+))]
+const O_NOFOLLOW: i32 = 0o100_000;
+#[cfg(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "riscv32",
+        target_arch = "riscv64",
+        target_arch = "s390x",
+        target_arch = "loongarch64"
+    )
+))]
+const O_NOFOLLOW: i32 = 0o400_000;
+#[cfg(all(
+    target_os = "linux",
+    not(any(
+        target_arch = "aarch64",
+        target_arch = "arm",
+        target_arch = "m68k",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "riscv32",
+        target_arch = "riscv64",
+        target_arch = "s390x",
+        target_arch = "loongarch64"
+    ))
+))]
+compile_error!("O_NOFOLLOW is not known for this Linux architecture; add it to src/open.rs");
+
+/// Canonicalize the parent of `path` namespace-aware, then open its final component
+/// with `options` without following it.
+///
+/// The parent is resolved with [`canonicalize_parent`](crate::canonicalize_parent),
+/// keeping its boundaries, and the final component is opened below it with
+/// `O_NOFOLLOW`. If that component is a symlink, including one swapped in after the
+/// parent was resolved, the open fails instead of following it. Unlike [`open`], the
+/// final component need not exist yet: with [`OpenOptions::create`] it is created
+/// inside the namespace, and a dangling symlink in its place is refused rather than
+/// created through.
+///
+/// # Residual race
+///
+/// Only the final component is protected. The parent is still resolved and opened
+/// in two steps, as with [`open`], so a directory above it swapped for a symlink in
+/// between is followed.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::fs::OpenOptions;
+/// use proc_canonicalize::open_nofollow_last;
+///
+/// let dir = tempfile::tempdir()?;
+/// std::fs::write(dir.path().join("app.conf"), "port=8080")?;
+/// std::os::unix::fs::symlink("/etc/passwd", dir.path().join("planted"))?;
+/// let through_namespace = format!("/proc/self/root{}", dir.path().display());
+///
+/// let mut read = OpenOptions::new();
+/// read.read(true);
+/// assert!(open_nofollow_last(format!("{through_namespace}/app.conf"), &read).is_ok());
+/// assert!(open_nofollow_last(format!("{through_namespace}/planted"), &read).is_err());
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Platform Behavior
+///
+/// - **Linux**: `O_NOFOLLOW` replaces any
+///   [`custom_flags`](std::os::unix::fs::OpenOptionsExt::custom_flags) set on `options`
+/// - **Other platforms**: the final component is checked with
+///   [`symlink_metadata`](std::fs::symlink_metadata) before the open, which leaves a
+///   window for it to be swapped
+///
+/// # Errors
+///
+/// - [`io::ErrorKind::InvalidInput`] if the final component as written is not a file
+///   name: a root, `..`, or `.`, including a trailing one such as `dir/link/.`.
+/// - The `ELOOP` OS error on Linux, and [`io::ErrorKind::InvalidInput`] elsewhere, if
+///   the final component is a symlink.
+/// - Any error [`canonicalize_parent`](crate::canonicalize_parent) returns for `path`,
+///   or [`OpenOptions::open`] returns for the final component.
+pub fn open_nofollow_last(path: impl AsRef<Path>, options: &OpenOptions) -> io::Result<File> {
+    let path = path.as_ref();
+    let name = match path.components().next_back() {
+        Some(Component::Normal(name)) if !ends_in_dot(path) => name,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "final component is not a file name",
+            ))
+        }
+    };
+    let last = crate::canonicalize_parent(path)?.join(name);
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        options.clone().custom_flags(O_NOFOLLOW).open(last)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        if std::fs::symlink_metadata(&last).is_ok_and(|m| m.file_type().is_symlink()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "final component is a symlink",
+            ));
+        }
+        options.open(last)
+    }
+}
+
+/// Whether `path` as written ends in a `.` component.
+///
+/// [`Path::components`] drops a trailing `.`, so `dir/link/.` would otherwise open
+/// `link` itself rather than the directory it points to.
+fn ends_in_dot(path: &Path) -> bool {
+    let written = path.as_os_str().to_string_lossy();
+    let trimmed = written.trim_end_matches(std::path::is_separator);
+    trimmed.rsplit(std::path::is_separator).next() == Some(".")
+}
+
+/// Pin a process's current directory, returning the open directory and its display prefix.
+///
+/// `/proc/PID/cwd` follows the process: after it `chdir`s, the same path names a
+/// different directory. This opens `/proc/PID/cwd` once and returns the resulting
+/// directory handle, which keeps referring to the directory the process was in at
+/// call time, together with the `/proc/PID/cwd` prefix to show users.
+///
+/// Resolve subpaths through the handle rather than the prefix for a consistent view:
+/// `/proc/self/fd/FD/sub/path`, with `FD` from [`AsRawFd`](std::os::unix::io::AsRawFd),
+/// walks the pinned directory even if the process has moved since. The handle stays
+/// inside the process's mount namespace, exactly as `/proc/PID/cwd` does.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::os::unix::io::AsRawFd;
+/// use std::path::Path;
+/// use proc_canonicalize::canonicalize_cwd_snapshot;
+///
+/// let pid = std::process::id();
+/// let (pinned_cwd, prefix) = canonicalize_cwd_snapshot(pid)?;
+/// assert_eq!(prefix, Path::new(&format!("/proc/{pid}/cwd")));
+///
+/// let through_pin = format!("/proc/self/fd/{}", pinned_cwd.as_raw_fd());
+/// assert_eq!(std::fs::canonicalize(through_pin)?, std::env::current_dir()?.canonicalize()?);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Returns [`io::ErrorKind::NotFound`] if the process does not exist and
+/// [`io::ErrorKind::PermissionDenied`] if its cwd cannot be opened (typically another
+/// user's process without `CAP_SYS_PTRACE`).
+#[cfg(target_os = "linux")]
+pub fn canonicalize_cwd_snapshot(pid: u32) -> io::Result<(File, PathBuf)> {
+    let prefix = crate::proc_root::proc_root()
+        .join(pid.to_string())
+        .join("cwd");
+    let pinned = File::open(&prefix)?;
+    Ok((pinned, prefix))
+}
@@ -0,0 +1,74 @@
+//! Memoizing the host paths of boundaries across calls.
+
+#[cfg(target_os = "linux")]
+use crate::resolve::PrefixCache;
+
+use super::CanonicalizeOptions;
+
+impl CanonicalizeOptions {
+    /// Remember the host directory of each `/proc/PID/root` across calls.
+    ///
+    /// Default: `false`, which resolves every boundary afresh.
+    ///
+    /// A scanner resolving thousands of paths under one container otherwise pays a
+    /// `realpath` of the same `/proc/PID/root` on every call. With this option the first
+    /// call stores where the boundary leads and later calls reuse it, until
+    /// [`clear_cache`](Self::clear_cache). Clones of these options share the cache.
+    /// Only `root` boundaries are remembered; a `cwd` changes with every `chdir`.
+    ///
+    /// Each answer is stored with the device and inode numbers of the directory the
+    /// boundary entered, and every call `stat`s the boundary to compare them. A PID
+    /// reused by another process, or a container restarted onto a new root directory,
+    /// shows up as a different directory and is resolved afresh, so the cache does not
+    /// serve a stale answer. That `stat` is the whole cost of a hit, against a full
+    /// `realpath` walk of the container root on a miss; it also makes a PID with no
+    /// process fail as usual. What it cannot see is the same directory being renamed on
+    /// the host: clear the cache if container roots may move.
+    ///
+    /// Answers are keyed by the path the boundary is entered through, which spells out
+    /// the proc root (or the [`stand_in_root`](Self::stand_in_root), when set). So a
+    /// relocated `/host/proc/1/root` and `/proc/1/root` are remembered separately, and
+    /// clones given different stand-ins never see each other's answers.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::Path;
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let memoized = CanonicalizeOptions::new().memoize_boundaries(true);
+    /// for dir in ["etc", "usr", "var"] {
+    ///     let resolved = memoized.canonicalize(Path::new("/proc/self/root").join(dir))?;
+    ///     assert_eq!(resolved, Path::new("/proc/self/root").join(dir));
+    /// }
+    /// memoized.clear_cache();
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn memoize_boundaries(self, memoize: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                prefix_cache: memoize.then(PrefixCache::default),
+                ..self
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = memoize;
+            self
+        }
+    }
+
+    /// Forget every boundary remembered by [`memoize_boundaries`](Self::memoize_boundaries).
+    ///
+    /// Affects every clone sharing the cache. Does nothing when memoization is off.
+    pub fn clear_cache(&self) {
+        #[cfg(target_os = "linux")]
+        if let Some(cache) = &self.prefix_cache {
+            cache.clear();
+        }
+    }
+}
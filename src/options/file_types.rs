@@ -0,0 +1,178 @@
+//! Restricting the kind of file a resolved path may name.
+
+use std::fmt;
+use std::fs::FileType;
+use std::io;
+use std::ops::BitOr;
+use std::path::Path;
+
+use super::CanonicalizeOptions;
+
+/// A set of file types, for [`CanonicalizeOptions::allowed_file_types`].
+///
+/// Built from the constants with `|`: `FileTypeSet::FILE | FileTypeSet::DIR`. There is
+/// no symlink type: a resolved path has no symlinks left to name. A namespace link such
+/// as `/proc/PID/ns/net` reads as a regular [`FILE`](Self::FILE).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FileTypeSet(u8);
+
+impl FileTypeSet {
+    /// Regular files.
+    pub const FILE: Self = FileTypeSet(1 << 0);
+    /// Directories.
+    pub const DIR: Self = FileTypeSet(1 << 1);
+    /// Named pipes.
+    pub const FIFO: Self = FileTypeSet(1 << 2);
+    /// Unix domain sockets.
+    pub const SOCKET: Self = FileTypeSet(1 << 3);
+    /// Character devices, such as `/dev/null`.
+    pub const CHAR_DEVICE: Self = FileTypeSet(1 << 4);
+    /// Block devices, such as `/dev/sda`.
+    pub const BLOCK_DEVICE: Self = FileTypeSet(1 << 5);
+    /// Every type: the default, which checks nothing.
+    pub const ALL: Self = FileTypeSet(0b11_1111);
+
+    /// No types at all.
+    pub const fn empty() -> Self {
+        FileTypeSet(0)
+    }
+
+    /// Whether every type in `other` is in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The single type `file_type` is.
+    fn of(file_type: FileType) -> Self {
+        if file_type.is_dir() {
+            return Self::DIR;
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+
+            if file_type.is_fifo() {
+                return Self::FIFO;
+            }
+            if file_type.is_socket() {
+                return Self::SOCKET;
+            }
+            if file_type.is_char_device() {
+                return Self::CHAR_DEVICE;
+            }
+            if file_type.is_block_device() {
+                return Self::BLOCK_DEVICE;
+            }
+        }
+        Self::FILE
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::DIR => "directory",
+            Self::FIFO => "FIFO",
+            Self::SOCKET => "socket",
+            Self::CHAR_DEVICE => "character device",
+            Self::BLOCK_DEVICE => "block device",
+            _ => "regular file",
+        }
+    }
+}
+
+impl BitOr for FileTypeSet {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        FileTypeSet(self.0 | other.0)
+    }
+}
+
+impl Default for FileTypeSet {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl fmt::Debug for FileTypeSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let members = [
+            Self::FILE,
+            Self::DIR,
+            Self::FIFO,
+            Self::SOCKET,
+            Self::CHAR_DEVICE,
+            Self::BLOCK_DEVICE,
+        ];
+        f.debug_set()
+            .entries(
+                members
+                    .into_iter()
+                    .filter(|member| self.contains(*member))
+                    .map(Self::name),
+            )
+            .finish()
+    }
+}
+
+impl CanonicalizeOptions {
+    /// Fail with [`io::ErrorKind::InvalidInput`] unless the result is one of `types`.
+    ///
+    /// Default: [`FileTypeSet::ALL`], which checks nothing and costs nothing.
+    ///
+    /// For a sandbox that hands out only regular files and directories, so a path that
+    /// resolves to a device, socket or FIFO is refused along with resolution instead of
+    /// in a separate step:
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use proc_canonicalize::{CanonicalizeOptions, FileTypeSet};
+    ///
+    /// let plain = CanonicalizeOptions::new()
+    ///     .allowed_file_types(FileTypeSet::FILE | FileTypeSet::DIR);
+    ///
+    /// assert!(plain.canonicalize("/proc/self/root/etc").is_ok());
+    /// let err = plain.canonicalize("/proc/self/root/dev/null").unwrap_err();
+    /// assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// The type is read with one `stat` of the result, through its boundary, once
+    /// resolution is done. The file can still be replaced after that `stat`; if that
+    /// matters, check [`File::metadata`](std::fs::File::metadata) of what you open as
+    /// well. Under [`allow_missing`](Self::allow_missing), a result that does not
+    /// exist has no type and is returned as usual. Elsewhere than Unix, only
+    /// [`FILE`](FileTypeSet::FILE) and [`DIR`](FileTypeSet::DIR) are told apart.
+    pub fn allowed_file_types(self, types: FileTypeSet) -> Self {
+        Self {
+            allowed_file_types: types,
+            ..self
+        }
+    }
+
+    /// Fails if `resolved` names a file type these options do not allow.
+    pub(super) fn check_file_type(&self, resolved: &Path) -> io::Result<()> {
+        if self.allowed_file_types == FileTypeSet::ALL {
+            return Ok(());
+        }
+        let found = match std::fs::metadata(resolved) {
+            Ok(metadata) => FileTypeSet::of(metadata.file_type()),
+            #[cfg(target_os = "linux")]
+            Err(err) if err.kind() == io::ErrorKind::NotFound && self.allows_missing() => {
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        };
+        if self.allowed_file_types.contains(found) {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("resolved to a {}, which is not allowed", found.name()),
+            ))
+        }
+    }
+}
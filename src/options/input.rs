@@ -0,0 +1,144 @@
+//! Options vetting and anchoring input before anything is resolved.
+
+use std::borrow::Cow;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use super::CanonicalizeOptions;
+
+impl CanonicalizeOptions {
+    /// Reject relative paths whose `..` climbs above the directory they start from,
+    /// with [`io::ErrorKind::InvalidInput`].
+    ///
+    /// Default: `false`, which joins them onto the current directory like any other
+    /// relative path.
+    ///
+    /// Where `../../proc/self/root` leads depends on the current directory, and in a
+    /// request it is usually a traversal attempt. With this option such a path fails
+    /// before the current directory is read or anything is looked up. The depth is
+    /// tracked through the whole path as [`checked_join`](crate::checked_join) does, so
+    /// `./../etc` and `logs/../../etc` are rejected too, while `logs/../etc`, which
+    /// stays below its start, and absolute paths are resolved as usual. The check is
+    /// lexical: a symlink in the path may still lead elsewhere once resolved.
+    ///
+    /// ```rust
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let strict = CanonicalizeOptions::new().reject_leading_dotdot(true);
+    /// let err = strict.canonicalize("../../proc/self/root").unwrap_err();
+    /// assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    /// ```
+    pub fn reject_leading_dotdot(self, reject: bool) -> Self {
+        Self {
+            reject_leading_dotdot: reject,
+            ..self
+        }
+    }
+
+    /// Resolve relative paths against `base` instead of the current directory.
+    ///
+    /// Default: unset, which joins them onto the current directory.
+    ///
+    /// A long-running daemon whose working directory was deleted cannot read it any
+    /// more, so every relative path fails with a [`CwdUnavailable`](crate::CwdUnavailable)
+    /// payload. With a base the current directory is never consulted: `etc/hostname` is
+    /// resolved as `base/etc/hostname`, with the same boundary handling as if it had been
+    /// written out in full, so a base of `/proc/PID/root` resolves relative paths inside
+    /// that process's root. Absolute paths are unaffected. `base` should itself be
+    /// absolute; a relative base is joined onto the current directory like any relative
+    /// path.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::Path;
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let inside = CanonicalizeOptions::new().base_dir("/proc/self/root");
+    /// assert_eq!(inside.canonicalize("etc")?, Path::new("/proc/self/root/etc"));
+    /// assert_eq!(inside.canonicalize("/etc")?, std::fs::canonicalize("/etc")?);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn base_dir(self, base: impl Into<PathBuf>) -> Self {
+        Self {
+            base_dir: Some(base.into()),
+            ..self
+        }
+    }
+
+    /// Fail on input these options reject outright.
+    pub(super) fn check_input(&self, path: &Path) -> io::Result<()> {
+        if self.reject_leading_dotdot && climbs_above_start(path) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "relative path climbs above its starting directory with `..`",
+            ));
+        }
+        Ok(())
+    }
+
+    /// `path`, joined onto [`base_dir`](Self::base_dir) when it is relative and a base
+    /// is set. The empty path is left alone, so it still fails rather than naming the
+    /// base.
+    pub(super) fn anchor<'p>(&self, path: &'p Path) -> Cow<'p, Path> {
+        match &self.base_dir {
+            Some(base) if path.is_relative() && !path.as_os_str().is_empty() => {
+                Cow::Owned(base.join(path))
+            }
+            _ => Cow::Borrowed(path),
+        }
+    }
+}
+
+/// Whether a `..` in the relative `path` climbs above the directory it starts from.
+fn climbs_above_start(path: &Path) -> bool {
+    let mut depth = 0usize;
+    for component in path.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => match depth.checked_sub(1) {
+                Some(up) => depth = up,
+                None => return true,
+            },
+            Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_parent_components_above_the_start_count() {
+        for path in [
+            "..",
+            "../x",
+            "./..",
+            "./../x",
+            "x/../..",
+            "a/../../x",
+            "a/b/../../..",
+        ] {
+            assert!(climbs_above_start(Path::new(path)), "{path}");
+        }
+        for path in [
+            "",
+            ".",
+            "..hidden",
+            "x/..",
+            "a/b/../..",
+            "/..",
+            "/../x",
+            "...",
+            ".x/..",
+        ] {
+            assert!(!climbs_above_start(Path::new(path)), "{path}");
+        }
+    }
+}
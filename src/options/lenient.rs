@@ -0,0 +1,165 @@
+//! Options that return a path without resolving all of it: past missing components,
+//! past a denied boundary, or without crossing the boundary at all.
+
+use super::CanonicalizeOptions;
+
+impl CanonicalizeOptions {
+    /// Resolve paths that do not exist, like `realpath -m`.
+    ///
+    /// Default: `false`, which fails with [`NotFound`](std::io::ErrorKind::NotFound) as
+    /// [`std::fs::canonicalize`] does.
+    ///
+    /// The existing part of the path is resolved as usual and the missing rest is
+    /// appended, with `.` and `..` applied lexically. A dangling symlink is followed to
+    /// wherever it points, so `/proc/PID/root/etc/broken` reports its target inside the
+    /// namespace. Below a boundary the missing part is resolved as under
+    /// [`EscapeBehavior::ClampToRoot`](crate::EscapeBehavior::ClampToRoot): `..` stops at the boundary and an absolute
+    /// target restarts at it, the way the process itself would read the link, so the
+    /// answer keeps the boundary. The boundary itself must still exist. Only missing
+    /// components are tolerated; a file used as a directory still fails.
+    ///
+    /// Has no effect on platforms other than Linux.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::Path;
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let lenient = CanonicalizeOptions::new().allow_missing(true);
+    /// assert_eq!(
+    ///     lenient.canonicalize("/proc/self/root/no/such/dir/../file")?,
+    ///     Path::new("/proc/self/root/no/such/file"),
+    /// );
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn allow_missing(self, allow: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                allow_missing: allow,
+                ..self
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = allow;
+            self
+        }
+    }
+
+    /// Return the preserved path, folded lexically, when its boundary cannot be
+    /// accessed for lack of permission.
+    ///
+    /// Default: `false`, which fails with [`PermissionDenied`](std::io::ErrorKind::PermissionDenied)
+    /// as [`std::fs::canonicalize`] does.
+    ///
+    /// Reading another user's `/proc/PID/root` needs privileges the caller may not have
+    /// yet, e.g. when the path is collected here and opened later by a privileged
+    /// helper. With `true`, a boundary that exists but is denied gives back the input
+    /// as [`canonicalize_lexical`](crate::canonicalize_lexical) would fold it: `.` and
+    /// `..` applied without looking at the disk, `..` stopping at the boundary, and no
+    /// trailing separator. Nothing below the boundary is checked, so the result may name
+    /// a file that does not exist, and symlinks in it are unresolved: resolve it again
+    /// once it is accessible.
+    ///
+    /// Only a denied boundary qualifies. A missing process still fails, as does a
+    /// component below an accessible boundary that is itself denied. The result is the
+    /// preserved form even under [`resolve_outer_to_host`](Self::resolve_outer_to_host)
+    /// and [`follow_terminal_boundary`](Self::follow_terminal_boundary), since the host
+    /// path behind the boundary cannot be read.
+    ///
+    /// Has no effect on platforms other than Linux.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::Path;
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let deferred = CanonicalizeOptions::new().preserve_on_inaccessible_boundary(true);
+    /// if std::fs::metadata("/proc/1/root").is_err() {
+    ///     // Not privileged: PID 1's root is denied, and the input comes back folded.
+    ///     assert_eq!(
+    ///         deferred.canonicalize("/proc/1/root/etc/../srv/")?,
+    ///         Path::new("/proc/1/root/srv"),
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn preserve_on_inaccessible_boundary(self, preserve: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                preserve_on_inaccessible_boundary: preserve,
+                ..self
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = preserve;
+            self
+        }
+    }
+
+    /// Do not follow a boundary's magic link: check that it exists and append the rest
+    /// of the path lexically.
+    ///
+    /// Default: `false`, which resolves the whole path through the boundary.
+    ///
+    /// Resolving `/proc/PID/root/etc` walks into the process's mount namespace, which
+    /// may be slow (a hung FUSE or NFS mount inside the container) or forbidden by
+    /// policy. With `true`, only the magic link itself is `lstat`ed, without following
+    /// it, and the result is the input as
+    /// [`canonicalize_lexical`](crate::canonicalize_lexical) folds it: `.` and `..`
+    /// applied without looking at the disk, `..` stopping at the boundary, and no
+    /// trailing separator. That is a well-formed preserved path to hand to a helper
+    /// that may cross. Since the link is never followed, this also works on another
+    /// user's process, whose `root` an unprivileged caller may `lstat` but not enter.
+    ///
+    /// **The remainder is not verified.** It may not exist, may be a file used as a
+    /// directory, and may contain symlinks, including ones that lead out of the
+    /// namespace: treat the result as a name, not as a checked location. Paths without
+    /// a boundary, and the part of a path before it, are resolved as usual; a symlink
+    /// into `/proc` is followed up to the boundary it reaches. The result keeps the
+    /// boundary even under [`resolve_outer_to_host`](Self::resolve_outer_to_host) and
+    /// [`follow_terminal_boundary`](Self::follow_terminal_boundary), whose host paths
+    /// would need the link followed.
+    ///
+    /// Has no effect on platforms other than Linux.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::Path;
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let outside = CanonicalizeOptions::new().no_cross(true);
+    /// assert_eq!(
+    ///     outside.canonicalize("/proc/self/root/no/such/../file")?,
+    ///     Path::new("/proc/self/root/no/file"),
+    /// );
+    /// assert!(outside.canonicalize("/proc/4294967294/root/etc").is_err()); // no such process
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn no_cross(self, no_cross: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self { no_cross, ..self }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = no_cross;
+            self
+        }
+    }
+}
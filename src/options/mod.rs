@@ -0,0 +1,519 @@
+//! [`CanonicalizeOptions`]: opt-in variations on [`canonicalize`](crate::canonicalize).
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+#[cfg(target_os = "linux")]
+use crate::fs::ReadLinkHook;
+#[cfg(target_os = "linux")]
+use crate::resolve::PrefixCache;
+use crate::EscapeBehavior;
+#[cfg(target_os = "linux")]
+use crate::ProcessIdentity;
+
+mod cache;
+mod file_types;
+mod input;
+mod lenient;
+mod naming;
+mod pids;
+mod security;
+
+pub use file_types::FileTypeSet;
+
+#[cfg(target_os = "linux")]
+pub(crate) use pids::{names_pid_zero, PidFilter};
+
+/// Opt-in variations on [`canonicalize`](crate::canonicalize).
+///
+/// Every option defaults to the behavior of [`canonicalize`](crate::canonicalize), so
+/// `CanonicalizeOptions::new().canonicalize(path)` is identical to `canonicalize(path)`.
+/// Options are set with chained builder methods:
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::CanonicalizeOptions;
+///
+/// let options = CanonicalizeOptions::new().resolve_outer_to_host(true);
+/// assert_eq!(options.canonicalize("/proc/self/root/etc")?, Path::new("/etc"));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CanonicalizeOptions {
+    // Only the Linux resolver reads these; elsewhere there are no boundaries to act on.
+    #[cfg(target_os = "linux")]
+    resolve_outer_to_host: bool,
+    #[cfg(target_os = "linux")]
+    escape_behavior: EscapeBehavior,
+    #[cfg(target_os = "linux")]
+    process_identity: Option<ProcessIdentity>,
+    #[cfg(target_os = "linux")]
+    stand_in_root: Option<PathBuf>,
+    #[cfg(target_os = "linux")]
+    read_link: Option<ReadLinkHook>,
+    #[cfg(target_os = "linux")]
+    pin_thread_self: bool,
+    #[cfg(target_os = "linux")]
+    expand_self: bool,
+    #[cfg(target_os = "linux")]
+    follow_terminal_boundary: bool,
+    #[cfg(target_os = "linux")]
+    preserve_unknown_proc_links: bool,
+    #[cfg(target_os = "linux")]
+    allow_missing: bool,
+    #[cfg(target_os = "linux")]
+    preserve_on_inaccessible_boundary: bool,
+    #[cfg(target_os = "linux")]
+    no_cross: bool,
+    #[cfg(target_os = "linux")]
+    max_symlinks_per_component: Option<u32>,
+    #[cfg(target_os = "linux")]
+    pid_filter: Option<PidFilter>,
+    #[cfg(target_os = "linux")]
+    reject_pid_zero: bool,
+    #[cfg(target_os = "linux")]
+    prefix_cache: Option<PrefixCache>,
+    reject_leading_dotdot: bool,
+    sanitize_errors: bool,
+    allowed_file_types: FileTypeSet,
+    base_dir: Option<PathBuf>,
+    deadline: Option<Instant>,
+}
+
+impl CanonicalizeOptions {
+    /// Options matching [`canonicalize`](crate::canonicalize) exactly.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the outermost namespace boundary with its host path, preserving deeper ones.
+    ///
+    /// Default: `false`.
+    ///
+    /// When `true`, the first boundary in the path (e.g. `/proc/1234/root`) is resolved
+    /// to the directory it points at on the host, exactly as [`std::fs::canonicalize`]
+    /// would. The remainder is then resolved from that host directory with boundaries
+    /// preserved as usual, so any `/proc/PID/{root,cwd}` the remainder reaches — directly
+    /// or through a symlink — keeps its prefix.
+    ///
+    /// | Input                                   | Result (host `/proc/self/root` is `/`) |
+    /// | --------------------------------------- | -------------------------------------- |
+    /// | `/proc/self/root/etc`                   | `/etc`                                 |
+    /// | `/proc/self/root`                       | `/`                                    |
+    /// | `/proc/self/root/proc/self/cwd/src`     | `/proc/self/cwd/src`                   |
+    /// | `/tmp/link/etc`, `link -> /proc/self/root` | `/etc`                              |
+    ///
+    /// Useful when bridging a path from a tool that wants host paths to one that wants
+    /// namespace paths. Namespace links (`/proc/PID/ns/TYPE`) have no host path; resolving
+    /// one as the outer boundary fails with the error [`std::fs::canonicalize`] returns.
+    pub fn resolve_outer_to_host(self, resolve: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                resolve_outer_to_host: resolve,
+                ..self
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = resolve;
+            self
+        }
+    }
+
+    /// Choose what happens when a path resolves outside the boundary it entered through.
+    ///
+    /// Default: [`EscapeBehavior::ReturnHost`], the true host path.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::Path;
+    /// use proc_canonicalize::{CanonicalizeOptions, EscapeBehavior};
+    ///
+    /// // The cwd's parent is outside /proc/self/cwd
+    /// let clamped = CanonicalizeOptions::new().escape_behavior(EscapeBehavior::ClampToRoot);
+    /// assert_eq!(clamped.canonicalize("/proc/self/cwd/..")?, Path::new("/proc/self/cwd"));
+    ///
+    /// let strict = CanonicalizeOptions::new().escape_behavior(EscapeBehavior::Error);
+    /// assert!(strict.canonicalize("/proc/self/cwd/..").is_err());
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn escape_behavior(self, behavior: EscapeBehavior) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                escape_behavior: behavior,
+                ..self
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = behavior;
+            self
+        }
+    }
+
+    /// Fail instead of resolving if `identity`'s PID now belongs to a different process.
+    ///
+    /// Default: unset. Linux only.
+    ///
+    /// Every boundary naming the PID numerically (`/proc/PID/root`, `/proc/PID/task/TID/cwd`,
+    /// ...) has its start time compared against `identity` before it is resolved and again
+    /// after, so a PID recycled at any point during the call is detected. On mismatch the
+    /// call fails with an [`io::ErrorKind::Other`] error carrying a
+    /// [`ProcessReused`](crate::ProcessReused) payload. `/proc/self` and boundaries under
+    /// other PIDs are not checked.
+    ///
+    /// This narrows the window for long-lived monitors that hold a PID across calls; it
+    /// cannot close it for whoever *uses* the returned path afterwards.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use proc_canonicalize::{CanonicalizeOptions, ProcessIdentity, ProcessReused};
+    ///
+    /// let pid = std::process::id();
+    /// let options = CanonicalizeOptions::new().verify_process(ProcessIdentity::capture(pid)?);
+    /// assert!(options.canonicalize(format!("/proc/{pid}/root/etc")).is_ok());
+    ///
+    /// // A start time that no longer matches, as after the PID was recycled
+    /// let stale = ProcessIdentity::from_parts(pid, 0);
+    /// let options = CanonicalizeOptions::new().verify_process(stale);
+    /// let err = options.canonicalize(format!("/proc/{pid}/root/etc")).unwrap_err();
+    /// assert!(err.get_ref().and_then(|e| e.downcast_ref::<ProcessReused>()).is_some());
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    #[cfg(target_os = "linux")]
+    pub fn verify_process(mut self, identity: ProcessIdentity) -> Self {
+        self.process_identity = Some(identity);
+        self
+    }
+
+    /// Resolve every boundary through `dir` instead of through the process's magic link.
+    ///
+    /// Default: unset. Linux only.
+    ///
+    /// Each `/proc/PID/root` (or `cwd`) the path reaches is treated as if it pointed at
+    /// `dir`: the subpath is resolved inside `dir`, and the result carries the boundary
+    /// prefix from the input exactly as an online [`canonicalize`](crate::canonicalize)
+    /// would. The process does not need to exist, so container paths can be computed
+    /// offline against an extracted image directory, with whatever `/proc/<pid>/root`
+    /// prefix the online code will later use.
+    ///
+    /// Escapes out of `dir` still return the host path (or follow
+    /// [`escape_behavior`](Self::escape_behavior)). Images are full of absolute symlinks
+    /// meant for their own root; pair this with [`EscapeBehavior::ClampToRoot`] to read
+    /// them that way, as below. The stand-in replaces *every*
+    /// boundary the path reaches, including ones reached through symlinks inside `dir`.
+    /// [`verify_process`](Self::verify_process) still reads the real `/proc`.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::Path;
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let image = tempfile::tempdir()?;
+    /// std::fs::create_dir_all(image.path().join("usr/share/nginx"))?;
+    /// std::os::unix::fs::symlink("/usr/share/nginx", image.path().join("www"))?;
+    ///
+    /// let offline = CanonicalizeOptions::new()
+    ///     .stand_in_root(image.path())
+    ///     .escape_behavior(proc_canonicalize::EscapeBehavior::ClampToRoot);
+    /// assert_eq!(
+    ///     offline.canonicalize("/proc/4242/root/www")?,
+    ///     Path::new("/proc/4242/root/usr/share/nginx"),
+    /// );
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn stand_in_root(self, dir: impl AsRef<Path>) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                stand_in_root: Some(dir.as_ref().to_path_buf()),
+                ..self
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = dir;
+            self
+        }
+    }
+
+    /// Read symlinks through `read_link` instead of [`std::fs::read_link`].
+    ///
+    /// Default: unset. For sandboxes where link reads must go through a broker.
+    ///
+    /// The function is called wherever the resolver itself reads a link: the walk that
+    /// looks for symlinks leading into `/proc`, and the clamped walk of
+    /// [`EscapeBehavior::ClampToRoot`]. It must behave like `read_link`, returning the
+    /// raw target. Existence checks and the final resolution by the kernel still go
+    /// through `std::fs`, so the broker decides where the walk goes, not what exists.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::{Path, PathBuf};
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let dir = tempfile::tempdir()?;
+    /// std::os::unix::fs::symlink("/proc/self/root", dir.path().join("container"))?;
+    ///
+    /// let brokered = CanonicalizeOptions::new().read_link_with(|path: &Path| -> std::io::Result<PathBuf> {
+    ///     // e.g. an IPC round-trip to a privileged helper
+    ///     std::fs::read_link(path)
+    /// });
+    /// assert_eq!(
+    ///     brokered.canonicalize(dir.path().join("container"))?,
+    ///     Path::new("/proc/self/root"),
+    /// );
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn read_link_with(
+        self,
+        read_link: impl Fn(&Path) -> io::Result<PathBuf> + Send + Sync + 'static,
+    ) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                read_link: Some(ReadLinkHook::new(read_link)),
+                ..self
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = read_link;
+            self
+        }
+    }
+
+    /// Fail with `ELOOP` once resolving any one component follows more than `limit`
+    /// symlinks.
+    ///
+    /// Default: unset, leaving only the overall limit of 40 symlinks per path.
+    ///
+    /// A component's hops are every link followed before the walk gets past it: a chain
+    /// `a -> b -> c` costs `a` two, including links inside a target such as
+    /// `a -> b/c` with `b` a link. Each component has its own budget, so one hostile
+    /// component cannot use up the whole path's allowance. `0` accepts no symlinks at
+    /// all. Counted while looking for symlinks into `/proc`, which covers a path up
+    /// to its first boundary, or all of it when it has none; the remainder below a
+    /// boundary is resolved by the kernel with only the overall limit.
+    ///
+    /// Has no effect on platforms other than Linux.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::os::unix::fs::symlink;
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let dir = tempfile::tempdir()?;
+    /// symlink("hop2", dir.path().join("hop1"))?;
+    /// symlink(".", dir.path().join("hop2"))?;
+    ///
+    /// let capped = CanonicalizeOptions::new().max_symlinks_per_component(1);
+    /// let err = capped.canonicalize(dir.path().join("hop1")).unwrap_err();
+    /// assert_eq!(err.raw_os_error(), Some(40)); // ELOOP
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn max_symlinks_per_component(self, limit: u32) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                max_symlinks_per_component: Some(limit),
+                ..self
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = limit;
+            self
+        }
+    }
+
+    /// Give up with [`io::ErrorKind::TimedOut`] once `deadline` has passed.
+    ///
+    /// Default: unset. Bounds the wall-clock time a hostile symlink topology can cost a
+    /// request handler, on top of the 40-link limit. The deadline is checked before
+    /// every filesystem operation the resolver starts; an operation already running
+    /// (e.g. a slow network mount) is not interrupted, so a call can overrun by one
+    /// syscall. On platforms other than Linux the deadline is checked once, before the
+    /// single call to [`std::fs::canonicalize`].
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::time::{Duration, Instant};
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let budget = CanonicalizeOptions::new().deadline(Instant::now() + Duration::from_secs(1));
+    /// assert!(budget.canonicalize("/proc/self/root/etc").is_ok());
+    ///
+    /// let expired = CanonicalizeOptions::new().deadline(Instant::now());
+    /// let err = expired.canonicalize("/proc/self/root/etc").unwrap_err();
+    /// assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn deadline(self, deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    /// Canonicalize `path` with these options.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`canonicalize`](crate::canonicalize), plus
+    /// [`io::ErrorKind::InvalidInput`] for a path rejected by
+    /// [`reject_leading_dotdot`](Self::reject_leading_dotdot) or a result excluded by
+    /// [`allowed_file_types`](Self::allowed_file_types). On Linux, a relative
+    /// path whose current directory cannot be read fails with a
+    /// [`CwdUnavailable`](crate::CwdUnavailable) payload, unless
+    /// [`base_dir`](Self::base_dir) is set. With
+    /// [`sanitize_errors`](Self::sanitize_errors), errors are replaced as described there.
+    #[must_use = "with EscapeBehavior::ReturnHost a boundary path may resolve to a host path"]
+    pub fn canonicalize(&self, path: impl AsRef<Path>) -> io::Result<PathBuf> {
+        self.resolve(path.as_ref())
+            .and_then(|resolved| {
+                self.check_file_type(&resolved)?;
+                Ok(resolved)
+            })
+            .map_err(|err| self.report(err))
+    }
+
+    fn resolve(&self, path: &Path) -> io::Result<PathBuf> {
+        self.check_input(path)?;
+        let path = self.anchor(path);
+        #[cfg(target_os = "linux")]
+        {
+            crate::resolve::Resolver::new(self).canonicalize(path.as_ref())
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            if self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "path resolution exceeded its deadline",
+                ));
+            }
+            crate::canonicalize(path)
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn resolves_outer_to_host(&self) -> bool {
+        self.resolve_outer_to_host
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn escape(&self) -> EscapeBehavior {
+        self.escape_behavior
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn process_identity(&self) -> Option<&ProcessIdentity> {
+        self.process_identity.as_ref()
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn stand_in(&self) -> Option<&Path> {
+        self.stand_in_root.as_deref()
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn pins_thread_self(&self) -> bool {
+        self.pin_thread_self
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn expands_self(&self) -> bool {
+        self.expand_self
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn follows_terminal_boundary(&self) -> bool {
+        self.follow_terminal_boundary
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn preserves_unknown_proc_links(&self) -> bool {
+        self.preserve_unknown_proc_links
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn allows_missing(&self) -> bool {
+        self.allow_missing
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn preserves_on_inaccessible_boundary(&self) -> bool {
+        self.preserve_on_inaccessible_boundary
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn crosses_boundaries(&self) -> bool {
+        !self.no_cross
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn symlinks_per_component(&self) -> Option<u32> {
+        self.max_symlinks_per_component
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn pid_filter(&self) -> Option<&PidFilter> {
+        self.pid_filter.as_ref()
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn rejects_pid_zero(&self) -> bool {
+        self.reject_pid_zero
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn deadline_at(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn prefix_cache(&self) -> Option<&PrefixCache> {
+        self.prefix_cache.as_ref()
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn read_link_hook(&self) -> Option<&ReadLinkHook> {
+        self.read_link.as_ref()
+    }
+}
@@ -0,0 +1,183 @@
+//! Options controlling how the result names its boundary: `self`, `thread-self`, IDs,
+//! or the host directory behind it.
+
+use super::CanonicalizeOptions;
+
+impl CanonicalizeOptions {
+    /// Expand `/proc/thread-self` into `/proc/PID/task/TID` for the calling thread.
+    ///
+    /// Default: `false`, which keeps `/proc/thread-self` verbatim.
+    ///
+    /// `/proc/thread-self` means whichever thread accesses it, so a preserved
+    /// `/proc/thread-self/root/...` names a different thread's view once handed to
+    /// another thread. With this option the calling thread's ID is read once, at call
+    /// time, and the result names that thread explicitly wherever it is used. Applies to
+    /// boundaries reached directly and through symlinks.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::Path;
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let me = Path::new("/proc").join(std::fs::read_link("/proc/thread-self")?);
+    ///
+    /// let pinned = CanonicalizeOptions::new().pin_thread_self(true);
+    /// assert_eq!(pinned.canonicalize("/proc/thread-self/root")?, me.join("root"));
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn pin_thread_self(self, pin: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                pin_thread_self: pin,
+                ..self
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = pin;
+            self
+        }
+    }
+
+    /// Name this process by PID: `/proc/self` becomes `/proc/PID`.
+    ///
+    /// Default: `false`, which keeps `self` and `thread-self` verbatim.
+    ///
+    /// `/proc/self/root/etc` is exact while this process runs but means some other
+    /// process once logged or handed on. With this option the boundary is rewritten to
+    /// `/proc/PID` using this process's ID, and `/proc/thread-self` to
+    /// `/proc/PID/task/TID` as with [`pin_thread_self`](Self::pin_thread_self). The
+    /// result names the same directory and canonicalizes to itself. Applies to boundaries
+    /// reached directly and through symlinks.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::Path;
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let expanded = CanonicalizeOptions::new().expand_self(true);
+    /// let pid = std::process::id();
+    /// assert_eq!(
+    ///     expanded.canonicalize("/proc/self/root/etc")?,
+    ///     Path::new(&format!("/proc/{pid}/root/etc")),
+    /// );
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn expand_self(self, expand: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                expand_self: expand,
+                ..self
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = expand;
+            self
+        }
+    }
+
+    /// Return a path that resolves to exactly a boundary as the host directory behind it.
+    ///
+    /// Default: `false`, which keeps `/proc/PID/root` itself: the kernel would replace
+    /// the link with its target, losing the boundary.
+    ///
+    /// With this option `/proc/1234/root` comes back as the host path of that process's
+    /// root, e.g. `/var/lib/containers/abc/rootfs`, as the kernel resolves it. Applies
+    /// to every input ending on a boundary: the bare link, `/proc/1234/root/etc/..`, or a
+    /// symlink to it. Paths below a boundary keep it; to drop those too, use
+    /// [`resolve_outer_to_host`](Self::resolve_outer_to_host). Namespace links
+    /// (`/proc/PID/ns/TYPE`) have no host directory and are kept.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::Path;
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let follow = CanonicalizeOptions::new().follow_terminal_boundary(true);
+    /// assert_eq!(follow.canonicalize("/proc/self/root")?, Path::new("/"));
+    /// assert_eq!(follow.canonicalize("/proc/self/root/etc")?, Path::new("/proc/self/root/etc"));
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn follow_terminal_boundary(self, follow: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                follow_terminal_boundary: follow,
+                ..self
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = follow;
+            self
+        }
+    }
+
+    /// Preserve `/proc/PID/NAME` for any per-process magic link `NAME`, not only `root`,
+    /// `cwd` and `ns/TYPE`.
+    ///
+    /// Default: `false`. Links such as `/proc/PID/exe` are followed like any symlink,
+    /// which reads the target as a path in the process's namespace and then looks it
+    /// up in the caller's, so the result may be a different file or none at all.
+    ///
+    /// With `true`, a path that is exactly one entry of `/proc/PID` or
+    /// `/proc/PID/task/TID`, and that entry is a symlink, is returned as it is: the
+    /// kernel resolves it across namespaces when it is opened, as it does for
+    /// `root`. This covers links the kernel adds later without a release of this
+    /// crate. Nothing may follow the link: `/proc/PID/exe/` and longer paths, and
+    /// entries such as `/proc/PID/fd/3` that sit one directory lower, are resolved as
+    /// usual. [`allowed_pids`](Self::allowed_pids), [`reject_pid_zero`](Self::reject_pid_zero)
+    /// and [`expand_self`](Self::expand_self) apply as they do to boundaries.
+    ///
+    /// The trade-off is over-preservation: every symlink in `/proc/PID` qualifies,
+    /// whether or not the kernel treats it as magic, and the path is not checked
+    /// further than one `lstat`. Only a path naming the link itself is recognized, not
+    /// a symlink elsewhere pointing to it.
+    ///
+    /// Has no effect on platforms other than Linux.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::Path;
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let generic = CanonicalizeOptions::new().preserve_unknown_proc_links(true);
+    /// assert_eq!(generic.canonicalize("/proc/self/exe")?, Path::new("/proc/self/exe"));
+    /// // A regular file, not a link: resolved as usual.
+    /// assert_ne!(generic.canonicalize("/proc/self/status")?, Path::new("/proc/self/status"));
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn preserve_unknown_proc_links(self, preserve: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                preserve_unknown_proc_links: preserve,
+                ..self
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = preserve;
+            self
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//! Options restricting which processes' boundaries are preserved.
+
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+use super::CanonicalizeOptions;
+#[cfg(target_os = "linux")]
+use crate::lexical::{parse_boundary, PidSelector};
+
+/// Which `/proc/<selector>` boundaries are preserved; the rest are flattened.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone)]
+pub(crate) struct PidFilter {
+    /// Numeric PIDs preserved; `None` preserves every PID.
+    pids: Option<Vec<u32>>,
+    allow_self: bool,
+    allow_thread_self: bool,
+}
+
+#[cfg(target_os = "linux")]
+impl Default for PidFilter {
+    fn default() -> Self {
+        PidFilter {
+            pids: None,
+            allow_self: true,
+            allow_thread_self: true,
+        }
+    }
+}
+
+/// Whether the boundary `namespace_prefix` is under PID 0, however it is spelled.
+#[cfg(target_os = "linux")]
+pub(crate) fn names_pid_zero(namespace_prefix: &Path) -> bool {
+    parse_boundary(namespace_prefix).is_some_and(|boundary| match boundary.pid() {
+        PidSelector::Numeric(pid) => pid.as_str().bytes().all(|digit| digit == b'0'),
+        _ => false,
+    })
+}
+
+#[cfg(target_os = "linux")]
+impl PidFilter {
+    /// Whether the boundary `namespace_prefix` is one to preserve.
+    pub(crate) fn admits(&self, namespace_prefix: &Path) -> bool {
+        let boundary = match parse_boundary(namespace_prefix) {
+            Some(boundary) => boundary,
+            None => return false,
+        };
+        match boundary.pid() {
+            PidSelector::SelfProcess => self.allow_self,
+            PidSelector::ThreadSelf => self.allow_thread_self,
+            // Compared by value, so `/proc/0042` counts as PID 42.
+            PidSelector::Numeric(_) => match &self.pids {
+                None => true,
+                Some(pids) => boundary.pid_u32().is_some_and(|pid| pids.contains(&pid)),
+            },
+        }
+    }
+}
+
+impl CanonicalizeOptions {
+    /// Preserve `/proc/PID` boundaries only for these PIDs.
+    ///
+    /// Default: every PID's boundaries are preserved.
+    ///
+    /// A boundary under any other numeric PID is not treated as one: the whole path is
+    /// handed to the kernel, which follows the magic link, as
+    /// [`std::fs::canonicalize`] would. So `/proc/999/root/etc` comes back as the host
+    /// path of that process's `etc`, and an attacker-chosen PID cannot pin a path to its
+    /// namespace. The PID of a task boundary (`/proc/PID/task/TID/root`) decides for it.
+    /// `/proc/self` and `/proc/thread-self` are not affected; see
+    /// [`allow_self`](Self::allow_self) and [`allow_thread_self`](Self::allow_thread_self).
+    ///
+    /// Has no effect on platforms other than Linux.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::Path;
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let only_self = CanonicalizeOptions::new().allowed_pids(&[]);
+    /// let me = format!("/proc/{}/root/etc", std::process::id());
+    ///
+    /// assert_eq!(only_self.canonicalize("/proc/self/root/etc")?, Path::new("/proc/self/root/etc"));
+    /// assert_eq!(only_self.canonicalize(me)?, std::fs::canonicalize("/etc")?);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn allowed_pids(self, pids: &[u32]) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            self.with_pid_filter(|filter| filter.pids = Some(pids.to_vec()))
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = pids;
+            self
+        }
+    }
+
+    /// Whether `/proc/self` boundaries are preserved.
+    ///
+    /// Default: `true`. With `false`, `/proc/self/root/...` is resolved by the kernel
+    /// like [`allowed_pids`](Self::allowed_pids) resolves PIDs not on its list.
+    ///
+    /// Has no effect on platforms other than Linux.
+    pub fn allow_self(self, allow: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            self.with_pid_filter(|filter| filter.allow_self = allow)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = allow;
+            self
+        }
+    }
+
+    /// Whether `/proc/thread-self` boundaries are preserved.
+    ///
+    /// Default: `true`. With `false`, `/proc/thread-self/root/...` is resolved by the
+    /// kernel like [`allowed_pids`](Self::allowed_pids) resolves PIDs not on its list.
+    ///
+    /// Has no effect on platforms other than Linux.
+    pub fn allow_thread_self(self, allow: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            self.with_pid_filter(|filter| filter.allow_thread_self = allow)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = allow;
+            self
+        }
+    }
+
+    /// Reject boundaries under PID 0 with
+    /// [`ErrorKind::InvalidInput`](std::io::ErrorKind::InvalidInput).
+    ///
+    /// Default: `false`. `/proc/0/root` is then a well-formed boundary that fails, like
+    /// any PID with no process, with [`ErrorKind::NotFound`](std::io::ErrorKind::NotFound)
+    /// and a [`ProcessGone`](crate::ProcessGone) payload: PID 0 is the idle task, which
+    /// `/proc` never lists.
+    ///
+    /// No caller means PID 0, so a boundary naming it usually comes from a PID that was
+    /// never filled in, such as a zeroed field or a failed parse defaulting to 0. This
+    /// option reports that as the caller's mistake rather than as a process that went
+    /// away. The PID is compared by value, so `/proc/00/root` is rejected too, and the
+    /// check applies wherever the boundary is found, including through a symlink.
+    ///
+    /// Has no effect on platforms other than Linux.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() {
+    /// use std::io::ErrorKind;
+    /// use proc_canonicalize::{canonicalize, CanonicalizeOptions};
+    ///
+    /// assert_eq!(canonicalize("/proc/0/root").unwrap_err().kind(), ErrorKind::NotFound);
+    ///
+    /// let strict = CanonicalizeOptions::new().reject_pid_zero(true);
+    /// let err = strict.canonicalize("/proc/0/root/etc").unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn reject_pid_zero(self, reject: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                reject_pid_zero: reject,
+                ..self
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = reject;
+            self
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn with_pid_filter(self, update: impl FnOnce(&mut PidFilter)) -> Self {
+        let mut filter = self.pid_filter.unwrap_or_default();
+        update(&mut filter);
+        Self {
+            pid_filter: Some(filter),
+            ..self
+        }
+    }
+}
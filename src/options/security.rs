@@ -0,0 +1,89 @@
+//! Options for security-focused callers: sanitized errors and the strictest preset.
+
+use std::io;
+
+use super::CanonicalizeOptions;
+use crate::EscapeBehavior;
+
+/// Symlinks [`CanonicalizeOptions::security_max`] allows for any one component.
+const SECURITY_MAX_SYMLINKS_PER_COMPONENT: u32 = 8;
+
+impl CanonicalizeOptions {
+    /// The strictest combination of options, as a baseline to adjust.
+    ///
+    /// Starting from [`new`](Self::new), this sets:
+    ///
+    /// | Option | Value | Effect |
+    /// | ------ | ----- | ------ |
+    /// | [`escape_behavior`](Self::escape_behavior) | [`EscapeBehavior::Error`] | a path that leaves its boundary fails instead of coming back as a host path |
+    /// | [`max_symlinks_per_component`](Self::max_symlinks_per_component) | `8` | a long symlink chain fails with `ELOOP` well before the kernel's 40 |
+    /// | [`reject_leading_dotdot`](Self::reject_leading_dotdot) | `true` | relative input whose `..` climbs above its start fails with [`io::ErrorKind::InvalidInput`] |
+    /// | [`allowed_pids`](Self::allowed_pids) | none | only `/proc/self` and `/proc/thread-self` boundaries are preserved |
+    /// | [`reject_pid_zero`](Self::reject_pid_zero) | `true` | a boundary under PID 0 fails as invalid input |
+    /// | [`sanitize_errors`](Self::sanitize_errors) | `true` | errors do not reveal whether a path exists |
+    ///
+    /// Symlinks into `/proc` are always followed as boundaries; there is no option to
+    /// turn that off. Everything else keeps its default. Each setting can be changed
+    /// by chaining its builder after this one, most often
+    /// [`allowed_pids`](Self::allowed_pids) to trust the PIDs of the containers being
+    /// resolved into: without it, a boundary under any numeric PID is handed to the
+    /// kernel and comes back as a host path.
+    ///
+    /// Only [`sanitize_errors`](Self::sanitize_errors) and
+    /// [`reject_leading_dotdot`](Self::reject_leading_dotdot) have an effect on
+    /// platforms other than Linux.
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use std::path::Path;
+    /// use proc_canonicalize::CanonicalizeOptions;
+    ///
+    /// let container = std::process::id();
+    /// let strict = CanonicalizeOptions::security_max().allowed_pids(&[container]);
+    ///
+    /// let inside = format!("/proc/{container}/root/etc");
+    /// assert_eq!(strict.canonicalize(&inside)?, Path::new(&inside));
+    /// assert!(strict.canonicalize("/proc/self/cwd/..").is_err()); // escapes
+    /// assert!(strict.canonicalize("../etc").is_err());
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn security_max() -> Self {
+        Self::new()
+            .escape_behavior(EscapeBehavior::Error)
+            .max_symlinks_per_component(SECURITY_MAX_SYMLINKS_PER_COMPONENT)
+            .reject_leading_dotdot(true)
+            .allowed_pids(&[])
+            .reject_pid_zero(true)
+            .sanitize_errors(true)
+    }
+
+    /// Replace errors that could reveal whether a path exists, as
+    /// [`canonicalize_sanitized`](crate::canonicalize_sanitized) does.
+    ///
+    /// Default: `false`, errors are returned as they are.
+    ///
+    /// With `true`, a failure of kind [`io::ErrorKind::NotFound`] or
+    /// [`io::ErrorKind::PermissionDenied`], or "not a directory", becomes
+    /// [`io::ErrorKind::Other`] with an [`Inaccessible`](crate::Inaccessible) payload
+    /// that keeps the real error for logging. Errors from the other options, such as
+    /// [`io::ErrorKind::InvalidInput`] for rejected input, are unchanged.
+    pub fn sanitize_errors(self, sanitize: bool) -> Self {
+        Self {
+            sanitize_errors: sanitize,
+            ..self
+        }
+    }
+
+    /// `err` as the caller is to see it.
+    pub(super) fn report(&self, err: io::Error) -> io::Error {
+        if self.sanitize_errors {
+            crate::sanitize::sanitize(err)
+        } else {
+            err
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Resolving a process's boundaries through a pidfd instead of a bare PID.
+
+use std::io;
+use std::os::unix::io::{AsRawFd, BorrowedFd};
+use std::path::{Path, PathBuf};
+
+use crate::proc_root::proc_root;
+use crate::process::process_gone;
+use crate::NamespaceKind;
+
+/// Resolve `subpath` under the root or cwd of the process `pidfd` refers to.
+///
+/// A pidfd names one process for as long as it is open, where a PID may be handed to an
+/// unrelated process once the original exits. The PID is read from the pidfd's entry in
+/// `/proc/self/fdinfo`, `subpath` is resolved under `/proc/PID/root` (or `cwd`) with
+/// the boundary preserved as by [`canonicalize`](crate::canonicalize), and the pidfd is
+/// read again afterwards: if its process exited meanwhile, the PID may since have been
+/// reused and the call fails with [`ProcessGone`](crate::ProcessGone). An absolute
+/// `subpath` is taken relative to the boundary.
+///
+/// The result still names the process by PID. It is only as stable as the process:
+/// keep the pidfd open while the path is in use and check it again before trusting it.
+///
+/// # Kernel requirements
+///
+/// pidfds need Linux 5.3 (`pidfd_open`), which also added the `Pid:` line to a
+/// pidfd's fdinfo. Detecting that the process exited during the call relies on fdinfo
+/// reporting `Pid: -1` for a reaped process, which Linux 5.10 added; on older kernels
+/// the check after resolution cannot see the exit.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() {
+/// use std::os::unix::io::BorrowedFd;
+/// use std::path::PathBuf;
+/// use proc_canonicalize::{canonicalize_pidfd, NamespaceKind};
+///
+/// // `pidfd` from pidfd_open(2) or clone3(CLONE_PIDFD)
+/// fn container_hosts(pidfd: BorrowedFd<'_>) -> std::io::Result<PathBuf> {
+///     // e.g. /proc/1234/root/etc/hosts, checked to still be that process's
+///     canonicalize_pidfd(pidfd, NamespaceKind::Root, "etc/hosts")
+/// }
+/// # let _ = container_hosts;
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Same as [`canonicalize`](crate::canonicalize), plus:
+/// - [`io::ErrorKind::InvalidInput`] if `pidfd` is not a pidfd
+/// - [`io::ErrorKind::NotFound`] if the process has exited, with a
+///   [`ProcessGone`](crate::ProcessGone) payload if it exited during the call
+/// - [`io::ErrorKind::Unsupported`] if the process is in a PID namespace this process
+///   cannot see, so it has no `/proc/PID` here
+pub fn canonicalize_pidfd(
+    pidfd: BorrowedFd<'_>,
+    kind: NamespaceKind,
+    subpath: impl AsRef<Path>,
+) -> io::Result<PathBuf> {
+    let fdinfo = proc_root()
+        .join("self/fdinfo")
+        .join(pidfd.as_raw_fd().to_string());
+    canonicalize_through_fdinfo(&fdinfo, kind, subpath.as_ref())
+}
+
+/// [`canonicalize_pidfd`] for the pidfd whose fdinfo is at `fdinfo`.
+fn canonicalize_through_fdinfo(
+    fdinfo: &Path,
+    kind: NamespaceKind,
+    subpath: &Path,
+) -> io::Result<PathBuf> {
+    let pid = read_pid(fdinfo)?;
+    let mut path = proc_root().join(pid.to_string()).join(kind.as_str());
+    // join would replace the boundary with an absolute subpath.
+    path.push(subpath.strip_prefix("/").unwrap_or(subpath));
+    let resolved = crate::canonicalize(&path)?;
+    // Still alive now means still alive throughout, so the PID was never reused.
+    if read_pid(fdinfo)? != pid {
+        return Err(process_gone(pid));
+    }
+    Ok(resolved)
+}
+
+/// The PID a pidfd's fdinfo reports.
+fn read_pid(fdinfo: &Path) -> io::Result<u32> {
+    let info = std::fs::read_to_string(fdinfo)?;
+    let field = info
+        .lines()
+        .find_map(|line| line.strip_prefix("Pid:"))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a pidfd"))?;
+    match field.trim() {
+        "-1" => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "the process behind the pidfd has exited",
+        )),
+        "0" => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the process behind the pidfd is not visible in this PID namespace",
+        )),
+        pid => pid.parse().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unrecognized pidfd fdinfo Pid: {pid}"),
+            )
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An fdinfo file reporting `pid`, as the kernel writes one for a pidfd.
+    fn fake_fdinfo(dir: &Path, pid: &str) -> PathBuf {
+        let fdinfo = dir.join("7");
+        std::fs::write(
+            &fdinfo,
+            format!(
+                "pos:\t0\nflags:\t02000002\nmnt_id:\t15\nino:\t1057\nPid:\t{pid}\nNSpid:\t{pid}\n"
+            ),
+        )
+        .unwrap();
+        fdinfo
+    }
+
+    // ==========================================================================
+    // RESOLUTION THROUGH THE REPORTED PID
+    // ==========================================================================
+
+    #[test]
+    fn subpath_resolves_under_reported_pid_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid = std::process::id();
+        let fdinfo = fake_fdinfo(dir.path(), &pid.to_string());
+
+        let resolved =
+            canonicalize_through_fdinfo(&fdinfo, NamespaceKind::Root, Path::new("etc/.")).unwrap();
+
+        assert_eq!(resolved, Path::new(&format!("/proc/{pid}/root/etc")));
+    }
+
+    #[test]
+    fn absolute_subpath_stays_under_boundary() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid = std::process::id();
+        let fdinfo = fake_fdinfo(dir.path(), &pid.to_string());
+
+        let resolved =
+            canonicalize_through_fdinfo(&fdinfo, NamespaceKind::Root, Path::new("/etc")).unwrap();
+
+        assert_eq!(resolved, Path::new(&format!("/proc/{pid}/root/etc")));
+    }
+
+    #[test]
+    fn cwd_kind_resolves_under_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid = std::process::id();
+        let fdinfo = fake_fdinfo(dir.path(), &pid.to_string());
+
+        let resolved =
+            canonicalize_through_fdinfo(&fdinfo, NamespaceKind::Cwd, Path::new("")).unwrap();
+
+        assert_eq!(resolved, Path::new(&format!("/proc/{pid}/cwd")));
+    }
+
+    #[test]
+    fn ns_kind_resolves_namespace_link() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid = std::process::id();
+        let fdinfo = fake_fdinfo(dir.path(), &pid.to_string());
+
+        let resolved =
+            canonicalize_through_fdinfo(&fdinfo, NamespaceKind::Ns, Path::new("net")).unwrap();
+
+        assert_eq!(resolved, Path::new(&format!("/proc/{pid}/ns/net")));
+    }
+
+    // ==========================================================================
+    // FDINFO THAT NAMES NO USABLE PROCESS
+    // ==========================================================================
+
+    #[test]
+    fn exited_process_is_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let fdinfo = fake_fdinfo(dir.path(), "-1");
+
+        let err =
+            canonicalize_through_fdinfo(&fdinfo, NamespaceKind::Root, Path::new("")).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn process_in_other_pid_namespace_is_unsupported() {
+        let dir = tempfile::tempdir().unwrap();
+        let fdinfo = fake_fdinfo(dir.path(), "0");
+
+        let err =
+            canonicalize_through_fdinfo(&fdinfo, NamespaceKind::Root, Path::new("")).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn fdinfo_without_pid_line_is_not_a_pidfd() {
+        let dir = tempfile::tempdir().unwrap();
+        let fdinfo = dir.path().join("3");
+        std::fs::write(&fdinfo, "pos:\t0\nflags:\t02100000\nmnt_id:\t15\n").unwrap();
+
+        let err =
+            canonicalize_through_fdinfo(&fdinfo, NamespaceKind::Root, Path::new("")).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
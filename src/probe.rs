@@ -0,0 +1,118 @@
+//! Questions about a boundary itself, answered without resolving a path under it.
+
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+use crate::lexical::{parse_boundary, ParsedBoundary};
+use crate::proc_root::proc_root;
+use crate::process::explain_missing_process;
+
+/// Whether the process behind `boundary` is in this process's mount namespace.
+///
+/// Compares the namespace `/proc/PID/ns/mnt` leads to with that of `/proc/self/ns/mnt`,
+/// by device and inode, as the kernel identifies namespaces. `boundary` is any path
+/// starting with a boundary (`/proc/1234/root`, `/proc/1234/cwd/src`,
+/// `/proc/1234/task/5678/root`); only the process or task it names matters. A task
+/// boundary is checked against that task's own namespace.
+///
+/// `false` means paths under the boundary may name different files than the same paths
+/// on the host, so keeping the boundary matters. `true` means the mounts are shared,
+/// which is not quite the same as the boundary being redundant: a `chroot`ed process
+/// keeps its parent's mount namespace but sees a different `/`, so compare
+/// `/proc/PID/root` itself (e.g. with [`same_file`](crate::same_file) against `/`)
+/// when that matters too.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use proc_canonicalize::boundary_is_current_namespace;
+///
+/// assert!(boundary_is_current_namespace("/proc/self/root")?);
+/// let me = format!("/proc/{}/cwd/src", std::process::id());
+/// assert!(boundary_is_current_namespace(me)?);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// [`io::ErrorKind::InvalidInput`] if `boundary` does not start with a boundary.
+/// [`io::ErrorKind::NotFound`] with a [`ProcessGone`](crate::ProcessGone) payload for
+/// a PID with no process, and [`io::ErrorKind::PermissionDenied`] for a process whose
+/// namespaces this one may not inspect (another user's, without `CAP_SYS_PTRACE`).
+pub fn boundary_is_current_namespace(boundary: impl AsRef<Path>) -> io::Result<bool> {
+    let parsed = parse(boundary.as_ref())?;
+    // `/proc/PID/root` to `/proc/PID`: one level up per segment of the magic link.
+    let mut process_dir = parsed.prefix().to_path_buf();
+    for _ in Path::new(parsed.link()).components() {
+        process_dir.pop();
+    }
+
+    let theirs = std::fs::metadata(process_dir.join("ns/mnt"))
+        .map_err(|err| explain_missing_process(parsed.prefix(), err))?;
+    let ours = std::fs::metadata(proc_root().join("self/ns/mnt"))?;
+    Ok(theirs.dev() == ours.dev() && theirs.ino() == ours.ino())
+}
+
+/// Whether `boundary` can be entered: its process exists and this one may look inside.
+///
+/// The same `stat` of the magic link that [`canonicalize`](crate::canonicalize) starts
+/// every boundary with, as a predicate. Checking once per container lets a batch skip
+/// every path under one it cannot reach, instead of collecting an error per path.
+/// `boundary` is any path starting with a boundary; only the boundary is checked, not
+/// the rest of the path.
+///
+/// `false` covers both a missing process (or one that exited between listing and
+/// checking) and a process this one lacks permission to inspect. The answer can go
+/// stale as soon as it is returned, so resolution can still fail afterwards.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use proc_canonicalize::can_access_boundary;
+///
+/// assert!(can_access_boundary("/proc/self/root")?);
+/// assert!(can_access_boundary("/proc/self/cwd/no/such/entry")?);
+/// assert!(!can_access_boundary("/proc/4294967295/root")?);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// [`io::ErrorKind::InvalidInput`] if `boundary` does not start with a boundary, and any
+/// error other than [`io::ErrorKind::NotFound`] and
+/// [`io::ErrorKind::PermissionDenied`] from the `stat` itself.
+pub fn can_access_boundary(boundary: impl AsRef<Path>) -> io::Result<bool> {
+    let parsed = parse(boundary.as_ref())?;
+    match std::fs::metadata(parsed.prefix()) {
+        Ok(_) => Ok(true),
+        Err(err)
+            if matches!(
+                err.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::PermissionDenied
+            ) =>
+        {
+            Ok(false)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+fn parse(boundary: &Path) -> io::Result<ParsedBoundary> {
+    parse_boundary(boundary).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "path does not start with a /proc/PID boundary",
+        )
+    })
+}
@@ -0,0 +1,77 @@
+//! Where the `/proc` filesystem is looked for.
+
+use std::path::Path;
+
+/// The default `/proc` mount point.
+const DEFAULT_PROC_ROOT: &str = "/proc";
+
+/// Environment variable that relocates `/proc` under the `env-config` feature.
+#[cfg(feature = "env-config")]
+const PROC_ROOT_ENV: &str = "PROC_CANONICALIZE_PROC_ROOT";
+
+/// The directory namespace boundaries are recognized under: `/proc` by default.
+///
+/// With the `env-config` feature, `PROC_CANONICALIZE_PROC_ROOT` replaces it when set
+/// to an absolute path made only of plain segments (no `.`, `..` or trailing
+/// separators). Any other value is ignored. The variable is read once, on first use,
+/// and holds for the life of the process.
+pub(crate) fn proc_root() -> &'static Path {
+    #[cfg(feature = "env-config")]
+    {
+        use std::path::PathBuf;
+        use std::sync::OnceLock;
+
+        static PROC_ROOT: OnceLock<Option<PathBuf>> = OnceLock::new();
+        let from_env = PROC_ROOT.get_or_init(|| {
+            std::env::var_os(PROC_ROOT_ENV)
+                .map(PathBuf::from)
+                .filter(|root| is_plain_absolute(root))
+        });
+        match from_env {
+            Some(root) => root,
+            None => Path::new(DEFAULT_PROC_ROOT),
+        }
+    }
+    #[cfg(not(feature = "env-config"))]
+    {
+        Path::new(DEFAULT_PROC_ROOT)
+    }
+}
+
+/// Number of [`Path::components`] in [`proc_root`], counting the leading `/`.
+pub(crate) fn proc_root_len() -> usize {
+    proc_root().components().count()
+}
+
+/// Whether `root` is absolute, below `/`, and spelled exactly as its components rebuild it.
+#[cfg(feature = "env-config")]
+fn is_plain_absolute(root: &Path) -> bool {
+    use std::path::{Component, PathBuf};
+
+    // Path equality ignores "//", interior "." and trailing "/"; compare the raw strings.
+    let rebuilt: PathBuf = root.components().collect();
+    root.has_root()
+        && root.components().count() > 1
+        && root
+            .components()
+            .all(|c| matches!(c, Component::RootDir | Component::Normal(_)))
+        && rebuilt.as_os_str() == root.as_os_str()
+}
+
+#[cfg(all(test, feature = "env-config"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_plain_absolute_roots_are_accepted() {
+        assert!(is_plain_absolute(Path::new("/proc")));
+        assert!(is_plain_absolute(Path::new("/mnt/host/proc")));
+
+        assert!(!is_plain_absolute(Path::new("proc")));
+        assert!(!is_plain_absolute(Path::new("/")));
+        assert!(!is_plain_absolute(Path::new("/mnt/../proc")));
+        assert!(!is_plain_absolute(Path::new("/mnt/./proc")));
+        assert!(!is_plain_absolute(Path::new("/mnt//proc")));
+        assert!(!is_plain_absolute(Path::new("/mnt/proc/")));
+    }
+}
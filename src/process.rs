@@ -0,0 +1,284 @@
+//! Guarding `/proc/PID` boundaries against PID reuse.
+
+use std::fmt;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Component, Path, PathBuf};
+
+use crate::proc_root::{proc_root, proc_root_len};
+
+/// The fields of `/proc/PID/stat` that follow `(comm)`; `starttime` is field 22 overall.
+const STARTTIME_INDEX_AFTER_COMM: usize = 19;
+
+/// A PID paired with the start time of the process that held it when captured.
+///
+/// PIDs are recycled: once a process exits, its number can be handed to an unrelated
+/// process, and `/proc/PID/root` then points into that process's namespace. The
+/// kernel's start time (clock ticks since boot, field 22 of `/proc/PID/stat`) tells
+/// the two apart. Pass an identity to [`CanonicalizeOptions::verify_process`] and
+/// boundaries under that PID are checked before and after resolution.
+///
+/// [`CanonicalizeOptions::verify_process`]: crate::CanonicalizeOptions::verify_process
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use proc_canonicalize::ProcessIdentity;
+///
+/// let identity = ProcessIdentity::capture(std::process::id())?;
+/// assert_eq!(identity.pid(), std::process::id());
+/// assert_eq!(ProcessIdentity::capture(identity.pid())?, identity);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProcessIdentity {
+    pid: u32,
+    start_time: u64,
+}
+
+/// The process behind a verified PID is no longer the one that was captured.
+///
+/// Carried as the payload of an [`io::Error`] of kind [`io::ErrorKind::Other`];
+/// recover it with [`io::Error::get_ref`] and `downcast_ref::<ProcessReused>()`.
+/// A process that exited without its PID being reused yet surfaces as
+/// [`ProcessGone`] instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessReused {
+    expected: ProcessIdentity,
+    found_start_time: u64,
+}
+
+/// The process behind a `/proc/PID` boundary no longer exists.
+///
+/// Carried as the payload of an [`io::Error`] of kind [`io::ErrorKind::NotFound`],
+/// whether the boundary was in the path itself or reached through a symlink; recover
+/// it with [`io::Error::get_ref`] and `downcast_ref::<ProcessGone>()`. Without it, a
+/// dead PID and a missing file inside a live process would read the same.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessGone {
+    pid: u32,
+}
+
+impl ProcessIdentity {
+    /// Record the current start time of `pid`.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`io::ErrorKind::NotFound`] if no such process exists, or with
+    /// [`io::ErrorKind::InvalidData`] if `/proc/PID/stat` cannot be parsed.
+    pub fn capture(pid: u32) -> io::Result<Self> {
+        Ok(ProcessIdentity {
+            pid,
+            start_time: read_start_time(pid)?,
+        })
+    }
+
+    /// An identity from a start time recorded elsewhere (e.g. an earlier `/proc/PID/stat`
+    /// read or a process-monitoring event), in clock ticks since boot.
+    pub fn from_parts(pid: u32, start_time: u64) -> Self {
+        ProcessIdentity { pid, start_time }
+    }
+
+    /// The PID.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// The process start time, in clock ticks since boot.
+    pub fn start_time(&self) -> u64 {
+        self.start_time
+    }
+
+    /// Whether `boundary` (a `/proc/PID/...` prefix) names this identity's PID.
+    pub(crate) fn guards(&self, boundary: &Path) -> bool {
+        numeric_pid(boundary) == Some(self.pid)
+    }
+
+    /// Fail unless the PID is still held by the captured process.
+    pub(crate) fn verify(&self) -> io::Result<()> {
+        let found_start_time =
+            read_start_time(self.pid).map_err(|err| explain_missing_pid(self.pid, err))?;
+        if found_start_time == self.start_time {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                ProcessReused {
+                    expected: *self,
+                    found_start_time,
+                },
+            ))
+        }
+    }
+}
+
+impl ProcessReused {
+    /// The identity the caller asked to verify.
+    pub fn expected(&self) -> ProcessIdentity {
+        self.expected
+    }
+
+    /// The start time of the process now holding the PID.
+    pub fn found_start_time(&self) -> u64 {
+        self.found_start_time
+    }
+}
+
+impl ProcessGone {
+    /// The PID that no longer names a process.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+}
+
+impl fmt::Display for ProcessGone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "process {} no longer exists", self.pid)
+    }
+}
+
+impl std::error::Error for ProcessGone {}
+
+/// The error for a `pid` known to have no process any more.
+pub(crate) fn process_gone(pid: u32) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, ProcessGone { pid })
+}
+
+/// `err` from accessing `boundary`, explained as [`ProcessGone`] when the PID it names
+/// has no `/proc/PID` directory.
+pub(crate) fn explain_missing_process(boundary: &Path, err: io::Error) -> io::Error {
+    match numeric_pid(boundary) {
+        Some(pid) => explain_missing_pid(pid, err),
+        None => err,
+    }
+}
+
+/// `err` from accessing something under `/proc/pid`, explained as [`ProcessGone`] when
+/// that directory does not exist.
+fn explain_missing_pid(pid: u32, err: io::Error) -> io::Error {
+    if err.kind() == io::ErrorKind::NotFound && !proc_root().join(pid.to_string()).exists() {
+        process_gone(pid)
+    } else {
+        err
+    }
+}
+
+impl fmt::Display for ProcessReused {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "process {} was replaced: start time {} expected, {} found",
+            self.expected.pid, self.expected.start_time, self.found_start_time
+        )
+    }
+}
+
+impl std::error::Error for ProcessReused {}
+
+/// `path` with a leading `/proc/thread-self` replaced by `/proc/PID/task/TID` for the
+/// calling thread, or `None` if it does not start with `/proc/thread-self`.
+pub(crate) fn pin_thread_self(path: &Path) -> io::Result<Option<PathBuf>> {
+    expand_proc_link(path, "thread-self")
+}
+
+/// `path` with a leading `/proc/self` replaced by `/proc/PID` for this process, or
+/// `None` if it does not start with `/proc/self`.
+pub(crate) fn expand_self(path: &Path) -> io::Result<Option<PathBuf>> {
+    expand_proc_link(path, "self")
+}
+
+/// `path` with the leading `/proc/<link>` replaced by what the link reads, or `None`
+/// if it does not start with that link.
+fn expand_proc_link(path: &Path, link: &str) -> io::Result<Option<PathBuf>> {
+    let link = proc_root().join(link);
+    let rest = match path.strip_prefix(&link) {
+        Ok(rest) => rest,
+        Err(_) => return Ok(None),
+    };
+    // The kernel answers relative to the proc root: "PID" or "PID/task/TID".
+    let mut expanded = proc_root().join(std::fs::read_link(&link)?);
+    expanded.push(rest);
+    if path.as_os_str().as_bytes().ends_with(b"/") {
+        expanded.push("");
+    }
+    Ok(Some(expanded))
+}
+
+/// The PID a `/proc/PID/...` boundary names numerically; `None` for `self` and `thread-self`.
+fn numeric_pid(boundary: &Path) -> Option<u32> {
+    match boundary.components().nth(proc_root_len()) {
+        Some(Component::Normal(segment)) => segment.to_str()?.parse().ok(),
+        _ => None,
+    }
+}
+
+fn read_start_time(pid: u32) -> io::Result<u64> {
+    let stat = std::fs::read_to_string(proc_root().join(pid.to_string()).join("stat"))?;
+    parse_start_time(&stat).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unrecognized /proc/{pid}/stat format"),
+        )
+    })
+}
+
+/// Extract `starttime` from the contents of `/proc/PID/stat`.
+///
+/// `comm` may itself contain spaces and parentheses, so fields are counted from
+/// the *last* `)`.
+fn parse_start_time(stat: &str) -> Option<u64> {
+    let (_, after_comm) = stat.rsplit_once(')')?;
+    after_comm
+        .split_whitespace()
+        .nth(STARTTIME_INDEX_AFTER_COMM)?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==========================================================================
+    // /proc/PID/stat PARSING
+    // ==========================================================================
+
+    #[test]
+    fn start_time_is_field_twenty_two() {
+        let stat = "42 (sleep) S 1 42 42 0 -1 4194560 90 0 0 0 0 0 0 0 20 0 1 0 987654 \
+                    5722112 130 18446744073709551615";
+
+        assert_eq!(parse_start_time(stat), Some(987_654));
+    }
+
+    #[test]
+    fn comm_with_spaces_and_parens_does_not_shift_fields() {
+        let stat = "42 (a) b (c) S 1 42 42 0 -1 4194560 90 0 0 0 0 0 0 0 20 0 1 0 555 \
+                    5722112 130";
+
+        assert_eq!(parse_start_time(stat), Some(555));
+    }
+
+    #[test]
+    fn truncated_stat_is_rejected() {
+        assert_eq!(parse_start_time("42 (sleep) S 1 42"), None);
+        assert_eq!(parse_start_time("garbage"), None);
+    }
+
+    // ==========================================================================
+    // WHICH BOUNDARIES ARE GUARDED
+    // ==========================================================================
+
+    #[test]
+    fn guards_only_boundaries_under_its_pid() {
+        let identity = ProcessIdentity::from_parts(1234, 1);
+
+        assert!(identity.guards(Path::new("/proc/1234/root")));
+        assert!(identity.guards(Path::new("/proc/1234/task/5678/cwd")));
+        assert!(!identity.guards(Path::new("/proc/12345/root")));
+        assert!(!identity.guards(Path::new("/proc/self/root")));
+    }
+}
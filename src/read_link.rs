@@ -0,0 +1,72 @@
+//! Single-hop symlink reads that keep namespace boundaries in the target.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Read one symlink, returning its target in boundary-preserving form.
+///
+/// Like [`std::fs::read_link`], this follows exactly one hop and does not touch the
+/// target. The difference is in how a target that reaches a namespace boundary is
+/// reported: if the target, taken relative to the link's directory and normalized
+/// lexically, is a `/proc/PID/{root,cwd}` (or `ns/TYPE`) path, that normalized
+/// absolute form is returned. `/proc/self/../self/root/etc` becomes
+/// `/proc/self/root/etc`, and a relative `../proc/self/root` in `/tmp` becomes
+/// `/proc/self/root`. Any other target is returned exactly as stored.
+///
+/// Normalization is lexical: `..` in the target is applied to the link's path as
+/// spelled, not to wherever its parent directories resolve. Use
+/// [`canonicalize`](crate::canonicalize) for a full resolution.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::read_link_preserving;
+///
+/// let dir = tempfile::tempdir()?;
+/// let link = dir.path().join("container");
+/// std::os::unix::fs::symlink("/proc/self/./root/etc", &link)?;
+///
+/// assert_eq!(read_link_preserving(&link)?, Path::new("/proc/self/root/etc"));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Platform Behavior
+///
+/// - **Linux**: As described above
+/// - **Other platforms**: Identical to [`std::fs::read_link`]
+///
+/// # Errors
+///
+/// Returns any error from [`std::fs::read_link`], e.g. when `path` is not a symlink.
+pub fn read_link_preserving(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    let target = std::fs::read_link(path)?;
+
+    #[cfg(target_os = "linux")]
+    {
+        use crate::lexical::{is_proc_magic_path, lexical_normalize_into};
+
+        // The target is relative to the directory holding the link.
+        let mut anchored = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            crate::cwd::current_dir()?.join(path)
+        };
+        anchored.pop();
+        anchored.push(&target);
+
+        let mut normalized = PathBuf::with_capacity(anchored.as_os_str().len());
+        lexical_normalize_into(&anchored, &mut normalized);
+        if is_proc_magic_path(&normalized) {
+            return Ok(normalized);
+        }
+    }
+
+    Ok(target)
+}
@@ -0,0 +1,53 @@
+//! Memoized host paths of boundaries, for [`CanonicalizeOptions::memoize_boundaries`].
+//!
+//! [`CanonicalizeOptions::memoize_boundaries`]: crate::CanonicalizeOptions::memoize_boundaries
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::fs::FileId;
+
+/// Host directory each boundary was last seen to resolve to, with the identity of that
+/// directory at the time, shared between clones.
+///
+/// Keyed by the path the boundary was entered through: the magic link spelled under
+/// the configured proc root (`/host/proc/1/root` and `/proc/1/root` are separate
+/// entries), or the stand-in root when one is set. Clones with different stand-ins
+/// therefore share the map but never an entry.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PrefixCache(Arc<Mutex<HashMap<PathBuf, (FileId, PathBuf)>>>);
+
+impl PrefixCache {
+    /// The cached host path of `boundary` if it was stored for the directory `id`, or
+    /// `resolve()`'s answer, remembered for next time.
+    pub(crate) fn get_or_resolve(
+        &self,
+        boundary: &Path,
+        id: FileId,
+        resolve: impl FnOnce() -> io::Result<PathBuf>,
+    ) -> io::Result<PathBuf> {
+        if let Some((cached_id, host)) = self.entries().get(boundary) {
+            if *cached_id == id {
+                return Ok(host.clone());
+            }
+        }
+        // Resolved unlocked: concurrent misses on one boundary both resolve it, and
+        // both answers are equally current. `id` predates the answer, so if the boundary
+        // moves in between, the pair mismatches and the next call resolves again.
+        let host = resolve()?;
+        self.entries()
+            .insert(boundary.to_path_buf(), (id, host.clone()));
+        Ok(host)
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries().clear();
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<PathBuf, (FileId, PathBuf)>> {
+        // Nothing holds the lock across a fallible step, so a poisoned map is intact.
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
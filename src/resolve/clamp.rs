@@ -0,0 +1,77 @@
+//! Resolution with a boundary treated as the filesystem root.
+
+use std::ffi::OsString;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use super::{ELOOP, MAX_SYMLINK_FOLLOWS};
+use crate::fs::{FileKind, FsOps};
+
+/// A component still to be walked.
+enum Pending {
+    Parent,
+    Name(OsString),
+}
+
+/// Resolve `remainder` beneath `root` as if `root` were `/`.
+///
+/// `..` never climbs above `root` and absolute symlink targets restart at `root`,
+/// the way the kernel walks paths for a process chrooted there. Returns the resolved
+/// path relative to `root`. Every component is looked up through `root`, so `root`
+/// may be a magic link such as `/proc/1234/root`.
+///
+/// With `allow_missing`, a component that does not exist is kept as named instead of
+/// failing the walk, and everything after it is applied lexically.
+pub(super) fn resolve_clamped(
+    fs: &dyn FsOps,
+    root: &Path,
+    remainder: &Path,
+    allow_missing: bool,
+) -> io::Result<PathBuf> {
+    let mut resolved = PathBuf::with_capacity(remainder.as_os_str().len());
+    // Stack of components, next one on top.
+    let mut pending = Vec::new();
+    push_reversed(&mut pending, remainder);
+    let mut follows = 0;
+
+    while let Some(next) = pending.pop() {
+        let name = match next {
+            Pending::Parent => {
+                resolved.pop();
+                continue;
+            }
+            Pending::Name(name) => name,
+        };
+        resolved.push(name);
+
+        let on_host = root.join(&resolved);
+        match fs.symlink_metadata(&on_host) {
+            Ok(FileKind::Symlink) => {}
+            Ok(_) => continue,
+            Err(err) if allow_missing && err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        }
+        follows += 1;
+        if follows > MAX_SYMLINK_FOLLOWS {
+            return Err(io::Error::from_raw_os_error(ELOOP));
+        }
+        let target = fs.read_link(&on_host)?;
+        resolved.pop();
+        if target.has_root() {
+            resolved.clear();
+        }
+        push_reversed(&mut pending, &target);
+    }
+
+    Ok(resolved)
+}
+
+fn push_reversed(pending: &mut Vec<Pending>, path: &Path) {
+    for component in path.components().rev() {
+        match component {
+            Component::Normal(name) => pending.push(Pending::Name(name.to_os_string())),
+            Component::ParentDir => pending.push(Pending::Parent),
+            Component::RootDir | Component::CurDir | Component::Prefix(_) => {}
+        }
+    }
+}
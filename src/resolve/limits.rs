@@ -0,0 +1,25 @@
+//! The kernel's limit on the length of a path it will accept.
+
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+
+use super::Resolved;
+
+/// `PATH_MAX` on Linux, counting the terminating NUL.
+const PATH_MAX: usize = 4096;
+
+/// `ENAMETOOLONG` on Linux: the path is longer than `PATH_MAX`.
+const ENAMETOOLONG: i32 = 36;
+
+/// `resolved`, unless its preserved form is too long for any syscall to take.
+///
+/// The kernel checks each path it is handed, but the preserved form is assembled
+/// here: a boundary prefix put back in front of a host path the kernel accepted, or a
+/// short symlink that expands to a long target. Without this check such a result would
+/// be returned, and fail only later, wherever it was used.
+pub(super) fn within_path_max(resolved: Resolved) -> io::Result<Resolved> {
+    if resolved.namespace.as_os_str().as_bytes().len() >= PATH_MAX {
+        return Err(io::Error::from_raw_os_error(ENAMETOOLONG));
+    }
+    Ok(resolved)
+}
@@ -0,0 +1,140 @@
+//! Per-process links that are not boundaries: kept on request, or because only the
+//! kernel can resolve them.
+
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Component, Path, PathBuf};
+
+use crate::fs::FileKind;
+use crate::lexical::per_process_entry_dir;
+use crate::options::names_pid_zero;
+use crate::process::{expand_self, pin_thread_self};
+
+use super::{Resolved, Resolver};
+
+impl Resolver<'_> {
+    /// `path`, spelled as the boundaries are, when it is a link in `/proc/PID` to be
+    /// kept as it is rather than followed.
+    pub(super) fn per_process_link(&self, path: &Path) -> io::Result<Option<PathBuf>> {
+        if path.as_os_str().as_bytes().ends_with(b"/") {
+            return Ok(None);
+        }
+        if self.preserve_unknown_proc_links {
+            if let Some(dir) = per_process_entry_dir(path) {
+                if self.admits(dir)?
+                    && matches!(self.fs.symlink_metadata(path), Ok(FileKind::Symlink))
+                {
+                    return self.spelled(path).map(Some);
+                }
+                return Ok(None);
+            }
+        }
+        let is_fd = path
+            .file_name()
+            .is_some_and(|n| !n.is_empty() && n.as_bytes().iter().all(u8::is_ascii_digit));
+        let fd_dir = path
+            .parent()
+            .filter(|parent| is_fd && parent.file_name() == Some("fd".as_ref()))
+            .and_then(per_process_entry_dir);
+        match fd_dir {
+            Some(dir) if self.admits(dir)? && self.has_no_name(path) => {
+                self.spelled(path).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Whether the fd link `path` leads to an open file that its target, as the
+    /// kernel writes it, does not name: unlinked (`/tmp/x (deleted)`), in another
+    /// mount namespace, or never named (`pipe:[1234]`). The link is then the only way
+    /// to reach it. A file named like the target, e.g. one created as
+    /// `/tmp/x (deleted)`, is a different file and does not count.
+    fn has_no_name(&self, path: &Path) -> bool {
+        let open_file = match self.fs.file_id(path) {
+            Ok(id) => id,
+            Err(_) => return false,
+        };
+        let named = self
+            .std_canonicalize(path)
+            .and_then(|target| self.fs.file_id(&target));
+        !named.is_ok_and(|id| id == open_file)
+    }
+
+    /// Whether links under `dir`, a `/proc/PID` or `/proc/PID/task/TID` directory,
+    /// pass the same PID checks as a boundary there.
+    fn admits(&self, dir: &Path) -> io::Result<bool> {
+        // The filters judge a boundary by its selector, which `dir/root` shares.
+        let boundary = dir.join("root");
+        if self.reject_pid_zero && names_pid_zero(&boundary) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "boundary names PID 0, which has no /proc entry",
+            ));
+        }
+        Ok(self.pid_filter.map_or(true, |f| f.admits(&boundary)))
+    }
+
+    /// `path` with its separators tidied and `self` expanded as for a boundary.
+    fn spelled(&self, path: &Path) -> io::Result<PathBuf> {
+        let mut link: PathBuf = path.components().collect();
+        if self.pin_thread_self || self.expand_self {
+            link = pin_thread_self(&link)?.unwrap_or(link);
+        }
+        if self.expand_self {
+            link = expand_self(&link)?.unwrap_or(link);
+        }
+        Ok(link)
+    }
+
+    /// `remainder` below the `root` boundary `namespace_prefix`, kept as written, when
+    /// it starts at that root's own `/proc/self` or `/proc/thread-self`.
+    ///
+    /// Those name the caller as the process's procfs numbers it, or nothing if the
+    /// caller is not in its PID namespace. `std::fs::canonicalize` would read them in
+    /// the caller's procfs instead, and so name the caller's host PID there. The kernel
+    /// walks the path to check that it exists; the components are not resolved further.
+    /// A `..` or a link followed below the selector leaves the written form, so such a
+    /// remainder is resolved as any other.
+    pub(super) fn resolve_inner_self(
+        &self,
+        namespace_prefix: &Path,
+        boundary_link: &Path,
+        remainder: &Path,
+        has_trailing_slash: bool,
+    ) -> io::Result<Option<Resolved>> {
+        let mut components = remainder.components();
+        let names_self = namespace_prefix.ends_with("root")
+            && components.next() == Some(Component::Normal("proc".as_ref()))
+            && matches!(
+                components.next(),
+                Some(Component::Normal(selector)) if selector == "self" || selector == "thread-self"
+            );
+        if !names_self {
+            return Ok(None);
+        }
+        // `proc` and the selector are walked as written. A `..` below them, or a link
+        // they lead to (`cwd`, `fd/N`) being followed, needs resolving like any other path.
+        let is_link = |path: &Path| matches!(self.fs.symlink_metadata(path), Ok(FileKind::Symlink));
+        let mut full_path = boundary_link.to_path_buf();
+        let mut kept = namespace_prefix.to_path_buf();
+        let mut walked = 0;
+        for component in remainder.components() {
+            if component == Component::ParentDir || walked > 2 && is_link(&full_path) {
+                return Ok(None);
+            }
+            full_path.push(component);
+            kept.push(component);
+            walked += 1;
+        }
+        if has_trailing_slash {
+            if walked > 2 && is_link(&full_path) {
+                return Ok(None);
+            }
+            full_path.push("");
+        }
+        match self.fs.metadata(&full_path) {
+            Err(err) if !self.is_tolerated(&err) => Err(err),
+            _ => Ok(Some(Resolved::same(kept))),
+        }
+    }
+}
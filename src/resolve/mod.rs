@@ -0,0 +1,594 @@
+//! Linux resolver: namespace-aware canonicalization.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::cwd::current_dir;
+use crate::fs::{DeadlineFs, FsOps, RealFs};
+use crate::lexical::{
+    canonicalize_lexical, find_namespace_boundary, join_components, starts_with_proc_root,
+};
+use crate::options::{names_pid_zero, PidFilter};
+use crate::process::{expand_self, explain_missing_process, pin_thread_self};
+use crate::trace::record;
+use crate::{BoundaryEscaped, CanonicalizeOptions, EscapeBehavior, ProcessIdentity, Step};
+
+mod cache;
+mod clamp;
+mod limits;
+mod links;
+mod scan;
+
+pub(crate) use cache::PrefixCache;
+use clamp::resolve_clamped;
+use scan::detect_indirect_proc_magic_link;
+pub(crate) use scan::ScanBuffers;
+
+/// Maximum number of symlinks to follow before giving up (matches kernel MAXSYMLINKS).
+pub(crate) const MAX_SYMLINK_FOLLOWS: u32 = 40;
+
+/// `ELOOP` on Linux: too many levels of symbolic links.
+const ELOOP: i32 = 40;
+
+/// `ENOENT` on Linux: no such file or directory.
+const ENOENT: i32 = 2;
+
+pub(crate) fn canonicalize_impl(path: &Path) -> io::Result<PathBuf> {
+    Resolver::new(&CanonicalizeOptions::default()).canonicalize(path)
+}
+
+/// Configuration for one namespace-aware resolution, carried across re-entry.
+#[derive(Clone, Copy)]
+pub(crate) struct Resolver<'opts> {
+    /// Stand-in for the directory every boundary magic link resolves to.
+    ///
+    /// When `Some`, each detected boundary is accessed through this directory instead
+    /// of through its magic link, as if `/proc/PID/root` resolved there. Used for
+    /// offline resolution against an extracted image, and to exercise the
+    /// container-like (`resolved_prefix != "/"`) branches without a real container.
+    boundary_target: Option<&'opts Path>,
+    /// Whether the outermost boundary is still to be replaced by its host path.
+    /// Cleared on re-entry so deeper boundaries are preserved.
+    resolve_outer_to_host: bool,
+    /// What to return when a path resolves outside its boundary.
+    escape_behavior: EscapeBehavior,
+    /// Whether a `/proc/thread-self` boundary is replaced by the calling thread's
+    /// `/proc/PID/task/TID`.
+    pin_thread_self: bool,
+    /// Whether `/proc/self` is replaced by this process's `/proc/PID` (and
+    /// `/proc/thread-self` by the calling thread's, as with `pin_thread_self`).
+    expand_self: bool,
+    /// Identity that boundaries under its PID are verified against.
+    process_identity: Option<&'opts ProcessIdentity>,
+    /// When to stop starting filesystem operations.
+    deadline: Option<Instant>,
+    /// Most symlinks the scanner follows for any one component, when capped.
+    max_symlinks_per_component: Option<u32>,
+    /// Whether a result that is exactly a boundary is replaced by its host path.
+    follow_terminal_boundary: bool,
+    /// Whether a symlink directly in `/proc/PID` is kept even when it is not a boundary.
+    preserve_unknown_proc_links: bool,
+    /// Whether paths that do not exist resolve lexically instead of failing.
+    allow_missing: bool,
+    /// Whether a boundary denied to the caller gives back the input, folded lexically.
+    preserve_on_inaccessible_boundary: bool,
+    /// Whether boundaries are followed; if not, they are only checked to exist and the
+    /// input is given back folded lexically.
+    cross_boundaries: bool,
+    /// Which selectors' boundaries are preserved; `None` preserves all.
+    pid_filter: Option<&'opts PidFilter>,
+    /// Whether a boundary under PID 0 is rejected as invalid input.
+    reject_pid_zero: bool,
+    /// Host paths of `root` boundaries remembered across calls.
+    prefix_cache: Option<&'opts PrefixCache>,
+    /// The filesystem every lookup goes through.
+    fs: &'opts dyn FsOps,
+    /// Where resolution steps are logged, for [`canonicalize_verbose`](crate::canonicalize_verbose).
+    trace: Option<&'opts RefCell<Vec<Step>>>,
+    /// Buffers the scanner reuses, for [`canonicalize_with_scratch`](crate::canonicalize_with_scratch).
+    scratch: Option<&'opts RefCell<ScanBuffers>>,
+    /// Whether a bare boundary's host path is resolved too, for
+    /// [`canonicalize_both`](crate::canonicalize_both). Every other result already has it.
+    want_host: bool,
+}
+
+/// The two forms of one resolved path.
+pub(crate) struct Resolved {
+    /// The path with its boundaries preserved.
+    pub(crate) namespace: PathBuf,
+    /// The same target as the host sees it, when that differs from `namespace` and
+    /// was resolved along the way.
+    pub(crate) host: Option<PathBuf>,
+}
+
+impl Resolved {
+    fn same(path: PathBuf) -> Self {
+        Resolved {
+            namespace: path,
+            host: None,
+        }
+    }
+}
+
+impl<'opts> Resolver<'opts> {
+    pub(crate) fn new(options: &'opts CanonicalizeOptions) -> Self {
+        Resolver {
+            boundary_target: options.stand_in(),
+            resolve_outer_to_host: options.resolves_outer_to_host(),
+            escape_behavior: options.escape(),
+            pin_thread_self: options.pins_thread_self(),
+            expand_self: options.expands_self(),
+            process_identity: options.process_identity(),
+            max_symlinks_per_component: options.symlinks_per_component(),
+            follow_terminal_boundary: options.follows_terminal_boundary(),
+            preserve_unknown_proc_links: options.preserves_unknown_proc_links(),
+            allow_missing: options.allows_missing(),
+            preserve_on_inaccessible_boundary: options.preserves_on_inaccessible_boundary(),
+            cross_boundaries: options.crosses_boundaries(),
+            pid_filter: options.pid_filter(),
+            reject_pid_zero: options.rejects_pid_zero(),
+            deadline: options.deadline_at(),
+            prefix_cache: options.prefix_cache(),
+            fs: match options.read_link_hook() {
+                Some(hook) => hook,
+                None => &RealFs,
+            },
+            trace: None,
+            scratch: None,
+            want_host: false,
+        }
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn with_fs(self, fs: &'opts dyn FsOps) -> Self {
+        Resolver { fs, ..self }
+    }
+
+    pub(crate) fn with_trace(self, trace: &'opts RefCell<Vec<Step>>) -> Self {
+        Resolver {
+            trace: Some(trace),
+            ..self
+        }
+    }
+
+    pub(crate) fn with_scratch(self, scratch: &'opts RefCell<ScanBuffers>) -> Self {
+        Resolver {
+            scratch: Some(scratch),
+            ..self
+        }
+    }
+
+    pub(crate) fn with_host(self) -> Self {
+        Resolver {
+            want_host: true,
+            ..self
+        }
+    }
+
+    pub(crate) fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.canonicalize_both(path)
+            .map(|resolved| resolved.namespace)
+    }
+
+    /// Like [`canonicalize`](Self::canonicalize), also keeping the host form.
+    pub(crate) fn canonicalize_both(&self, path: &Path) -> io::Result<Resolved> {
+        if let Some(deadline) = self.deadline {
+            // Every lookup below goes through `fs`, so that is where the clock is read.
+            let fs = DeadlineFs::new(self.fs, deadline);
+            let bounded = Resolver {
+                fs: &fs,
+                deadline: None,
+                ..*self
+            };
+            return bounded.canonicalize_both(path);
+        }
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::bump(&crate::metrics::CALLS);
+
+        // What `std::fs::canonicalize("")` returns. Left to the scanner, "" would first
+        // be joined onto the current directory, making the error depend on it.
+        if path.as_os_str().is_empty() {
+            return Err(io::Error::from_raw_os_error(ENOENT));
+        }
+
+        // The kernel would reject this too, but only at the first syscall, after the
+        // scanner has already walked (and silently skipped) the unreadable components.
+        if path.as_os_str().as_bytes().contains(&0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "path contains a NUL byte",
+            ));
+        }
+
+        let resolved = self.resolve(path).and_then(limits::within_path_max);
+        #[cfg(feature = "metrics")]
+        if matches!(&resolved, Err(err) if err.raw_os_error() == Some(ELOOP)) {
+            crate::metrics::bump(&crate::metrics::LOOPS);
+        }
+        resolved
+    }
+
+    /// Resolve `remainder` below `namespace_prefix`, a boundary the caller has already
+    /// found, without looking for one again.
+    pub(crate) fn canonicalize_under(
+        &self,
+        namespace_prefix: &Path,
+        remainder: &Path,
+    ) -> io::Result<PathBuf> {
+        // `join` keeps a trailing "/" on the remainder, and with it the directory check.
+        let path = namespace_prefix.join(remainder);
+        let remainder: PathBuf = remainder.components().collect();
+        self.canonicalize_boundary(&path, namespace_prefix, &remainder)
+            .and_then(limits::within_path_max)
+            .map(|resolved| resolved.namespace)
+    }
+
+    /// Resolve `path`, which has already passed the entry checks.
+    fn resolve(&self, path: &Path) -> io::Result<Resolved> {
+        // Each scanner hit hands back a path to resolve afresh. That is a loop, not
+        // recursion, so crafted chains of indirect links cannot grow the stack.
+        let mut path = Cow::Borrowed(path);
+        let mut rescans = 0;
+        // Set once a boundary has passed the PID filter, so rewriting its selector
+        // (`self` to the PID) does not subject it to the filter a second time.
+        let mut admitted = false;
+        loop {
+            // Check if path contains a /proc namespace boundary. Most paths are ruled
+            // out by their first bytes, before any component parsing.
+            let boundary = if starts_with_proc_root(&path) {
+                find_namespace_boundary(&path)
+            } else {
+                None
+            };
+            if let Some((namespace_prefix, remainder)) = boundary {
+                if self.reject_pid_zero && names_pid_zero(&namespace_prefix) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "boundary names PID 0, which has no /proc entry",
+                    ));
+                }
+                if !admitted
+                    && !self
+                        .pid_filter
+                        .map_or(true, |f| f.admits(&namespace_prefix))
+                {
+                    // Not a boundary the caller trusts: the kernel follows the magic
+                    // link, as for any other path. Skipping the scanner matters, since it
+                    // would hand this same path back as magic.
+                    return self.std_canonicalize(&path).map(Resolved::same);
+                }
+                admitted = true;
+                if self.pin_thread_self || self.expand_self {
+                    if let Some(pinned) = pin_thread_self(&path)? {
+                        path = Cow::Owned(pinned);
+                        continue;
+                    }
+                }
+                if self.expand_self {
+                    if let Some(expanded) = expand_self(&path)? {
+                        path = Cow::Owned(expanded);
+                        continue;
+                    }
+                }
+                return self.canonicalize_boundary(&path, &namespace_prefix, &remainder);
+            }
+            if starts_with_proc_root(&path) {
+                if let Some(link) = self.per_process_link(&path)? {
+                    return Ok(Resolved::same(link));
+                }
+            }
+
+            // Check for indirect symlinks to /proc magic paths BEFORE calling std::fs::canonicalize.
+            //
+            // This handles cases like:
+            //   symlink("/proc/self/root", "/tmp/container_link")
+            //   canonicalize("/tmp/container_link")        -> should return /proc/self/root, not /
+            //   canonicalize("/tmp/container_link/etc")    -> should return /proc/self/root/etc, not /etc
+            //
+            // We detect symlinks in the path that point to /proc magic paths and handle them
+            // the same way we handle direct /proc paths.
+            match detect_indirect_proc_magic_link(
+                self.fs,
+                &path,
+                self.max_symlinks_per_component,
+                self.trace,
+                self.scratch,
+            )? {
+                Some(mut magic_path) => {
+                    rescans += 1;
+                    if rescans > MAX_SYMLINK_FOLLOWS {
+                        return Err(io::Error::from_raw_os_error(ELOOP));
+                    }
+                    if path.as_os_str().as_bytes().ends_with(b"/") {
+                        // The scanner rebuilds the path from components; carry the "/" across.
+                        magic_path.push("");
+                    }
+                    path = Cow::Owned(magic_path);
+                }
+                // Normal path - use std::fs::canonicalize directly
+                None => {
+                    return match self.std_canonicalize(&path) {
+                        Err(err) if self.is_tolerated(&err) => {
+                            self.resolve_missing(&path).map(Resolved::same)
+                        }
+                        resolved => resolved.map(Resolved::same),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Resolve a path with a boundary, checking the process identity around it.
+    fn canonicalize_boundary(
+        &self,
+        path: &Path,
+        namespace_prefix: &Path,
+        remainder: &Path,
+    ) -> io::Result<Resolved> {
+        #[cfg(feature = "metrics")]
+        crate::metrics::bump(&crate::metrics::BOUNDARIES);
+        record(self.trace, || {
+            Step::DetectedBoundary(namespace_prefix.to_path_buf())
+        });
+        // Check-resolve-check: a PID recycled before or during resolution is caught
+        // by one of the two reads of its start time.
+        let identity = self
+            .process_identity
+            .filter(|identity| identity.guards(namespace_prefix));
+        if let Some(identity) = identity {
+            identity.verify()?;
+        }
+        let mut resolved = match self.resolve_boundary(path, namespace_prefix, remainder) {
+            Err(err) if self.is_denied_boundary(&err, namespace_prefix) => {
+                return canonicalize_lexical(path).map(Resolved::same);
+            }
+            resolved => resolved?,
+        };
+        if let Some(identity) = identity {
+            identity.verify()?;
+        }
+        if self.follow_terminal_boundary
+            && resolved.namespace.as_os_str() == namespace_prefix.as_os_str()
+        {
+            // Namespace links have no host form and stay as they are.
+            if let Some(host) = resolved.host.take() {
+                resolved = Resolved::same(host);
+            }
+        }
+        // Only a host escape under ReturnHost, or an explicit swap to the host
+        // view, may drop the prefix; anything else is a resolver bug.
+        debug_assert!(
+            resolved.namespace.starts_with(namespace_prefix)
+                || self.escape_behavior == EscapeBehavior::ReturnHost
+                || self.resolve_outer_to_host
+                || self.follow_terminal_boundary,
+            "{} resolved to {} without keeping {} under {:?}",
+            path.display(),
+            resolved.namespace.display(),
+            namespace_prefix.display(),
+            self.escape_behavior,
+        );
+        Ok(resolved)
+    }
+
+    /// Resolve a path whose first boundary is `namespace_prefix`, followed by `remainder`.
+    fn resolve_boundary(
+        &self,
+        path: &Path,
+        namespace_prefix: &Path,
+        remainder: &Path,
+    ) -> io::Result<Resolved> {
+        // The path the kernel walks through: the magic link itself, or the stand-in.
+        let boundary_link = self.boundary_target.unwrap_or(namespace_prefix);
+
+        if !self.cross_boundaries {
+            // lstat, not stat: the link is checked without being followed.
+            self.fs
+                .symlink_metadata(boundary_link)
+                .map_err(|err| self.boundary_error(namespace_prefix, err))?;
+            return canonicalize_lexical(path).map(Resolved::same);
+        }
+
+        // Path::components drops trailing separators, but a trailing "/" still means
+        // "must be a directory". Re-append it where the kernel sees the path so a file
+        // with a trailing slash fails with ENOTDIR exactly as std::fs::canonicalize does.
+        let has_trailing_slash = path.as_os_str().as_bytes().ends_with(b"/");
+
+        // Verify the namespace prefix exists and is accessible. A bare boundary needs a
+        // metadata() call for that (it gives PermissionDenied vs NotFound, unlike
+        // exists()); every other path resolves the prefix next, which fails the same way.
+        if remainder.as_os_str().is_empty() {
+            let exists = if has_trailing_slash {
+                // join("") appends the separator
+                self.fs.metadata(&boundary_link.join(""))
+            } else {
+                self.fs.metadata(boundary_link)
+            };
+            exists.map_err(|err| self.boundary_error(namespace_prefix, err))?;
+        }
+
+        if self.resolve_outer_to_host {
+            // Swap the boundary for its host path, then resolve the remainder from
+            // there with boundaries preserved again: any /proc magic path the
+            // remainder reaches (directly or through symlinks) is kept.
+            let mut host_path = self.host_prefix(namespace_prefix, boundary_link)?;
+            host_path.push(remainder);
+            if has_trailing_slash {
+                host_path.push("");
+            }
+            let inner = Resolver {
+                resolve_outer_to_host: false,
+                ..*self
+            };
+            return inner.resolve(&host_path);
+        }
+
+        if remainder.as_os_str().is_empty() {
+            // Path IS the namespace boundary (e.g., "/proc/1234/root")
+            // A namespace link (/proc/PID/ns/TYPE) is no directory and has no host path;
+            // the link is the only name it has.
+            let is_ns_link =
+                namespace_prefix.parent().and_then(Path::file_name) == Some("ns".as_ref());
+            let host = if (self.want_host || self.follow_terminal_boundary) && !is_ns_link {
+                Some(self.host_prefix(namespace_prefix, boundary_link)?)
+            } else {
+                None
+            };
+            Ok(Resolved {
+                namespace: namespace_prefix.to_path_buf(),
+                host,
+            })
+        } else {
+            // Path goes through namespace boundary (e.g., "/proc/1234/root/etc/passwd")
+
+            // 1. Resolve the namespace prefix to its absolute path on the host.
+            // This is necessary because /proc/PID/root might not be "/" (e.g. in containers),
+            // and /proc/PID/cwd is almost certainly not "/".
+            let resolved_prefix = self.host_prefix(namespace_prefix, boundary_link)?;
+            if let Some(resolved) = self.resolve_inner_self(
+                namespace_prefix,
+                boundary_link,
+                remainder,
+                has_trailing_slash,
+            )? {
+                return Ok(resolved);
+            }
+
+            // 2. Canonicalize the full path.
+            // This traverses the magic link and resolves everything.
+            let mut full_path = boundary_link.join(remainder);
+            if has_trailing_slash {
+                full_path.push("");
+            }
+            // Under ClampToRoot the clamped walk is the answer whenever the host view
+            // is not: `..` past the boundary or an absolute symlink may miss on the host
+            // (NotFound) yet land inside the boundary once clamped.
+            let clamp = || -> io::Result<Resolved> {
+                let clamped =
+                    resolve_clamped(self.fs, boundary_link, remainder, self.allow_missing)?;
+                if has_trailing_slash {
+                    // The walk never stats the final component as a directory.
+                    if let Err(err) = self.fs.metadata(&boundary_link.join(&clamped).join("")) {
+                        if !self.is_tolerated(&err) {
+                            return Err(err);
+                        }
+                    }
+                }
+                Ok(Resolved {
+                    namespace: join_components(namespace_prefix, &clamped),
+                    host: Some(join_components(&resolved_prefix, &clamped)),
+                })
+            };
+            let canonicalized = match self.std_canonicalize(&full_path) {
+                Ok(canonicalized) => canonicalized,
+                Err(_) if self.escape_behavior == EscapeBehavior::ClampToRoot => return clamp(),
+                Err(err) if self.is_tolerated(&err) => return clamp(),
+                Err(err) => return Err(err),
+            };
+
+            // 3. Try to re-base the canonicalized path onto the namespace prefix.
+            // We do this by stripping the resolved prefix from the canonicalized path.
+            // When the prefix resolves to "/" (a host's own /proc/PID/root) this always
+            // succeeds; only a container root or a cwd can be escaped. strip_prefix
+            // compares whole components, so ".../rootfs2" is not inside ".../rootfs".
+            if let Ok(suffix) = canonicalized.strip_prefix(&resolved_prefix) {
+                // The path is within the namespace. Re-attach the prefix.
+                Ok(Resolved {
+                    namespace: join_components(namespace_prefix, suffix),
+                    host: Some(canonicalized),
+                })
+            } else {
+                // The path escaped the namespace (e.g. via ".." or symlinks to outside).
+                // We cannot preserve the prefix while being correct, so by default we
+                // return the fully resolved path (absolute path on host).
+                #[cfg(feature = "metrics")]
+                crate::metrics::bump(&crate::metrics::ESCAPES);
+                match self.escape_behavior {
+                    EscapeBehavior::ReturnHost => Ok(Resolved::same(canonicalized)),
+                    EscapeBehavior::ClampToRoot => clamp(),
+                    EscapeBehavior::Error => Err(io::Error::new(
+                        io::ErrorKind::Other,
+                        BoundaryEscaped::new(namespace_prefix.to_path_buf(), canonicalized),
+                    )),
+                }
+            }
+        }
+    }
+
+    /// The host directory `boundary_link` resolves to, from the cache when allowed.
+    ///
+    /// That is the directory at the end of any chain of host symlinks the link leads
+    /// through, so it compares equal to the prefix of every path resolved below it.
+    /// Fails if the boundary does not exist, even when the answer is cached.
+    fn host_prefix(&self, namespace_prefix: &Path, boundary_link: &Path) -> io::Result<PathBuf> {
+        // A process's root rarely moves; its cwd moves with every chdir, so only root
+        // boundaries are worth remembering.
+        let resolved = match self.prefix_cache {
+            Some(cache) if namespace_prefix.ends_with("root") => {
+                // The stat both checks that the boundary still exists and tells whether
+                // it still enters the directory the remembered answer is for.
+                self.fs.file_id(boundary_link).and_then(|id| {
+                    cache.get_or_resolve(boundary_link, id, || self.std_canonicalize(boundary_link))
+                })
+            }
+            _ => self.std_canonicalize(boundary_link),
+        };
+        resolved.map_err(|err| self.boundary_error(namespace_prefix, err))
+    }
+
+    /// `err` from accessing a boundary, explained when its process is gone.
+    fn boundary_error(&self, namespace_prefix: &Path, err: io::Error) -> io::Error {
+        match self.boundary_target {
+            // A stand-in has no process behind it to be gone.
+            Some(_) => err,
+            None => explain_missing_process(namespace_prefix, err),
+        }
+    }
+
+    /// Whether `err` is the boundary `namespace_prefix` itself being denied, and the
+    /// input is to be returned anyway.
+    fn is_denied_boundary(&self, err: &io::Error, namespace_prefix: &Path) -> bool {
+        let boundary_link = self.boundary_target.unwrap_or(namespace_prefix);
+        // The error may come from a component further down; only a second look at the
+        // boundary tells.
+        self.preserve_on_inaccessible_boundary
+            && err.kind() == io::ErrorKind::PermissionDenied
+            && self
+                .fs
+                .metadata(boundary_link)
+                .is_err_and(|err| err.kind() == io::ErrorKind::PermissionDenied)
+    }
+
+    /// Whether `err` only says that part of the path is missing, and that is allowed.
+    fn is_tolerated(&self, err: &io::Error) -> bool {
+        self.allow_missing && err.kind() == io::ErrorKind::NotFound
+    }
+
+    /// `path`, with no boundary on it, resolved as far as it exists and lexically after.
+    fn resolve_missing(&self, path: &Path) -> io::Result<PathBuf> {
+        let absolute = if path.is_absolute() {
+            Cow::Borrowed(path)
+        } else {
+            Cow::Owned(current_dir()?.join(path))
+        };
+        // `..` at "/" stays at "/", so the host root is a boundary like any other.
+        let root = Path::new("/");
+        Ok(root.join(resolve_clamped(self.fs, root, &absolute, true)?))
+    }
+
+    /// [`std::fs::canonicalize`], logged to the trace.
+    fn std_canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        record(self.trace, || {
+            Step::CalledStdCanonicalize(path.to_path_buf())
+        });
+        self.fs.canonicalize(path)
+    }
+}
+
+#[cfg(test)]
+mod tests;
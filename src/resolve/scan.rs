@@ -0,0 +1,405 @@
+//! The indirect-symlink scanner: finds symlinks that lead into `/proc` magic paths.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::cwd::current_dir;
+use crate::fs::{FileKind, FsOps};
+use crate::lexical::{is_proc_magic_path, lexical_normalize_into};
+use crate::trace::record;
+use crate::Step;
+
+use super::{ELOOP, MAX_SYMLINK_FOLLOWS};
+
+/// Buffers the scanner builds paths in, kept between calls by a
+/// [`Scratch`](crate::Scratch) so their capacity is reused.
+#[derive(Debug, Default)]
+pub(crate) struct ScanBuffers {
+    accumulated: PathBuf,
+    normalized: PathBuf,
+    /// Holds the path being scanned when it is not the caller's: a relative input
+    /// joined onto the cwd, or the path rebuilt after a symlink.
+    current: PathBuf,
+}
+
+/// Detect if a path contains an indirect symlink to a `/proc` magic path.
+///
+/// This walks the ancestor chain of the input path looking for symlinks that
+/// point to `/proc/.../root` or `/proc/.../cwd`. Targets are matched with the same
+/// [`is_proc_magic_path`] as direct paths, so a relocated proc root applies to both.
+///
+/// Returns `Some(magic_path)` with any remaining suffix if found, or `None` otherwise.
+/// Fails with `ELOOP` once one component takes more than `per_component_limit` hops.
+/// With `scratch`, its buffers are used and handed back afterwards instead of fresh
+/// ones being allocated.
+pub(super) fn detect_indirect_proc_magic_link(
+    fs: &dyn FsOps,
+    path: &Path,
+    per_component_limit: Option<u32>,
+    trace: Option<&RefCell<Vec<Step>>>,
+    scratch: Option<&RefCell<ScanBuffers>>,
+) -> io::Result<Option<PathBuf>> {
+    let mut buffers = scratch.map(RefCell::take).unwrap_or_default();
+    let found = scan(fs, path, per_component_limit, trace, &mut buffers);
+    if let Some(scratch) = scratch {
+        scratch.replace(buffers);
+    }
+    found
+}
+
+fn scan(
+    fs: &dyn FsOps,
+    path: &Path,
+    per_component_limit: Option<u32>,
+    trace: Option<&RefCell<Vec<Step>>>,
+    buffers: &mut ScanBuffers,
+) -> io::Result<Option<PathBuf>> {
+    // Absolute input is scanned in place; only a relative path (which needs the cwd)
+    // or a followed symlink produces an owned path to scan.
+    let mut current_path = if path.is_absolute() {
+        Cow::Borrowed(path)
+    } else {
+        let mut joined = std::mem::take(&mut buffers.current);
+        joined.clear();
+        joined.push(current_dir()?);
+        joined.push(path);
+        Cow::Owned(joined)
+    };
+
+    // Scratch buffers reused across scan iterations so the hot loop does no
+    // per-iteration heap allocation. Sized to the input path up-front; push/pop
+    // may still grow on longer symlink targets, but most cases fit.
+    let cap = current_path.as_os_str().len();
+    let accumulated = &mut buffers.accumulated;
+    let normalized = &mut buffers.normalized;
+    accumulated.reserve(cap);
+    normalized.reserve(cap);
+
+    let mut iterations = 0;
+    let mut hops = ComponentHops::new(per_component_limit);
+    // How many leading components of `current_path` were already probed and found not
+    // to be symlinks. A relative target is spliced in after its link's parent, so the
+    // rescan resumes there instead of re-probing the whole prefix: without this, a
+    // path of n symlinked directories costs O(n^2) lookups.
+    let mut verified = 0;
+    // Hashes of every path scanned so far, one per follow. Following a symlink back
+    // to one of them would repeat the same walk, so that is reported as a loop on the
+    // spot instead of after `MAX_SYMLINK_FOLLOWS` rounds. Kept on the stack so a hop
+    // allocates nothing.
+    let mut visited = [0u64; MAX_SYMLINK_FOLLOWS as usize];
+    let mut visited_len = 0;
+
+    // We restart the scan whenever we resolve a symlink
+    let found = 'scan: loop {
+        if iterations >= MAX_SYMLINK_FOLLOWS {
+            break None;
+        }
+
+        // We CANNOT blindly normalize_path() here because if we have "symlink/..",
+        // normalize_path() will remove "symlink" and "..", completely missing the fact
+        // that "symlink" might point to a magic path.
+        //
+        // Instead, we must walk the components one by one. If we hit a symlink, we resolve it.
+        // If we hit "..", we pop from our accumulated path.
+
+        // Check if the path's lexical normalization is magic. This catches two
+        // distinct bypasses that plain `is_proc_magic_path(&current_path)` misses:
+        //
+        // 1. `..` in the PREFIX:  /proc/<PID>/../<PID>/root lexically normalizes
+        //    to /proc/<PID>/root. Without this check, the component walk below
+        //    reaches /proc/<PID>/root after the `..` pop, sees it is a symlink,
+        //    and follows it via `read_link` — which returns "/", dropping the
+        //    namespace boundary entirely.
+        //
+        // 2. `..` in the REMAINDER that lands back on a boundary: after resolving
+        //    an indirect symlink to /proc/<PID>, a path like .../cwd/../root has
+        //    current_path = /proc/<PID>/cwd/../root, which matches the cwd branch
+        //    with remainder `../root`. Returning it as-is sends the caller down
+        //    the host-resolution path in canonicalize_impl, which loses the
+        //    boundary. Lexical normalization gives /proc/<PID>/root directly.
+        lexical_normalize_into(&current_path, normalized);
+        if is_proc_magic_path(normalized) {
+            if *normalized != current_path {
+                record(trace, || Step::Normalized {
+                    before: current_path.to_path_buf(),
+                    after: normalized.clone(),
+                });
+            }
+            break Some(std::mem::take(normalized));
+        }
+
+        // 3. `..` right after a boundary reached through a symlink: a link to
+        //    /proc/<PID>/root followed by `..` rebuilds /proc/<PID>/root/.., which
+        //    normalizes to /proc/<PID> and so is not caught above. The walk below
+        //    would follow the magic link to "/" and leave the boundary; instead hand
+        //    it over as-is, so `..` is clamped or treated as an escape exactly as if
+        //    the caller had written the rebuilt path.
+        if iterations > 0 && is_proc_magic_path(&current_path) {
+            break Some(current_path.to_path_buf());
+        }
+
+        accumulated.clear();
+        let mut components = current_path.components().peekable();
+        let mut position = 0;
+
+        if let Some(Component::RootDir) = components.peek() {
+            accumulated.push("/");
+            components.next();
+            position += 1;
+        }
+
+        while let Some(component) = components.next() {
+            position += 1;
+            match component {
+                Component::RootDir => {
+                    accumulated.push("/");
+                }
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    accumulated.pop();
+                    // After popping, we might be at a magic path (e.g. /proc/self/root/etc/..)
+                    if is_proc_magic_path(accumulated) {
+                        // Append remaining components in place to preserve the suffix.
+                        accumulated.extend(components);
+                        break 'scan Some(std::mem::take(accumulated));
+                    }
+                }
+                Component::Normal(name) => {
+                    // Push first, then probe. On symlink we pop back to the parent
+                    // before resolving so relative targets rebase correctly.
+                    accumulated.push(name);
+                    if position <= verified {
+                        continue;
+                    }
+
+                    let kind = match fs.symlink_metadata(accumulated) {
+                        Ok(kind) => kind,
+                        Err(_) => continue,
+                    };
+
+                    if kind == FileKind::Symlink {
+                        iterations += 1;
+                        hops.follow(|| components.clone().count())?;
+                        // read_link returns an owned PathBuf; no borrowing API exists.
+                        let target = fs.read_link(accumulated)?;
+                        if target.as_os_str().is_empty() {
+                            // Linux refuses to create these, but other filesystems or
+                            // images can carry one. The kernel fails the lookup with
+                            // ENOENT; pushing "" would instead silently mean the parent.
+                            return Err(io::Error::new(
+                                io::ErrorKind::NotFound,
+                                "symlink has an empty target",
+                            ));
+                        }
+                        record(trace, || Step::FollowedSymlink {
+                            from: accumulated.clone(),
+                            to: target.clone(),
+                        });
+                        accumulated.pop(); // drop the symlink name
+                        verified = if target.has_root() {
+                            0
+                        } else {
+                            accumulated.components().count()
+                        };
+                        // PathBuf::push replaces when target is absolute, appends when relative.
+                        accumulated.push(target);
+                        accumulated.extend(components);
+                        let next_hash = path_hash(accumulated);
+                        if *accumulated == *current_path
+                            || visited
+                                .iter()
+                                .take(visited_len)
+                                .any(|&seen| seen == next_hash)
+                        {
+                            return Err(io::Error::from_raw_os_error(ELOOP));
+                        }
+                        // `iterations` stays below the array's length, so there is
+                        // always a free slot.
+                        if let Some(slot) = visited.get_mut(visited_len) {
+                            *slot = path_hash(&current_path);
+                            visited_len += 1;
+                        }
+                        // Scan the rebuilt path next; recycle the old one as scratch.
+                        let next = Cow::Owned(std::mem::take(accumulated));
+                        if let Cow::Owned(buffer) = std::mem::replace(&mut current_path, next) {
+                            *accumulated = buffer;
+                        }
+                        continue 'scan;
+                    }
+                }
+                Component::Prefix(_) => unreachable!("Linux paths don't have prefixes"),
+            }
+        }
+
+        // Scanned the whole path, no symlinks remain and the normalized form
+        // wasn't magic. One final check on the accumulated path.
+        if is_proc_magic_path(accumulated) {
+            break Some(std::mem::take(accumulated));
+        }
+
+        break None;
+    };
+
+    if let Cow::Owned(buffer) = current_path {
+        buffers.current = buffer;
+    }
+    Ok(found)
+}
+
+/// A hash of `path` for spotting a revisit, consistent with `Path` equality.
+///
+/// Two distinct paths share one with a chance of about 2^-64 per pair, which would
+/// report a loop that is not there; the 40 paths of one walk make that negligible.
+fn path_hash(path: &Path) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Symlinks followed for one logical component of the scanned path.
+///
+/// Every restart replaces a link with its target, so the component being resolved is
+/// identified by how many components still follow it: a hop with no fewer of them
+/// than the last is part of the same chain, e.g. a link inside the previous target.
+struct ComponentHops {
+    limit: Option<u32>,
+    /// Components after the link that started the current chain.
+    rest: usize,
+    count: u32,
+}
+
+impl ComponentHops {
+    fn new(limit: Option<u32>) -> Self {
+        ComponentHops {
+            limit,
+            rest: 0,
+            count: 0,
+        }
+    }
+
+    /// Count one hop with `rest()` components after the link.
+    fn follow(&mut self, rest: impl FnOnce() -> usize) -> io::Result<()> {
+        let limit = match self.limit {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+        let rest = rest();
+        if self.count == 0 || rest < self.rest {
+            // The walk got past the previous chain's component: a new chain starts.
+            self.rest = rest;
+            self.count = 0;
+        }
+        self.count += 1;
+        if self.count > limit {
+            return Err(io::Error::from_raw_os_error(ELOOP));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A filesystem where nothing can be looked up, as under a process whose `/proc`
+    /// entries this process may not read: every component is skipped unresolved.
+    struct Unreadable;
+
+    impl FsOps for Unreadable {
+        fn metadata(&self, _: &Path) -> io::Result<FileKind> {
+            Err(io::ErrorKind::PermissionDenied.into())
+        }
+
+        fn symlink_metadata(&self, _: &Path) -> io::Result<FileKind> {
+            Err(io::ErrorKind::PermissionDenied.into())
+        }
+
+        fn file_id(&self, _: &Path) -> io::Result<crate::fs::FileId> {
+            Err(io::ErrorKind::PermissionDenied.into())
+        }
+
+        fn read_link(&self, _: &Path) -> io::Result<PathBuf> {
+            Err(io::ErrorKind::PermissionDenied.into())
+        }
+
+        fn canonicalize(&self, _: &Path) -> io::Result<PathBuf> {
+            Err(io::ErrorKind::PermissionDenied.into())
+        }
+    }
+
+    fn scan(path: &str) -> Option<PathBuf> {
+        detect_indirect_proc_magic_link(&Unreadable, Path::new(path), None, None, None).unwrap()
+    }
+
+    // ==========================================================================
+    // `..` POPPING BACK ONTO A BOUNDARY
+    // The whole path normalizes to something that is not a boundary, so only the
+    // check after each pop can find it.
+    // ==========================================================================
+
+    #[test]
+    fn pop_onto_root_boundary_is_detected() {
+        assert_eq!(
+            scan("/proc/1234/root/etc/../../x"),
+            Some(PathBuf::from("/proc/1234/root/../x"))
+        );
+    }
+
+    #[test]
+    fn pop_onto_task_level_boundaries_is_detected() {
+        for (path, boundary) in [
+            (
+                "/proc/1234/task/5678/root/etc/../../x",
+                "/proc/1234/task/5678/root/../x",
+            ),
+            (
+                "/proc/1234/task/5678/cwd/src/../../x",
+                "/proc/1234/task/5678/cwd/../x",
+            ),
+            // The first pop already lands inside the boundary; the rest is left to
+            // the resolver.
+            (
+                "/proc/self/task/5678/root/a/b/../../../x",
+                "/proc/self/task/5678/root/a/../../x",
+            ),
+            (
+                "/proc/thread-self/cwd/src/../../x",
+                "/proc/thread-self/cwd/../x",
+            ),
+        ] {
+            assert_eq!(scan(path), Some(PathBuf::from(boundary)), "{path}");
+        }
+    }
+
+    #[test]
+    fn pop_onto_task_directory_is_not_a_boundary() {
+        assert_eq!(scan("/proc/1234/task/5678/x/../../y"), None);
+    }
+
+    // ==========================================================================
+    // SCRATCH BUFFERS
+    // ==========================================================================
+
+    #[test]
+    fn scratch_buffers_come_back_with_their_capacity() {
+        let scratch = RefCell::new(ScanBuffers::default());
+
+        let found = detect_indirect_proc_magic_link(
+            &Unreadable,
+            Path::new("/srv/data/x"),
+            None,
+            None,
+            Some(&scratch),
+        )
+        .unwrap();
+
+        assert_eq!(found, None);
+        let buffers = scratch.into_inner();
+        assert!(buffers.accumulated.capacity() >= "/srv/data/x".len());
+        assert!(buffers.normalized.capacity() >= "/srv/data/x".len());
+    }
+}
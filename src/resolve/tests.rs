@@ -0,0 +1,394 @@
+//! Resolver unit tests.
+
+use super::*;
+use std::os::unix::fs::symlink;
+
+fn canonicalize_through(path: &Path, boundary_target: &Path) -> io::Result<PathBuf> {
+    let options = CanonicalizeOptions::new().stand_in_root(boundary_target);
+    Resolver::new(&options).canonicalize(path)
+}
+
+// ==========================================================================
+// CONTAINER-LIKE BOUNDARY TARGETS (CanonicalizeOptions::stand_in_root)
+// The boundary resolves to a directory other than "/", so the re-basing
+// and escape branches are exercised the way they are inside a container.
+// ==========================================================================
+
+#[test]
+fn subpath_inside_stand_in_root_keeps_namespace_prefix() {
+    let container_root = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(container_root.path().join("etc/app")).unwrap();
+
+    let canonical = canonicalize_through(
+        Path::new("/proc/self/root/etc/./app"),
+        container_root.path(),
+    )
+    .unwrap();
+
+    assert_eq!(canonical, Path::new("/proc/self/root/etc/app"));
+}
+
+#[test]
+fn bare_boundary_with_stand_in_root_is_preserved() {
+    let container_root = tempfile::tempdir().unwrap();
+
+    let canonical =
+        canonicalize_through(Path::new("/proc/1234/root"), container_root.path()).unwrap();
+
+    assert_eq!(canonical, Path::new("/proc/1234/root"));
+}
+
+#[test]
+fn absolute_symlink_out_of_stand_in_root_returns_host_path() {
+    let host_dir = tempfile::tempdir().unwrap();
+    let container_root = tempfile::tempdir().unwrap();
+    symlink(host_dir.path(), container_root.path().join("escape")).unwrap();
+
+    let canonical =
+        canonicalize_through(Path::new("/proc/self/root/escape"), container_root.path()).unwrap();
+
+    let host_target = std::fs::canonicalize(host_dir.path()).unwrap();
+    assert_eq!(canonical, host_target);
+}
+
+#[test]
+fn dotdot_above_stand_in_cwd_returns_host_parent() {
+    let workdir_parent = tempfile::tempdir().unwrap();
+    let process_cwd = workdir_parent.path().join("workdir");
+    std::fs::create_dir(&process_cwd).unwrap();
+
+    let canonical = canonicalize_through(Path::new("/proc/self/cwd/.."), &process_cwd).unwrap();
+
+    let host_parent = std::fs::canonicalize(workdir_parent.path()).unwrap();
+    assert_eq!(canonical, host_parent);
+}
+
+#[test]
+fn missing_stand_in_root_reports_not_found() {
+    let container_root = tempfile::tempdir().unwrap();
+    let vanished_root = container_root.path().join("gone");
+
+    let err = canonicalize_through(Path::new("/proc/self/root/etc"), &vanished_root).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn indirect_symlink_reuses_stand_in_root() {
+    let container_root = tempfile::tempdir().unwrap();
+    std::fs::create_dir(container_root.path().join("srv")).unwrap();
+    let links = tempfile::tempdir().unwrap();
+    let container_link = links.path().join("container");
+    symlink("/proc/self/root", &container_link).unwrap();
+
+    let canonical =
+        canonicalize_through(&container_link.join("srv"), container_root.path()).unwrap();
+
+    assert_eq!(canonical, Path::new("/proc/self/root/srv"));
+}
+
+#[test]
+fn resolve_outer_to_host_returns_stand_in_directory_path() {
+    let container_root = tempfile::tempdir().unwrap();
+    std::fs::create_dir(container_root.path().join("srv")).unwrap();
+    let options = CanonicalizeOptions::new()
+        .resolve_outer_to_host(true)
+        .stand_in_root(container_root.path());
+
+    let resolved = Resolver::new(&options)
+        .canonicalize(Path::new("/proc/1234/root/srv"))
+        .unwrap();
+
+    let host_srv = std::fs::canonicalize(container_root.path().join("srv")).unwrap();
+    assert_eq!(resolved, host_srv);
+}
+
+// ==========================================================================
+// BOUNDARIES RESOLVING TO "/"
+// strip_prefix("/") leaves the whole subpath, which must be re-attached intact.
+// ==========================================================================
+
+#[test]
+fn host_root_boundary_rebases_multi_level_subpath_exactly() {
+    let canonical = canonicalize_impl(Path::new("/proc/self/root/usr/lib")).unwrap();
+
+    assert_eq!(canonical, Path::new("/proc/self/root/usr/lib"));
+}
+
+#[test]
+fn stand_in_spelled_with_trailing_slash_rebases_exactly() {
+    let container_root = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(container_root.path().join("usr/lib")).unwrap();
+    let with_slash = container_root.path().join("");
+
+    let canonical =
+        canonicalize_through(Path::new("/proc/1234/root/usr/lib"), &with_slash).unwrap();
+
+    assert_eq!(canonical, Path::new("/proc/1234/root/usr/lib"));
+}
+
+#[test]
+fn stand_in_at_filesystem_root_rebases_exactly() {
+    let canonical =
+        canonicalize_through(Path::new("/proc/1234/root/usr/lib"), Path::new("/")).unwrap();
+
+    assert_eq!(canonical, Path::new("/proc/1234/root/usr/lib"));
+}
+
+// ==========================================================================
+// FILESYSTEM OPERATIONS PER CALL
+// Counted through the FsOps layer: a stand-in for timing that does not vary
+// between machines.
+// ==========================================================================
+
+/// [`RealFs`], counting each operation the resolver starts.
+#[derive(Default)]
+struct CountingFs {
+    ops: RefCell<Vec<&'static str>>,
+}
+
+impl FsOps for CountingFs {
+    fn metadata(&self, path: &Path) -> io::Result<crate::fs::FileKind> {
+        self.ops.borrow_mut().push("metadata");
+        RealFs.metadata(path)
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<crate::fs::FileKind> {
+        self.ops.borrow_mut().push("symlink_metadata");
+        RealFs.symlink_metadata(path)
+    }
+
+    fn file_id(&self, path: &Path) -> io::Result<crate::fs::FileId> {
+        self.ops.borrow_mut().push("file_id");
+        RealFs.file_id(path)
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        self.ops.borrow_mut().push("read_link");
+        RealFs.read_link(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.ops.borrow_mut().push("canonicalize");
+        RealFs.canonicalize(path)
+    }
+}
+
+fn ops_for(options: &CanonicalizeOptions, path: &str) -> Vec<&'static str> {
+    let fs = CountingFs::default();
+    Resolver::new(options)
+        .with_fs(&fs)
+        .canonicalize(Path::new(path))
+        .unwrap();
+    fs.ops.into_inner()
+}
+
+#[test]
+fn already_canonical_subpath_costs_two_realpaths() {
+    let ops = ops_for(&CanonicalizeOptions::new(), "/proc/self/root/etc");
+
+    assert_eq!(ops, ["canonicalize", "canonicalize"]);
+}
+
+#[test]
+fn memoized_already_canonical_subpath_costs_one_realpath_and_a_stat() {
+    let options = CanonicalizeOptions::new().memoize_boundaries(true);
+    ops_for(&options, "/proc/self/root/etc");
+
+    let ops = ops_for(&options, "/proc/self/root/etc");
+
+    assert_eq!(ops, ["file_id", "canonicalize"]);
+}
+
+#[test]
+fn self_loop_is_caught_on_the_first_revisit() {
+    let temp = tempfile::tempdir().unwrap();
+    let link = std::fs::canonicalize(temp.path()).unwrap().join("x");
+    symlink(&link, &link).unwrap();
+    let fs = CountingFs::default();
+
+    let err = Resolver::new(&CanonicalizeOptions::new())
+        .with_fs(&fs)
+        .canonicalize(&link)
+        .unwrap_err();
+
+    assert_eq!(err.raw_os_error(), Some(ELOOP));
+    let ops = fs.ops.into_inner();
+    assert_eq!(ops.iter().filter(|op| **op == "read_link").count(), 1);
+    assert!(!ops.contains(&"canonicalize"), "{ops:?}");
+}
+
+#[test]
+fn bare_boundary_costs_one_stat() {
+    let ops = ops_for(&CanonicalizeOptions::new(), "/proc/self/root");
+
+    assert_eq!(ops, ["metadata"]);
+}
+
+/// `base/a/b/.../` with every component a relative symlink to a sibling directory.
+fn all_symlink_path(base: &Path, depth: usize) -> PathBuf {
+    let mut dir = base.to_path_buf();
+    let mut path = base.to_path_buf();
+    for name in (b'a'..)
+        .take(depth)
+        .map(|byte| char::from(byte).to_string())
+    {
+        let real = format!("{name}.d");
+        std::fs::create_dir(dir.join(&real)).unwrap();
+        symlink(&real, dir.join(&name)).unwrap();
+        dir.push(real);
+        path.push(name);
+    }
+    path
+}
+
+#[test]
+fn deep_all_symlink_path_probes_each_component_once() {
+    let temp = tempfile::tempdir().unwrap();
+    let base = std::fs::canonicalize(temp.path()).unwrap();
+    let depth = 26;
+    let path = all_symlink_path(&base, depth);
+    let fs = CountingFs::default();
+
+    let resolved = Resolver::new(&CanonicalizeOptions::new())
+        .with_fs(&fs)
+        .canonicalize(&path)
+        .unwrap();
+
+    assert_eq!(resolved, std::fs::canonicalize(&path).unwrap());
+    let probes = fs
+        .ops
+        .into_inner()
+        .into_iter()
+        .filter(|op| *op == "symlink_metadata")
+        .count();
+    // The base once, then each link and the directory it leads to once: linear, where
+    // rescanning from the root would re-probe the whole prefix after every link.
+    let base_components = base.components().count() - 1;
+    assert_eq!(probes, base_components + 2 * depth);
+}
+
+#[test]
+fn dotdot_after_resumed_symlinks_matches_kernel() {
+    let temp = tempfile::tempdir().unwrap();
+    let base = std::fs::canonicalize(temp.path()).unwrap();
+    let path = all_symlink_path(&base, 6);
+
+    for suffix in ["..", "../..", "../../../x/..", "../../../../../../.."] {
+        let input = path.join(suffix);
+        let expected = std::fs::canonicalize(&input);
+        let resolved = Resolver::new(&CanonicalizeOptions::new()).canonicalize(&input);
+        match expected {
+            Ok(expected) => assert_eq!(resolved.unwrap(), expected, "{suffix}"),
+            Err(err) => assert_eq!(resolved.unwrap_err().kind(), err.kind(), "{suffix}"),
+        }
+    }
+}
+
+// ==========================================================================
+// INACCESSIBLE BOUNDARIES (CanonicalizeOptions::preserve_on_inaccessible_boundary)
+// Permissions cannot be taken away from root, so the denial is simulated.
+// ==========================================================================
+
+/// [`RealFs`], except that everything at or below `denied` fails with `EACCES`.
+struct DeniedFs {
+    denied: &'static str,
+}
+
+impl DeniedFs {
+    fn check(&self, path: &Path) -> io::Result<()> {
+        if path.starts_with(self.denied) {
+            Err(io::ErrorKind::PermissionDenied.into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl FsOps for DeniedFs {
+    fn metadata(&self, path: &Path) -> io::Result<crate::fs::FileKind> {
+        self.check(path).and_then(|()| RealFs.metadata(path))
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<crate::fs::FileKind> {
+        self.check(path)
+            .and_then(|()| RealFs.symlink_metadata(path))
+    }
+
+    fn file_id(&self, path: &Path) -> io::Result<crate::fs::FileId> {
+        self.check(path).and_then(|()| RealFs.file_id(path))
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        self.check(path).and_then(|()| RealFs.read_link(path))
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.check(path).and_then(|()| RealFs.canonicalize(path))
+    }
+}
+
+fn denied_at(denied: &'static str, preserve: bool, path: &str) -> io::Result<PathBuf> {
+    let options = CanonicalizeOptions::new().preserve_on_inaccessible_boundary(preserve);
+    Resolver::new(&options)
+        .with_fs(&DeniedFs { denied })
+        .canonicalize(Path::new(path))
+}
+
+#[test]
+fn denied_boundary_fails_by_default() {
+    let err = denied_at("/proc/self/root", false, "/proc/self/root/etc").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn denied_boundary_returns_the_folded_input_when_asked() {
+    for (input, expected) in [
+        ("/proc/self/root", "/proc/self/root"),
+        ("/proc/self/root/", "/proc/self/root"),
+        ("/proc/self/root/etc", "/proc/self/root/etc"),
+        ("/proc/self/root/./etc/../srv/", "/proc/self/root/srv"),
+        (
+            "/proc/self/root/../../no/such/file",
+            "/proc/self/root/no/such/file",
+        ),
+    ] {
+        assert_eq!(
+            denied_at("/proc/self/root", true, input).unwrap(),
+            Path::new(expected),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn denied_component_below_an_accessible_boundary_still_fails() {
+    let err = denied_at("/proc/self/root/etc", true, "/proc/self/root/etc/hostname").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn denied_boundary_is_preserved_even_when_the_host_form_is_asked_for() {
+    let options = CanonicalizeOptions::new()
+        .preserve_on_inaccessible_boundary(true)
+        .resolve_outer_to_host(true);
+    let resolved = Resolver::new(&options)
+        .with_fs(&DeniedFs {
+            denied: "/proc/self/root",
+        })
+        .canonicalize(Path::new("/proc/self/root/etc"))
+        .unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+}
+
+#[test]
+fn no_cross_only_lstats_the_boundary() {
+    let options = CanonicalizeOptions::new().no_cross(true);
+
+    let ops = ops_for(&options, "/proc/self/root/etc/../no/such/file");
+
+    assert_eq!(ops, ["symlink_metadata"]);
+}
@@ -0,0 +1,60 @@
+//! File identity across namespace and host spellings of a path.
+
+use std::io;
+use std::path::Path;
+
+/// Whether `a` and `b` name the same file, however each is spelled.
+///
+/// This deliberately crosses namespace boundaries: `/proc/1234/root/etc/hostname` and
+/// the host's `/etc/hostname` compare equal when they are the same inode, which is
+/// what dedup logic receiving mixed representations needs. Both paths are opened by
+/// the kernel (following every symlink and magic link) and compared by device and
+/// inode number.
+///
+/// The comparison does not go through host path *strings*: a boundary's host spelling
+/// can name a different file in another mount namespace, while the device and inode
+/// reached through the magic link cannot.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use proc_canonicalize::same_file;
+///
+/// let dir = tempfile::tempdir()?;
+/// let host_path = dir.path().join("hostname");
+/// std::fs::write(&host_path, "box")?;
+/// let namespace_path = format!("/proc/self/root{}", host_path.display());
+///
+/// assert!(same_file(&namespace_path, &host_path)?);
+/// assert!(!same_file(&namespace_path, dir.path())?);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Platform Behavior
+///
+/// - **Unix**: Compares device and inode numbers
+/// - **Other platforms**: Compares the results of [`canonicalize`](crate::canonicalize)
+///
+/// # Errors
+///
+/// Returns an error if either path does not exist or cannot be accessed; the caller
+/// needs search permission along both paths, including into the target namespace.
+pub fn same_file(a: impl AsRef<Path>, b: impl AsRef<Path>) -> io::Result<bool> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let a = std::fs::metadata(a)?;
+        let b = std::fs::metadata(b)?;
+        Ok(a.dev() == b.dev() && a.ino() == b.ino())
+    }
+    #[cfg(not(unix))]
+    {
+        Ok(crate::canonicalize(a)? == crate::canonicalize(b)?)
+    }
+}
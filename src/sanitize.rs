@@ -0,0 +1,102 @@
+//! Errors that do not reveal whether a path exists, for tenant-facing responses.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// `ENOTDIR` on Linux, macOS and the BSDs: not a directory.
+#[cfg(unix)]
+const ENOTDIR: i32 = 20;
+
+/// A path could not be resolved, for a reason deliberately not shown.
+///
+/// Returned as the payload of an [`io::Error`] of kind [`io::ErrorKind::Other`] by
+/// [`canonicalize_sanitized`]. Its [`Display`](fmt::Display) is the same fixed message
+/// whatever happened; the real error is kept for server-side logging and is reached
+/// with [`original`](Self::original) or [`std::error::Error::source`]. Note that the
+/// derived [`Debug`] output includes it, so format tenant responses with `{}`.
+#[derive(Debug)]
+pub struct Inaccessible {
+    original: io::Error,
+}
+
+impl Inaccessible {
+    /// The error resolution actually failed with.
+    pub fn original(&self) -> &io::Error {
+        &self.original
+    }
+
+    /// The error resolution actually failed with, by value.
+    pub fn into_original(self) -> io::Error {
+        self.original
+    }
+}
+
+impl fmt::Display for Inaccessible {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("path is not accessible")
+    }
+}
+
+impl std::error::Error for Inaccessible {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.original)
+    }
+}
+
+/// [`canonicalize`](crate::canonicalize), with errors that could reveal whether the
+/// path exists replaced by one opaque error.
+///
+/// In a multi-tenant service, answering "not found" for `/proc/PID/root/secret` but
+/// "permission denied" for `/proc/PID/root/private/secret` tells the tenant which
+/// files exist. Here both come back as [`io::ErrorKind::Other`] carrying an
+/// [`Inaccessible`] payload with a fixed message. So does "not a directory", which
+/// reveals that a file exists where a directory was expected. Other failures, e.g. a
+/// symlink loop or an I/O error, are returned unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() {
+/// use proc_canonicalize::{canonicalize_sanitized, Inaccessible};
+///
+/// let err = canonicalize_sanitized("/proc/self/root/no/such/file").unwrap_err();
+/// assert_eq!(err.to_string(), "path is not accessible");
+///
+/// // Server side, the real cause is still there
+/// let inaccessible = err.get_ref().and_then(|e| e.downcast_ref::<Inaccessible>()).unwrap();
+/// assert_eq!(inaccessible.original().kind(), std::io::ErrorKind::NotFound);
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// [`io::ErrorKind::Other`] with an [`Inaccessible`] payload where
+/// [`canonicalize`](crate::canonicalize) fails with [`io::ErrorKind::NotFound`],
+/// [`io::ErrorKind::PermissionDenied`] or `ENOTDIR`; otherwise the same error.
+pub fn canonicalize_sanitized(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    crate::canonicalize(path).map_err(sanitize)
+}
+
+/// `err`, wrapped in [`Inaccessible`] if it would reveal whether the path exists.
+pub(crate) fn sanitize(err: io::Error) -> io::Error {
+    if reveals_existence(&err) {
+        io::Error::new(io::ErrorKind::Other, Inaccessible { original: err })
+    } else {
+        err
+    }
+}
+
+fn reveals_existence(err: &io::Error) -> bool {
+    #[cfg(unix)]
+    if err.raw_os_error() == Some(ENOTDIR) {
+        return true;
+    }
+    matches!(
+        err.kind(),
+        io::ErrorKind::NotFound | io::ErrorKind::PermissionDenied
+    )
+}
@@ -0,0 +1,111 @@
+//! Reusable buffers and shared results for allocation-sensitive callers.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Buffers that [`canonicalize_with_scratch`] reuses from one call to the next.
+///
+/// Resolving a path that is not under `/proc` scans it for symlinks into `/proc`,
+/// building candidate paths as it goes. A `Scratch` keeps those buffers between calls:
+/// they are cleared, not freed, so a loop resolving many paths allocates them once and
+/// then only when a longer path needs them to grow. The returned path is still
+/// allocated per call.
+///
+/// A `Scratch` is used by one call at a time, which `&mut` enforces. It is `Send`, so
+/// the usual arrangement is one per thread, e.g. in a `thread_local!` or owned by each
+/// worker. On platforms other than Linux it holds nothing.
+#[derive(Debug, Default)]
+pub struct Scratch {
+    #[cfg(target_os = "linux")]
+    buffers: crate::resolve::ScanBuffers,
+}
+
+impl Scratch {
+    /// Empty buffers; they grow on first use.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// [`canonicalize`](crate::canonicalize), building intermediate paths in `scratch`
+/// instead of fresh allocations.
+///
+/// The result is exactly that of `canonicalize(path)`. See [`Scratch`] for what is
+/// reused and how to share one across threads.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::{canonicalize_with_scratch, Scratch};
+///
+/// let mut scratch = Scratch::new();
+/// for path in ["/proc/self/root/etc", "/etc/.", "/proc/self/root/usr/../etc"] {
+///     let resolved = canonicalize_with_scratch(path, &mut scratch)?;
+///     assert_eq!(resolved, proc_canonicalize::canonicalize(path)?);
+/// }
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Same as [`canonicalize`](crate::canonicalize). A failed call leaves `scratch`
+/// usable.
+pub fn canonicalize_with_scratch(
+    path: impl AsRef<Path>,
+    scratch: &mut Scratch,
+) -> io::Result<PathBuf> {
+    #[cfg(target_os = "linux")]
+    {
+        let options = crate::CanonicalizeOptions::default();
+        let buffers = std::cell::RefCell::new(std::mem::take(&mut scratch.buffers));
+        let resolved = crate::resolve::Resolver::new(&options)
+            .with_scratch(&buffers)
+            .canonicalize(path.as_ref());
+        scratch.buffers = buffers.into_inner();
+        resolved
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = scratch;
+        crate::canonicalize(path)
+    }
+}
+
+/// [`canonicalize`](crate::canonicalize), returned as an [`Arc<Path>`] to share.
+///
+/// For a resolved path held by many tasks at once, such as a container's root: each
+/// consumer clones the [`Arc`], which bumps a count instead of copying the path. The
+/// result is copied once into the shared allocation. Any other result converts the same
+/// way, e.g. `Arc::<Path>::from(options.canonicalize(path)?)`.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use std::sync::Arc;
+/// use proc_canonicalize::canonicalize_arc;
+///
+/// let root = canonicalize_arc("/proc/self/root/etc/.")?;
+/// let for_worker = Arc::clone(&root);
+/// assert_eq!(&*for_worker, Path::new("/proc/self/root/etc"));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Same as [`canonicalize`](crate::canonicalize).
+pub fn canonicalize_arc(path: impl AsRef<Path>) -> io::Result<Arc<Path>> {
+    crate::canonicalize(path).map(Arc::from)
+}
@@ -0,0 +1,88 @@
+//! Canonicalizing into forms built from the boundary: split from the relative path
+//! inside it, or written without the IDs that differ between runs.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::lexical::{find_namespace_boundary, parse_boundary, StableFormat};
+
+/// [`canonicalize`](crate::canonicalize), split into the boundary and the path below it.
+///
+/// Returns `(Some(boundary), subpath)` when the result is under a boundary, with
+/// `subpath` relative and free of `.`, `..` and symlinks: `/proc/1234/root/etc/passwd`
+/// becomes `(Some("/proc/1234/root"), "etc/passwd")`, ready to store keyed by
+/// container. A path that resolves to the boundary itself has an empty subpath.
+/// Joining the two gives back the result of [`canonicalize`](crate::canonicalize).
+///
+/// Returns `(None, host_path)` when the result has no boundary: the input had none, or
+/// it escaped its boundary and came back as a host path (see the Escapes section of
+/// [`canonicalize`](crate::canonicalize)). On platforms other than Linux the boundary is
+/// always `None`.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::canonicalize_split;
+///
+/// let (boundary, subpath) = canonicalize_split("/proc/self/root/./etc/")?;
+/// assert_eq!(boundary.as_deref(), Some(Path::new("/proc/self/root")));
+/// assert_eq!(subpath, Path::new("etc"));
+///
+/// let (boundary, host) = canonicalize_split("/etc")?;
+/// assert_eq!(boundary, None);
+/// assert_eq!(host, std::fs::canonicalize("/etc")?);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Any error [`canonicalize`](crate::canonicalize) returns.
+pub fn canonicalize_split(path: impl AsRef<Path>) -> io::Result<(Option<PathBuf>, PathBuf)> {
+    let canonical = crate::canonicalize(path)?;
+    Ok(match find_namespace_boundary(&canonical) {
+        Some((boundary, subpath)) => (Some(boundary), subpath),
+        None => (None, canonical),
+    })
+}
+
+/// [`canonicalize`](crate::canonicalize), written with placeholders for process and
+/// thread IDs, for snapshots that must match across machines.
+///
+/// A result under a boundary is rendered by [`StableFormat`]: `/proc/self/root/etc`
+/// and `/proc/1234/root/etc` both become `/proc/<PID>/root/etc`. Any other result is
+/// written as it is, with non-UTF-8 bytes replaced as by [`Path::display`]. Only the
+/// boundary is rewritten; a temporary directory elsewhere in the path still varies.
+/// The string is for comparing, not for opening: it names no real file.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use proc_canonicalize::canonicalize_stable;
+///
+/// let pid = std::process::id();
+/// assert_eq!(canonicalize_stable(format!("/proc/{pid}/root/etc/."))?, "/proc/<PID>/root/etc");
+/// assert_eq!(canonicalize_stable("/proc/self/root/etc")?, "/proc/<PID>/root/etc");
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Any error [`canonicalize`](crate::canonicalize) returns.
+pub fn canonicalize_stable(path: impl AsRef<Path>) -> io::Result<String> {
+    let canonical = crate::canonicalize(path)?;
+    Ok(match parse_boundary(&canonical) {
+        Some(boundary) => boundary.display_with(StableFormat).to_string(),
+        None => canonical.display().to_string(),
+    })
+}
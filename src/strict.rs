@@ -0,0 +1,70 @@
+//! Resolution under a policy that forbids symlinks outside the `/proc` boundary itself.
+
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::lexical::namespace_prefix_len;
+
+/// [`canonicalize`](crate::canonicalize), failing if the path goes through any symlink
+/// other than the leading namespace boundary.
+///
+/// For policies that pre-verify there are no unexpected links. Every component is
+/// checked with `symlink_metadata`, as written, before the path is resolved. The one
+/// exception is the leading boundary prefix, whose magic links (`/proc/PID/root`, and
+/// `/proc/self` or `/proc/thread-self` on the way there) are the intended crossing.
+/// A second boundary further along, such as `/proc/1/root/proc/2/root`, counts as a
+/// symlink like any other.
+///
+/// The check and the resolution are separate steps: a symlink created between them is
+/// followed. Use this to enforce a layout you control, not to defend against a
+/// concurrent attacker.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::canonicalize_reject_symlinks;
+///
+/// let dir = tempfile::tempdir()?;
+/// std::fs::create_dir(dir.path().join("real"))?;
+/// std::os::unix::fs::symlink("real", dir.path().join("alias"))?;
+///
+/// assert!(canonicalize_reject_symlinks(dir.path().join("real")).is_ok());
+/// assert!(canonicalize_reject_symlinks(dir.path().join("alias")).is_err());
+/// assert_eq!(
+///     canonicalize_reject_symlinks("/proc/self/root")?,
+///     Path::new("/proc/self/root"),
+/// );
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Fails with [`io::ErrorKind::InvalidInput`] naming the first symlink found, with the
+/// error `symlink_metadata` returns for a component that cannot be inspected (e.g.
+/// [`io::ErrorKind::NotFound`]), and otherwise as [`canonicalize`](crate::canonicalize).
+pub fn canonicalize_reject_symlinks(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    let boundary_len = namespace_prefix_len(path).unwrap_or(0);
+
+    let mut prefix = PathBuf::with_capacity(path.as_os_str().len());
+    for (index, component) in path.components().enumerate() {
+        prefix.push(component);
+        if !matches!(component, Component::Normal(_)) || index < boundary_len {
+            continue;
+        }
+        if std::fs::symlink_metadata(&prefix)?.file_type().is_symlink() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("path goes through symlink {}", prefix.display()),
+            ));
+        }
+    }
+
+    crate::canonicalize(path)
+}
@@ -0,0 +1,97 @@
+//! Resolving many paths under one known boundary.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::lexical::parse_boundary;
+use crate::resolve::Resolver;
+use crate::{CanonicalizeOptions, NamespaceKind};
+
+/// Canonicalize each of `subpaths` under `boundary`, which is resolved only once.
+///
+/// For the pattern of expanding a glob under `/proc/PID/root` and canonicalizing every
+/// match: each result is what [`canonicalize`](crate::canonicalize) returns for it, but
+/// the paths are not searched for a boundary (the caller already knows it), and the
+/// boundary's host directory is resolved up front and then only revalidated with one
+/// `stat` per path, as with
+/// [`memoize_boundaries`](CanonicalizeOptions::memoize_boundaries). A path costs one
+/// `realpath` instead of two.
+///
+/// `boundary` must be a bare boundary such as `/proc/1234/root` or `/proc/self/cwd`.
+/// Each subpath is either absolute and under `boundary` (`/proc/1234/root/etc/hosts`,
+/// as a glob yields it) or relative to it (`etc/hosts`). Results are in the order of
+/// `subpaths`, one per input.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::resolve_subpaths_under;
+///
+/// let results = resolve_subpaths_under(
+///     "/proc/self/root",
+///     ["/proc/self/root/etc/./", "usr/../etc", "no/such/entry"],
+/// )?;
+///
+/// assert_eq!(results[0].as_ref().unwrap(), Path::new("/proc/self/root/etc"));
+/// assert_eq!(results[1].as_ref().unwrap(), Path::new("/proc/self/root/etc"));
+/// assert!(results[2].is_err());
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// The call fails as a whole with [`io::ErrorKind::InvalidInput`] if `boundary` is not
+/// a bare `root` or `cwd` boundary, and with the error
+/// [`canonicalize`](crate::canonicalize) gives the boundary itself if it cannot be
+/// resolved, e.g. a [`ProcessGone`](crate::ProcessGone) payload for a PID with no
+/// process. Per path, an absolute path not under `boundary` is an
+/// [`io::ErrorKind::InvalidInput`] error, and anything else is the error
+/// [`canonicalize`](crate::canonicalize) would return for that path.
+pub fn resolve_subpaths_under<I>(
+    boundary: impl AsRef<Path>,
+    subpaths: I,
+) -> io::Result<Vec<io::Result<PathBuf>>>
+where
+    I: IntoIterator,
+    I::Item: AsRef<Path>,
+{
+    let boundary = boundary.as_ref();
+    let is_directory_boundary = parse_boundary(boundary).is_some_and(|parsed| {
+        parsed.remainder().as_os_str().is_empty() && parsed.kind() != NamespaceKind::Ns
+    });
+    if !is_directory_boundary {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "not a bare /proc/PID/root or /proc/PID/cwd boundary",
+        ));
+    }
+
+    let options = CanonicalizeOptions::new().memoize_boundaries(true);
+    let resolver = Resolver::new(&options);
+    // Fails for a missing process, and leaves a `root` boundary's host path cached.
+    resolver.with_host().canonicalize_both(boundary)?;
+
+    Ok(subpaths
+        .into_iter()
+        .map(|subpath| {
+            let subpath = subpath.as_ref();
+            let remainder = if subpath.is_absolute() {
+                subpath.strip_prefix(boundary).map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "path is not under the boundary",
+                    )
+                })?
+            } else {
+                subpath
+            };
+            resolver.canonicalize_under(boundary, remainder)
+        })
+        .collect())
+}
@@ -0,0 +1,182 @@
+//! Step-by-step resolution logs for debugging and teaching.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[cfg(target_os = "linux")]
+use std::cell::RefCell;
+
+/// One step taken while resolving a path, as reported by [`canonicalize_verbose`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Step {
+    /// The symlink at `from` was read; its raw target is `to`.
+    FollowedSymlink {
+        /// The symlink's own path.
+        from: PathBuf,
+        /// The target as stored in the link, possibly relative.
+        to: PathBuf,
+    },
+    /// A namespace boundary prefix (e.g. `/proc/1234/root`) was found and will be preserved.
+    DetectedBoundary(PathBuf),
+    /// `.` and `..` were folded lexically, exposing a boundary.
+    Normalized {
+        /// The path before folding.
+        before: PathBuf,
+        /// The path after folding.
+        after: PathBuf,
+    },
+    /// [`std::fs::canonicalize`] was called on this path.
+    CalledStdCanonicalize(PathBuf),
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::FollowedSymlink { from, to } => {
+                write!(f, "followed symlink {} -> {}", from.display(), to.display())
+            }
+            Step::DetectedBoundary(prefix) => {
+                write!(f, "detected boundary {}", prefix.display())
+            }
+            Step::Normalized { before, after } => {
+                write!(f, "normalized {} to {}", before.display(), after.display())
+            }
+            Step::CalledStdCanonicalize(path) => {
+                write!(f, "called std::fs::canonicalize on {}", path.display())
+            }
+        }
+    }
+}
+
+/// [`canonicalize`](crate::canonicalize), also returning every step it took.
+///
+/// The result is identical to `canonicalize(path)`. The log lists, in order, each
+/// symlink followed while looking for indirect boundaries, each boundary detected,
+/// each lexical normalization that exposed one, and each call into
+/// [`std::fs::canonicalize`]. Intended for debugging tools and snapshot tests; the
+/// plain [`canonicalize`](crate::canonicalize) keeps no log.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::{Path, PathBuf};
+/// use proc_canonicalize::{canonicalize_verbose, Step};
+///
+/// let (resolved, steps) = canonicalize_verbose("/proc/self/root/etc")?;
+/// assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+/// assert_eq!(steps.first(), Some(&Step::DetectedBoundary(PathBuf::from("/proc/self/root"))));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Platform Behavior
+///
+/// - **Linux**: The full log as described above
+/// - **Other platforms**: A single [`Step::CalledStdCanonicalize`]
+///
+/// # Errors
+///
+/// Same as [`canonicalize`](crate::canonicalize). The log is discarded on error.
+pub fn canonicalize_verbose(path: impl AsRef<Path>) -> io::Result<(PathBuf, Vec<Step>)> {
+    let (resolved, steps) = traced(path.as_ref());
+    Ok((resolved?, steps))
+}
+
+/// [`canonicalize`](crate::canonicalize), writing each step it takes to `out` as text.
+///
+/// For debugging in the field: point it at stderr to see why a path resolves the way
+/// it does, with no feature to enable and no rebuild. The steps are those of
+/// [`canonicalize_verbose`], one numbered line each, between a line naming the input
+/// and a final line with the result or the error. Unlike
+/// [`canonicalize_verbose`], the steps are written even when resolution fails, which is
+/// usually when they are wanted.
+///
+/// The return value is exactly that of `canonicalize(path)`. Errors writing to `out`
+/// are ignored, so a closed or full stream cannot change it. The plain
+/// [`canonicalize`](crate::canonicalize) records nothing, so this costs nothing unless
+/// called.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::canonicalize_trace_to_writer;
+///
+/// // In the field, `&mut std::io::stderr()`.
+/// let mut log = Vec::new();
+/// let resolved = canonicalize_trace_to_writer("/proc/self/root/etc", &mut log)?;
+/// assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+///
+/// let log = String::from_utf8(log).unwrap();
+/// assert!(log.contains("detected boundary /proc/self/root"));
+/// assert!(log.ends_with("result: /proc/self/root/etc\n"));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Same as [`canonicalize`](crate::canonicalize).
+pub fn canonicalize_trace_to_writer(
+    path: impl AsRef<Path>,
+    out: &mut dyn io::Write,
+) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    let (resolved, steps) = traced(path);
+    let _ = write_trace(out, path, &steps, &resolved);
+    resolved
+}
+
+/// Resolve `path` as [`canonicalize`](crate::canonicalize) does, keeping the steps
+/// taken whether or not it succeeds.
+fn traced(path: &Path) -> (io::Result<PathBuf>, Vec<Step>) {
+    #[cfg(target_os = "linux")]
+    {
+        let options = crate::CanonicalizeOptions::default();
+        let trace = RefCell::new(Vec::new());
+        let resolved = crate::resolve::Resolver::new(&options)
+            .with_trace(&trace)
+            .canonicalize(path);
+        (resolved, trace.into_inner())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let steps = vec![Step::CalledStdCanonicalize(path.to_path_buf())];
+        (crate::canonicalize(path), steps)
+    }
+}
+
+fn write_trace(
+    out: &mut dyn io::Write,
+    path: &Path,
+    steps: &[Step],
+    resolved: &io::Result<PathBuf>,
+) -> io::Result<()> {
+    writeln!(out, "resolving {}", path.display())?;
+    for (number, step) in steps.iter().enumerate() {
+        writeln!(out, "  {}. {step}", number + 1)?;
+    }
+    match resolved {
+        Ok(resolved) => writeln!(out, "result: {}", resolved.display())?,
+        Err(err) => writeln!(out, "error: {err}")?,
+    }
+    out.flush()
+}
+
+/// Append the step built by `step` to `trace`, if there is one.
+#[cfg(target_os = "linux")]
+pub(crate) fn record(trace: Option<&RefCell<Vec<Step>>>, step: impl FnOnce() -> Step) {
+    if let Some(trace) = trace {
+        trace.borrow_mut().push(step());
+    }
+}
@@ -0,0 +1,147 @@
+//! Canonicalize variants that also require a particular kind of final target, or
+//! resolve only the directory around it.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// `EISDIR` on Linux, macOS and the BSDs: is a directory.
+#[cfg(unix)]
+const EISDIR: i32 = 21;
+
+/// [`canonicalize`](crate::canonicalize), failing unless the result is a directory.
+///
+/// On Unix the check is folded into resolution: the path is resolved with a trailing
+/// `/`, so the kernel itself rejects a non-directory while walking it, with no separate
+/// `metadata` call afterwards. Symlinks to directories count as directories.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::canonicalize_dir;
+///
+/// assert_eq!(canonicalize_dir("/proc/self/root/etc")?, Path::new("/proc/self/root/etc"));
+/// assert!(canonicalize_dir("/proc/self/root/etc/hostname").is_err());
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Any error [`canonicalize`](crate::canonicalize) returns. A target that is not a
+/// directory fails with the OS `ENOTDIR` error on Unix
+/// ([`io::ErrorKind::NotADirectory`] on Rust 1.83 and later) and with
+/// [`io::ErrorKind::InvalidInput`] elsewhere.
+pub fn canonicalize_dir(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    #[cfg(unix)]
+    {
+        // join("") appends the separator; an empty path stays empty and fails as usual.
+        crate::canonicalize(path.as_ref().join(""))
+    }
+    #[cfg(not(unix))]
+    {
+        let canonical = crate::canonicalize(path)?;
+        if std::fs::metadata(&canonical)?.is_dir() {
+            Ok(canonical)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "not a directory",
+            ))
+        }
+    }
+}
+
+/// [`canonicalize`](crate::canonicalize), failing unless the result is a regular file.
+///
+/// Symlinks to regular files count as regular files. Unlike [`canonicalize_dir`], the
+/// kind is checked with a `metadata` call on the resolved path, so a swap between the
+/// two steps goes unnoticed.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::canonicalize_file;
+///
+/// let dir = tempfile::tempdir()?;
+/// std::fs::write(dir.path().join("app.conf"), "")?;
+/// let through_root = format!("/proc/self/root{}/app.conf", dir.path().display());
+///
+/// assert_eq!(canonicalize_file(&through_root)?, Path::new(&through_root));
+/// assert!(canonicalize_file("/proc/self/root/etc").is_err());
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Any error [`canonicalize`](crate::canonicalize) returns. A directory fails with the
+/// OS `EISDIR` error on Unix ([`io::ErrorKind::IsADirectory`] on Rust 1.83 and later);
+/// any other non-regular target, and a directory elsewhere, fails with
+/// [`io::ErrorKind::InvalidInput`].
+pub fn canonicalize_file(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let canonical = crate::canonicalize(path)?;
+    let file_type = std::fs::metadata(&canonical)?.file_type();
+    if file_type.is_file() {
+        return Ok(canonical);
+    }
+    #[cfg(unix)]
+    if file_type.is_dir() {
+        return Err(io::Error::from_raw_os_error(EISDIR));
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "not a regular file",
+    ))
+}
+
+/// Canonicalize the directory containing `path`, which itself need not exist.
+///
+/// The final component is dropped *before* resolution and the parent is resolved with
+/// [`canonicalize_dir`], so a file that is about to be created has a canonical home.
+/// Boundaries in the parent are preserved: the parent of `/proc/1234/root/etc/new.conf`
+/// is `/proc/1234/root/etc`. A bare file name has the current directory as its parent.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # fn main() -> std::io::Result<()> {
+/// use std::path::Path;
+/// use proc_canonicalize::canonicalize_parent;
+///
+/// let parent = canonicalize_parent("/proc/self/root/etc/./not-created-yet.conf")?;
+/// assert_eq!(parent, Path::new("/proc/self/root/etc"));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(target_os = "linux"))]
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// Fails with [`io::ErrorKind::InvalidInput`] if `path` does not end in a file name:
+/// a root, or a path ending in `..`, whose "parent" would be ambiguous. Otherwise any
+/// error [`canonicalize_dir`] returns for the parent.
+pub fn canonicalize_parent(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    if path.file_name().is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "path has no final component to take the parent of",
+        ));
+    }
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => canonicalize_dir(parent),
+        _ => canonicalize_dir("."),
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "fs")]
+
 //! Additional security tests for edge cases and potential vulnerabilities
 
 #[cfg(target_os = "linux")]
@@ -0,0 +1,53 @@
+//! A child process to resolve `/proc/PID/cwd` through, shared by integration tests.
+
+use std::path::{Path, PathBuf};
+
+/// A `sleep` child whose cwd is a known directory, so tests can resolve through
+/// `/proc/PID/cwd` without changing this process's cwd. Killed on drop.
+pub struct CwdProcess {
+    /// The directory, when this owns it.
+    _temp: Option<tempfile::TempDir>,
+    dir: PathBuf,
+    child: std::process::Child,
+}
+
+impl CwdProcess {
+    /// A child in a fresh temporary directory, removed on drop.
+    pub fn spawn() -> Self {
+        let temp = tempfile::tempdir().unwrap();
+        let mut process = Self::spawn_in(temp.path());
+        process._temp = Some(temp);
+        process
+    }
+
+    /// A child in `dir`, an existing directory the caller keeps.
+    pub fn spawn_in(dir: &Path) -> Self {
+        let child = std::process::Command::new("sleep")
+            .arg("30")
+            .current_dir(dir)
+            .spawn()
+            .unwrap();
+        CwdProcess {
+            _temp: None,
+            dir: dir.to_path_buf(),
+            child,
+        }
+    }
+
+    /// The child's cwd, as a host path.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// `/proc/PID/cwd` for the child.
+    pub fn cwd_boundary(&self) -> PathBuf {
+        PathBuf::from(format!("/proc/{}/cwd", self.child.id()))
+    }
+}
+
+impl Drop for CwdProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,8 @@
+//! This process's own `/proc/PID` directory, shared by integration tests.
+
+use std::path::{Path, PathBuf};
+
+/// `/proc/PID` for this process.
+pub fn this_process() -> PathBuf {
+    Path::new("/proc").join(std::process::id().to_string())
+}
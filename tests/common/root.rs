@@ -0,0 +1,8 @@
+//! Host paths spelled through `/proc/self/root`, shared by integration tests.
+
+use std::path::{Path, PathBuf};
+
+/// `host`, an absolute host path, as seen through `/proc/self/root`.
+pub fn through_root(host: &Path) -> PathBuf {
+    Path::new("/proc/self/root").join(host.strip_prefix("/").unwrap())
+}
@@ -0,0 +1,11 @@
+//! Temporary directories named by their canonical host path, shared by integration tests.
+
+use std::path::PathBuf;
+
+/// A temporary directory and its canonical host path, the form symlink targets and
+/// expected results are spelled in.
+pub fn canonical_tempdir() -> (tempfile::TempDir, PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let host = std::fs::canonicalize(dir.path()).unwrap();
+    (dir, host)
+}
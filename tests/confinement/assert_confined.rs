@@ -0,0 +1,112 @@
+#![cfg(target_os = "linux")]
+
+//! `assert_confined`: canonicalize, then enforce an allow-list of boundaries.
+
+use crate::common::cwd::CwdProcess;
+use proc_canonicalize::{assert_confined, BoundaryEscaped};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+// ==========================================================================
+// ALLOWED
+// ==========================================================================
+
+#[test]
+fn path_inside_an_allowed_boundary_is_returned_resolved() {
+    let process = CwdProcess::spawn();
+    std::fs::create_dir(process.dir().join("data")).unwrap();
+    let boundary = process.cwd_boundary();
+
+    let resolved = assert_confined(boundary.join("data/./"), &[&boundary]).unwrap();
+
+    assert_eq!(resolved, boundary.join("data"));
+}
+
+#[test]
+fn any_of_several_boundaries_may_match() {
+    let process = CwdProcess::spawn();
+    let boundary = process.cwd_boundary();
+    let allowed = [Path::new("/proc/1/root"), boundary.as_path()];
+
+    assert_eq!(assert_confined(&boundary, &allowed).unwrap(), boundary);
+}
+
+#[test]
+fn symlink_into_an_allowed_boundary_is_followed() {
+    let links = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root/etc", links.path().join("etc")).unwrap();
+
+    let resolved =
+        assert_confined(links.path().join("etc"), &[Path::new("/proc/self/root")]).unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+}
+
+// ==========================================================================
+// DENIED
+// ==========================================================================
+
+#[test]
+fn escape_from_allowed_boundary_is_permission_denied() {
+    let process = CwdProcess::spawn();
+    let boundary = process.cwd_boundary();
+
+    let err = assert_confined(boundary.join(".."), &[&boundary]).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    let escaped = err
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<BoundaryEscaped>())
+        .unwrap();
+    assert_eq!(escaped.boundary(), boundary);
+}
+
+#[test]
+fn boundary_outside_the_set_is_permission_denied() {
+    let process = CwdProcess::spawn();
+
+    let err = assert_confined("/proc/self/root/etc", &[&process.cwd_boundary()]).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn pid_sharing_a_numeric_prefix_is_not_allowed() {
+    let pid = std::process::id();
+    let path = format!("/proc/{pid}/root/etc");
+    let shorter = format!("/proc/{}/root", pid / 10);
+
+    let err = assert_confined(path, &[Path::new(&shorter)]).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn plain_host_path_is_denied_by_boundary_set() {
+    let err = assert_confined("/etc", &[Path::new("/proc/self/root")]).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn empty_allow_list_denies_everything() {
+    let err = assert_confined("/proc/self/root", &[]).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+// ==========================================================================
+// OTHER ERRORS PASS THROUGH
+// ==========================================================================
+
+#[test]
+fn missing_path_is_not_found() {
+    let err = assert_confined(
+        "/proc/self/root/no/such/path",
+        &[Path::new("/proc/self/root")],
+    )
+    .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
@@ -0,0 +1,99 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_in_container` with a caller-supplied `ContainerResolver`. This
+//! process stands in for the container: its root is the host's.
+
+use proc_canonicalize::{canonicalize_in_container, ContainerResolver, ProcessGone};
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+struct Table(HashMap<&'static str, u32>);
+
+impl ContainerResolver for Table {
+    fn pid_for(&self, container_id: &str) -> io::Result<u32> {
+        self.0
+            .get(container_id)
+            .copied()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such container"))
+    }
+}
+
+fn table() -> Table {
+    Table(HashMap::from([
+        ("web", std::process::id()),
+        ("gone", 999_999_999),
+    ]))
+}
+
+fn root_of_self(rest: &str) -> String {
+    format!("/proc/{}/root{rest}", std::process::id())
+}
+
+// ==========================================================================
+// RESOLUTION
+// ==========================================================================
+
+#[test]
+fn subpath_resolves_under_the_container_root() {
+    assert_eq!(
+        canonicalize_in_container(&table(), "web", "etc").unwrap(),
+        Path::new(&root_of_self("/etc"))
+    );
+}
+
+#[test]
+fn absolute_subpath_is_inside_the_container() {
+    assert_eq!(
+        canonicalize_in_container(&table(), "web", "/etc/./").unwrap(),
+        Path::new(&root_of_self("/etc"))
+    );
+}
+
+#[test]
+fn empty_subpath_is_the_root() {
+    for subpath in ["", "/"] {
+        assert_eq!(
+            canonicalize_in_container(&table(), "web", subpath).unwrap(),
+            Path::new(&root_of_self("")),
+            "{subpath:?}"
+        );
+    }
+}
+
+#[test]
+fn closures_are_resolvers() {
+    let lookup = |id: &str| -> io::Result<u32> {
+        assert_eq!(id, "abc123");
+        Ok(std::process::id())
+    };
+
+    assert_eq!(
+        canonicalize_in_container(&lookup, "abc123", "etc").unwrap(),
+        Path::new(&root_of_self("/etc"))
+    );
+}
+
+// ==========================================================================
+// ERRORS
+// ==========================================================================
+
+#[test]
+fn lookup_error_is_returned_unchanged() {
+    let err = canonicalize_in_container(&table(), "db", "etc").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(err.to_string(), "no such container");
+}
+
+#[test]
+fn pid_without_a_process_is_process_gone() {
+    let err = canonicalize_in_container(&table(), "gone", "etc").unwrap_err();
+
+    let gone = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<ProcessGone>()
+        .unwrap();
+    assert_eq!(gone.pid(), 999_999_999);
+}
@@ -0,0 +1,118 @@
+#![cfg(target_os = "linux")]
+
+//! `host_to_namespace`: attributing a host path to a process's root or cwd.
+
+use crate::common::cwd::CwdProcess;
+use proc_canonicalize::{canonicalize, host_to_namespace, NamespaceKind, ProcessGone};
+use std::io;
+use std::path::Path;
+
+/// The child's PID, from its `/proc/PID/cwd`.
+fn pid_of(process: &CwdProcess) -> u32 {
+    process
+        .cwd_boundary()
+        .parent()
+        .and_then(|dir| dir.file_name()?.to_str()?.parse().ok())
+        .unwrap()
+}
+
+// ==========================================================================
+// INSIDE THE BOUNDARY
+// ==========================================================================
+
+#[test]
+fn host_path_under_host_root_is_named_through_root() {
+    let pid = std::process::id();
+
+    let named = host_to_namespace(Path::new("/etc"), pid, NamespaceKind::Root).unwrap();
+
+    assert_eq!(named.unwrap(), Path::new(&format!("/proc/{pid}/root/etc")));
+}
+
+#[test]
+fn host_path_under_cwd_is_named_through_cwd() {
+    let process = CwdProcess::spawn();
+    std::fs::create_dir(process.dir().join("logs")).unwrap();
+    let pid = pid_of(&process);
+
+    let named = host_to_namespace(&process.dir().join("logs/."), pid, NamespaceKind::Cwd)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(named, process.cwd_boundary().join("logs"));
+    assert_eq!(canonicalize(&named).unwrap(), named);
+}
+
+#[test]
+fn boundary_itself_is_named_with_empty_remainder() {
+    let process = CwdProcess::spawn();
+    let pid = pid_of(&process);
+
+    let named = host_to_namespace(process.dir(), pid, NamespaceKind::Cwd)
+        .unwrap()
+        .unwrap();
+
+    // Compared as strings: Path equality would accept a trailing separator.
+    assert_eq!(named.as_os_str(), process.cwd_boundary().as_os_str());
+}
+
+// ==========================================================================
+// OUTSIDE THE BOUNDARY
+// ==========================================================================
+
+#[test]
+fn host_path_outside_cwd_is_none() {
+    let process = CwdProcess::spawn();
+    let pid = pid_of(&process);
+
+    assert_eq!(
+        host_to_namespace(Path::new("/etc"), pid, NamespaceKind::Cwd).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn sibling_with_shared_name_prefix_is_none() {
+    let process = CwdProcess::spawn();
+    let pid = pid_of(&process);
+    let mut sibling = process.dir().as_os_str().to_owned();
+    sibling.push("2");
+    std::fs::create_dir(&sibling).unwrap();
+
+    let named = host_to_namespace(Path::new(&sibling), pid, NamespaceKind::Cwd);
+    std::fs::remove_dir(&sibling).unwrap();
+
+    assert_eq!(named.unwrap(), None);
+}
+
+// ==========================================================================
+// ERRORS
+// ==========================================================================
+
+#[test]
+fn namespace_kind_is_invalid_input() {
+    let err =
+        host_to_namespace(Path::new("/etc"), std::process::id(), NamespaceKind::Ns).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn missing_process_is_process_gone() {
+    let err = host_to_namespace(Path::new("/etc"), 999_999_999, NamespaceKind::Root).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.get_ref().is_some_and(|inner| inner.is::<ProcessGone>()));
+}
+
+#[test]
+fn missing_host_path_is_not_found() {
+    let err = host_to_namespace(
+        Path::new("/no/such/host/path"),
+        std::process::id(),
+        NamespaceKind::Root,
+    )
+    .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
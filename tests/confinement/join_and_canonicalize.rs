@@ -0,0 +1,103 @@
+#![cfg(target_os = "linux")]
+
+//! `join_and_canonicalize`: a trusted base, an untrusted child, a typed outcome.
+
+use proc_canonicalize::{canonicalize, join_and_canonicalize, Containment};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::PathBuf;
+
+/// A base directory under `/proc/self/root`, as `canonicalize` would return it.
+fn base_through_root(dir: &tempfile::TempDir) -> PathBuf {
+    canonicalize(format!("/proc/self/root{}", dir.path().display())).unwrap()
+}
+
+// ==========================================================================
+// INSIDE
+// ==========================================================================
+
+#[test]
+fn child_inside_base_keeps_boundary_prefix() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("data/logs")).unwrap();
+    let base = base_through_root(&dir);
+
+    let outcome = join_and_canonicalize(&base, "data/./logs/../logs").unwrap();
+
+    assert_eq!(outcome, Containment::Inside(base.join("data/logs")));
+    assert!(outcome.path().starts_with("/proc/self/root"));
+}
+
+#[test]
+fn empty_child_is_the_base_itself() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = base_through_root(&dir);
+
+    let outcome = join_and_canonicalize(&base, "").unwrap();
+
+    assert_eq!(outcome.inside(), Some(base));
+}
+
+#[test]
+fn relative_symlink_staying_inside_is_inside() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("real")).unwrap();
+    symlink("real", dir.path().join("alias")).unwrap();
+    let base = base_through_root(&dir);
+
+    let outcome = join_and_canonicalize(&base, "alias").unwrap();
+
+    assert_eq!(outcome, Containment::Inside(base.join("real")));
+}
+
+// ==========================================================================
+// ESCAPED
+// ==========================================================================
+
+#[test]
+fn lexical_dotdot_escape_is_reported_without_resolution() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = base_through_root(&dir);
+
+    let outcome = join_and_canonicalize(&base, "../does-not-exist").unwrap();
+
+    assert_eq!(
+        outcome,
+        Containment::Escaped(base.join("../does-not-exist"))
+    );
+}
+
+#[test]
+fn absolute_child_is_escaped() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = base_through_root(&dir);
+
+    let outcome = join_and_canonicalize(base, "/etc/passwd").unwrap();
+
+    assert!(!outcome.is_inside());
+    assert_eq!(outcome.inside(), None);
+}
+
+#[test]
+fn symlink_out_of_base_is_escaped_but_keeps_boundary() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("/etc", dir.path().join("sneaky")).unwrap();
+    let base = base_through_root(&dir);
+
+    let outcome = join_and_canonicalize(base, "sneaky").unwrap();
+
+    assert_eq!(
+        outcome,
+        Containment::Escaped(PathBuf::from("/proc/self/root/etc"))
+    );
+}
+
+#[test]
+fn missing_child_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = base_through_root(&dir);
+
+    let err = join_and_canonicalize(base, "missing").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
@@ -0,0 +1,18 @@
+#![cfg(feature = "fs")]
+
+//! Keeping results inside a boundary and mapping between host and namespace paths.
+
+#[cfg(target_os = "linux")]
+#[path = "../common"]
+mod common {
+    pub mod cwd;
+}
+
+mod assert_confined;
+mod container;
+mod host_to_namespace;
+mod join_and_canonicalize;
+mod resolve_subpaths_under;
+mod resolve_under;
+mod same_file;
+mod to_host;
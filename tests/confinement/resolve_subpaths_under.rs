@@ -0,0 +1,116 @@
+#![cfg(target_os = "linux")]
+
+//! `resolve_subpaths_under`: many paths under one boundary, resolved once.
+
+use crate::common::cwd::CwdProcess;
+use proc_canonicalize::{canonicalize, resolve_subpaths_under, ProcessGone};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+// ==========================================================================
+// RESULTS
+// ==========================================================================
+
+#[test]
+fn each_result_matches_canonicalize() {
+    let inputs = [
+        "/proc/self/root/etc",
+        "/proc/self/root/usr/./lib/..",
+        "/proc/self/root/etc/",
+        "/proc/self/root",
+        "/proc/self/root/no/such/entry",
+    ];
+
+    let results = resolve_subpaths_under("/proc/self/root", inputs).unwrap();
+
+    assert_eq!(results.len(), inputs.len());
+    for (input, result) in inputs.iter().zip(results) {
+        match (result, canonicalize(input)) {
+            (Ok(got), Ok(expected)) => assert_eq!(got, expected, "{input}"),
+            (Err(got), Err(expected)) => assert_eq!(got.kind(), expected.kind(), "{input}"),
+            (got, expected) => panic!("{input}: {got:?} vs {expected:?}"),
+        }
+    }
+}
+
+#[test]
+fn relative_subpaths_are_taken_from_the_boundary() {
+    let results = resolve_subpaths_under("/proc/self/root", ["etc", "usr/../etc"]).unwrap();
+
+    for result in results {
+        assert_eq!(result.unwrap(), Path::new("/proc/self/root/etc"));
+    }
+}
+
+#[test]
+fn one_failure_does_not_stop_the_rest() {
+    let results =
+        resolve_subpaths_under("/proc/self/root", ["no/such/entry", "etc", "/etc"]).unwrap();
+
+    assert_eq!(
+        results[0].as_ref().unwrap_err().kind(),
+        io::ErrorKind::NotFound
+    );
+    assert_eq!(
+        results[1].as_ref().unwrap(),
+        Path::new("/proc/self/root/etc")
+    );
+    assert_eq!(
+        results[2].as_ref().unwrap_err().kind(),
+        io::ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn symlinks_inside_are_followed_within_the_boundary() {
+    let process = CwdProcess::spawn();
+    std::fs::create_dir(process.dir().join("real")).unwrap();
+    symlink("real", process.dir().join("alias")).unwrap();
+    let boundary = process.cwd_boundary();
+
+    let results =
+        resolve_subpaths_under(&boundary, [boundary.join("alias"), "real/".into()]).unwrap();
+
+    for result in results {
+        assert_eq!(result.unwrap(), boundary.join("real"));
+    }
+}
+
+#[test]
+fn escapes_come_back_as_host_paths() {
+    let process = CwdProcess::spawn();
+    let boundary = process.cwd_boundary();
+
+    let results = resolve_subpaths_under(&boundary, [".."]).unwrap();
+
+    assert_eq!(
+        results[0].as_ref().unwrap(),
+        &canonicalize(boundary.join("..")).unwrap()
+    );
+}
+
+// ==========================================================================
+// BOUNDARY ERRORS
+// ==========================================================================
+
+#[test]
+fn boundary_must_be_bare() {
+    for boundary in ["/proc/self/root/etc", "/etc", "/proc/self/ns/net", "root"] {
+        let err = resolve_subpaths_under(boundary, ["etc"]).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{boundary}");
+    }
+}
+
+#[test]
+fn dead_process_fails_the_whole_call() {
+    let mut child = std::process::Command::new("true").spawn().unwrap();
+    let pid = child.id();
+    child.wait().unwrap();
+
+    let err = resolve_subpaths_under(format!("/proc/{pid}/root"), ["etc"]).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.get_ref().is_some_and(|inner| inner.is::<ProcessGone>()));
+}
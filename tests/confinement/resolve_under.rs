@@ -0,0 +1,146 @@
+#![cfg(target_os = "linux")]
+
+//! `resolve_under`: canonicalize a path and require it to be within one root.
+
+use crate::common::cwd::CwdProcess;
+use proc_canonicalize::{resolve_under, BoundaryEscaped};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+// ==========================================================================
+// INSIDE THE ROOT
+// ==========================================================================
+
+#[test]
+fn path_below_a_boundary_root_is_returned_resolved() {
+    let process = CwdProcess::spawn();
+    std::fs::create_dir(process.dir().join("data")).unwrap();
+    let boundary = process.cwd_boundary();
+
+    let resolved = resolve_under(boundary.join("data/./"), &boundary).unwrap();
+
+    assert_eq!(resolved, boundary.join("data"));
+}
+
+#[test]
+fn root_itself_is_within_the_root() {
+    assert_eq!(
+        resolve_under("/proc/self/root", "/proc/self/root").unwrap(),
+        Path::new("/proc/self/root")
+    );
+}
+
+#[test]
+fn root_is_canonicalized_before_comparing() {
+    let resolved = resolve_under("/proc/self/root/etc", "/proc/self/root/usr/../etc/").unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+}
+
+#[test]
+fn symlink_into_the_root_is_followed() {
+    let links = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root/etc", links.path().join("etc")).unwrap();
+
+    let resolved = resolve_under(links.path().join("etc"), "/proc/self/root").unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+}
+
+#[test]
+fn host_root_holds_host_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("data")).unwrap();
+
+    let resolved = resolve_under(dir.path().join("data/."), dir.path()).unwrap();
+
+    assert_eq!(
+        resolved,
+        std::fs::canonicalize(dir.path().join("data")).unwrap()
+    );
+}
+
+// ==========================================================================
+// OUTSIDE THE ROOT
+// ==========================================================================
+
+#[test]
+fn sibling_directory_is_permission_denied() {
+    let err = resolve_under("/proc/self/root/usr", "/proc/self/root/etc").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    assert!(
+        err.to_string().contains("outside /proc/self/root/etc"),
+        "{err}"
+    );
+}
+
+#[test]
+fn escape_from_the_boundary_is_permission_denied() {
+    let process = CwdProcess::spawn();
+    let boundary = process.cwd_boundary();
+
+    let err = resolve_under(boundary.join(".."), &boundary).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    let escaped = err
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<BoundaryEscaped>())
+        .unwrap();
+    assert_eq!(escaped.boundary(), boundary);
+}
+
+#[test]
+fn pid_sharing_a_numeric_prefix_is_outside() {
+    let pid = std::process::id();
+    let path = format!("/proc/{pid}/root/etc");
+
+    let err = resolve_under(path, format!("/proc/{}/root", pid / 10)).unwrap_err();
+
+    assert!(
+        matches!(
+            err.kind(),
+            io::ErrorKind::PermissionDenied | io::ErrorKind::NotFound
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn name_sharing_a_prefix_is_outside() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("data")).unwrap();
+    std::fs::create_dir(dir.path().join("database")).unwrap();
+
+    let err = resolve_under(dir.path().join("database"), dir.path().join("data")).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn host_path_is_outside_a_boundary_root() {
+    let process = CwdProcess::spawn();
+
+    let err = resolve_under(process.dir(), process.cwd_boundary()).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+// ==========================================================================
+// OTHER ERRORS PASS THROUGH
+// ==========================================================================
+
+#[test]
+fn missing_path_is_not_found() {
+    let err = resolve_under("/proc/self/root/no/such/path", "/proc/self/root").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn missing_root_is_not_found() {
+    let err = resolve_under("/proc/self/root/etc", "/proc/self/root/no/such/root").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
@@ -0,0 +1,77 @@
+//! `same_file`: identity comparison across namespace and host spellings.
+
+use proc_canonicalize::same_file;
+use std::io;
+
+#[test]
+fn path_equals_itself() {
+    let dir = tempfile::tempdir().unwrap();
+
+    assert!(same_file(dir.path(), dir.path()).unwrap());
+}
+
+#[test]
+fn distinct_files_differ() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a"), "").unwrap();
+    std::fs::write(dir.path().join("b"), "").unwrap();
+
+    assert!(!same_file(dir.path().join("a"), dir.path().join("b")).unwrap());
+}
+
+#[test]
+fn missing_path_reports_not_found() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = same_file(dir.path(), dir.path().join("missing")).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn namespace_spelling_matches_host_spelling() {
+        let dir = tempfile::tempdir().unwrap();
+        let host_path = dir.path().join("hostname");
+        std::fs::write(&host_path, "box").unwrap();
+
+        let namespace_path = format!("/proc/self/root{}", host_path.display());
+
+        assert!(same_file(namespace_path, &host_path).unwrap());
+    }
+
+    #[test]
+    fn cwd_boundary_matches_host_cwd() {
+        let cwd = std::env::current_dir().unwrap();
+
+        assert!(same_file("/proc/self/cwd", cwd).unwrap());
+    }
+
+    #[test]
+    fn symlink_matches_its_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target");
+        std::fs::write(&target, "").unwrap();
+        let link = dir.path().join("link");
+        symlink("/proc/self/root", &link).unwrap();
+
+        let through_link = link.join(target.strip_prefix("/").unwrap());
+
+        assert!(same_file(through_link, &target).unwrap());
+    }
+
+    #[test]
+    fn hard_links_are_the_same_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("original");
+        std::fs::write(&original, "").unwrap();
+        let hard_link = dir.path().join("hard");
+        std::fs::hard_link(&original, &hard_link).unwrap();
+
+        assert!(same_file(&original, &hard_link).unwrap());
+    }
+}
@@ -0,0 +1,55 @@
+#![cfg(target_os = "linux")]
+
+//! `to_host`: the plain host path another process in the same mount namespace can use.
+
+use crate::common::cwd::CwdProcess;
+use proc_canonicalize::{canonicalize, to_host};
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+#[test]
+fn root_boundary_becomes_host_root() {
+    assert_eq!(to_host("/proc/self/root").unwrap(), Path::new("/"));
+    assert_eq!(
+        to_host("/proc/self/root/etc").unwrap(),
+        std::fs::canonicalize("/etc").unwrap()
+    );
+}
+
+#[test]
+fn cwd_boundary_becomes_host_directory() {
+    let process = CwdProcess::spawn();
+    std::fs::create_dir(process.dir().join("data")).unwrap();
+
+    let namespaced = canonicalize(process.cwd_boundary().join("data")).unwrap();
+    let host = to_host(namespaced).unwrap();
+
+    assert_eq!(
+        host,
+        std::fs::canonicalize(process.dir().join("data")).unwrap()
+    );
+    assert!(!host.starts_with("/proc"));
+}
+
+#[test]
+fn nested_boundaries_are_all_replaced() {
+    let cwd = std::fs::canonicalize(std::env::current_dir().unwrap()).unwrap();
+
+    assert_eq!(to_host("/proc/self/root/proc/self/cwd").unwrap(), cwd);
+}
+
+#[test]
+fn indirect_link_into_proc_becomes_host_path() {
+    let temp = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", temp.path().join("container")).unwrap();
+
+    assert_eq!(
+        to_host(temp.path().join("container/etc")).unwrap(),
+        std::fs::canonicalize("/etc").unwrap()
+    );
+}
+
+#[test]
+fn namespace_link_has_no_host_path() {
+    assert!(to_host("/proc/self/ns/net").is_err());
+}
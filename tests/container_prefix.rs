@@ -0,0 +1,306 @@
+#![cfg(all(target_os = "linux", feature = "fs", feature = "env-config"))]
+
+//! Escape detection when the boundary does *not* resolve to `/`.
+//!
+//! On a host, `/proc/PID/root` is `/`, every absolute path lies under it, and the
+//! escape branch is unreachable. Here `PROC_CANONICALIZE_PROC_ROOT` points at a fake
+//! procfs whose `4242/root` is an ordinary symlink to a container directory. To a
+//! caller outside the container that is exactly what the magic link does: `..` and
+//! absolute symlinks are resolved against the caller's root, so they can leave the
+//! container.
+//!
+//! Symlinks that lead into the fake procfs are followed to the same boundaries, and
+//! `4343/root` reaches the same container directory through a chain of host symlinks.
+//!
+//! The variable is read once per process, so every test shares one fake procfs.
+
+use proc_canonicalize::{
+    canonicalize, canonicalize_both, BoundaryEscaped, CanonicalizeOptions, EscapeBehavior,
+};
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+struct FakeProc {
+    /// `.../proc/4242/root`, the boundary under test.
+    boundary: PathBuf,
+    /// `.../proc/self/root`, the same boundary through the fake `self` link.
+    self_boundary: PathBuf,
+    /// `.../proc/4343/root`, which reaches the rootfs through `.../current` and
+    /// `.../containers/c1/rootfs-link`.
+    chained: PathBuf,
+    /// What the boundary resolves to: `.../containers/c1/rootfs`.
+    rootfs: PathBuf,
+    /// A sibling whose name extends the rootfs's: `.../containers/c1/rootfs2`.
+    sibling: PathBuf,
+}
+
+fn fake_proc() -> &'static FakeProc {
+    static FAKE: OnceLock<FakeProc> = OnceLock::new();
+    FAKE.get_or_init(|| {
+        // Kept for the life of the test process; the variable cannot be changed later.
+        let base = std::fs::canonicalize(tempfile::tempdir().unwrap().keep()).unwrap();
+        let rootfs = base.join("containers/c1/rootfs");
+        let sibling = base.join("containers/c1/rootfs2");
+        std::fs::create_dir_all(rootfs.join("etc/app")).unwrap();
+        std::fs::create_dir_all(rootfs.join("usr/lib")).unwrap();
+        std::fs::create_dir_all(&sibling).unwrap();
+        symlink("/usr/lib", rootfs.join("lib")).unwrap();
+        symlink("../../rootfs2", rootfs.join("etc/sideways")).unwrap();
+        symlink(&sibling, rootfs.join("abs-sibling")).unwrap();
+
+        let proc_dir = base.join("proc");
+        std::fs::create_dir_all(proc_dir.join("4242")).unwrap();
+        symlink(&rootfs, proc_dir.join("4242/root")).unwrap();
+        symlink("4242", proc_dir.join("self")).unwrap();
+        std::fs::create_dir_all(proc_dir.join("4343")).unwrap();
+        symlink(base.join("current"), proc_dir.join("4343/root")).unwrap();
+        symlink("containers/c1/rootfs-link", base.join("current")).unwrap();
+        symlink("rootfs", base.join("containers/c1/rootfs-link")).unwrap();
+        std::env::set_var("PROC_CANONICALIZE_PROC_ROOT", &proc_dir);
+
+        FakeProc {
+            boundary: proc_dir.join("4242/root"),
+            self_boundary: proc_dir.join("self/root"),
+            chained: proc_dir.join("4343/root"),
+            rootfs,
+            sibling,
+        }
+    })
+}
+
+// ==========================================================================
+// INSIDE THE CONTAINER: RE-BASED ONTO THE PREFIX
+// ==========================================================================
+
+#[test]
+fn subpath_is_rebased_onto_boundary() {
+    let fake = fake_proc();
+
+    let resolved = canonicalize(fake.boundary.join("etc/./app")).unwrap();
+
+    assert_eq!(resolved, fake.boundary.join("etc/app"));
+}
+
+#[test]
+fn dotdot_that_stays_inside_is_rebased() {
+    let fake = fake_proc();
+
+    let resolved = canonicalize(fake.boundary.join("etc/app/../../usr")).unwrap();
+
+    assert_eq!(resolved, fake.boundary.join("usr"));
+}
+
+// ==========================================================================
+// ESCAPES: THE HOST PATH BY DEFAULT
+// ==========================================================================
+
+#[test]
+fn dotdot_past_container_root_escapes_to_host() {
+    let fake = fake_proc();
+
+    let resolved = canonicalize(fake.boundary.join("..")).unwrap();
+
+    assert_eq!(resolved, fake.rootfs.parent().unwrap());
+}
+
+#[test]
+fn absolute_symlink_resolves_against_host_root() {
+    let fake = fake_proc();
+
+    let resolved = canonicalize(fake.boundary.join("lib")).unwrap();
+
+    assert_eq!(resolved, std::fs::canonicalize("/usr/lib").unwrap());
+}
+
+#[test]
+fn sibling_with_shared_name_prefix_is_an_escape() {
+    // "rootfs2" starts with the characters of "rootfs" but is not inside it.
+    let fake = fake_proc();
+
+    assert_eq!(
+        canonicalize(fake.boundary.join("etc/sideways")).unwrap(),
+        fake.sibling
+    );
+    assert_eq!(
+        canonicalize(fake.boundary.join("abs-sibling")).unwrap(),
+        fake.sibling
+    );
+}
+
+// ==========================================================================
+// ESCAPES: OTHER BEHAVIORS
+// ==========================================================================
+
+#[test]
+fn escape_is_reported_with_error_behavior() {
+    let fake = fake_proc();
+    let strict = CanonicalizeOptions::new().escape_behavior(EscapeBehavior::Error);
+
+    let err = strict
+        .canonicalize(fake.boundary.join("etc/sideways"))
+        .unwrap_err();
+
+    let escaped = err
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<BoundaryEscaped>())
+        .unwrap();
+    assert_eq!(escaped.boundary(), fake.boundary);
+    assert_eq!(escaped.host_path(), fake.sibling);
+}
+
+#[test]
+fn absolute_symlink_is_clamped_into_container() {
+    let fake = fake_proc();
+    let clamped = CanonicalizeOptions::new().escape_behavior(EscapeBehavior::ClampToRoot);
+
+    assert_eq!(
+        clamped.canonicalize(fake.boundary.join("lib")).unwrap(),
+        fake.boundary.join("usr/lib")
+    );
+    assert_eq!(
+        clamped.canonicalize(fake.boundary.join("..")).unwrap(),
+        fake.boundary
+    );
+}
+
+#[test]
+fn prefix_is_never_kept_for_a_path_outside_rootfs() {
+    let fake = fake_proc();
+
+    for remainder in ["..", "lib", "etc/sideways", "abs-sibling"] {
+        let resolved = canonicalize(fake.boundary.join(remainder)).unwrap();
+        assert!(!resolved.starts_with(&fake.boundary), "{remainder}");
+        assert!(!resolved.starts_with(&fake.rootfs), "{remainder}");
+        assert!(!resolved.starts_with(Path::new("/proc")), "{remainder}");
+    }
+}
+
+// ==========================================================================
+// A ROOT REACHED THROUGH A CHAIN OF HOST SYMLINKS
+// The boundary stands for the directory at the end of the chain, not for any
+// link along the way.
+// ==========================================================================
+
+#[test]
+fn chained_boundary_resolves_to_the_final_directory() {
+    let fake = fake_proc();
+
+    let both = canonicalize_both(&fake.chained).unwrap();
+
+    assert_eq!(both.namespace(), fake.chained);
+    assert_eq!(both.host(), fake.rootfs);
+}
+
+#[test]
+fn subpath_of_chained_boundary_is_rebased() {
+    let fake = fake_proc();
+
+    let both = canonicalize_both(fake.chained.join("etc/./app")).unwrap();
+
+    assert_eq!(both.namespace(), fake.chained.join("etc/app"));
+    assert_eq!(both.host(), fake.rootfs.join("etc/app"));
+}
+
+#[test]
+fn dotdot_past_chained_boundary_escapes_from_the_final_directory() {
+    // Not to `.../`, where the first link of the chain lives.
+    let fake = fake_proc();
+
+    let resolved = canonicalize(fake.chained.join("..")).unwrap();
+
+    assert_eq!(resolved, fake.rootfs.parent().unwrap());
+}
+
+#[test]
+fn escape_from_chained_boundary_is_reported() {
+    let fake = fake_proc();
+    let strict = CanonicalizeOptions::new().escape_behavior(EscapeBehavior::Error);
+
+    assert_eq!(
+        canonicalize(fake.chained.join("etc/sideways")).unwrap(),
+        fake.sibling
+    );
+    let err = strict
+        .canonicalize(fake.chained.join("etc/sideways"))
+        .unwrap_err();
+    let escaped = err
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<BoundaryEscaped>())
+        .unwrap();
+    assert_eq!(escaped.boundary(), fake.chained);
+    assert_eq!(escaped.host_path(), fake.sibling);
+}
+
+#[test]
+fn absolute_symlink_is_clamped_into_chained_boundary() {
+    let fake = fake_proc();
+    let clamped = CanonicalizeOptions::new().escape_behavior(EscapeBehavior::ClampToRoot);
+
+    assert_eq!(
+        clamped.canonicalize(fake.chained.join("lib")).unwrap(),
+        fake.chained.join("usr/lib")
+    );
+}
+
+#[test]
+fn memoized_chained_boundary_gives_the_same_results() {
+    let fake = fake_proc();
+    let memoized = CanonicalizeOptions::new().memoize_boundaries(true);
+
+    for _ in 0..2 {
+        assert_eq!(
+            memoized.canonicalize(fake.chained.join("etc/app")).unwrap(),
+            fake.chained.join("etc/app")
+        );
+        assert_eq!(
+            memoized.canonicalize(fake.chained.join("..")).unwrap(),
+            fake.rootfs.parent().unwrap()
+        );
+    }
+}
+
+// ==========================================================================
+// SYMLINKS INTO THE CONFIGURED ROOT
+// The indirect scanner recognizes boundaries under the configured root, and
+// only there, exactly as direct paths are recognized.
+// ==========================================================================
+
+#[test]
+fn symlink_to_configured_self_root_is_preserved() {
+    let fake = fake_proc();
+    let links = tempfile::tempdir().unwrap();
+    symlink(&fake.self_boundary, links.path().join("container")).unwrap();
+
+    assert_eq!(
+        canonicalize(links.path().join("container")).unwrap(),
+        fake.self_boundary
+    );
+    assert_eq!(
+        canonicalize(links.path().join("container/etc/app")).unwrap(),
+        fake.self_boundary.join("etc/app")
+    );
+}
+
+#[test]
+fn relative_symlink_into_configured_root_is_preserved() {
+    let fake = fake_proc();
+    let proc_dir = fake.boundary.parent().unwrap().parent().unwrap();
+    let links = proc_dir.parent().unwrap().join("links");
+    std::fs::create_dir_all(&links).unwrap();
+    let link = links.join("relative");
+    symlink("../proc/4242/root/etc", &link).unwrap();
+
+    assert_eq!(canonicalize(&link).unwrap(), fake.boundary.join("etc"));
+}
+
+#[test]
+fn symlink_to_default_proc_is_not_a_boundary_once_relocated() {
+    let _ = fake_proc();
+    let links = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", links.path().join("host_root")).unwrap();
+
+    let resolved = canonicalize(links.path().join("host_root/etc")).unwrap();
+
+    assert_eq!(resolved, std::fs::canonicalize("/etc").unwrap());
+}
@@ -0,0 +1,48 @@
+#![cfg(all(target_os = "linux", feature = "fs"))]
+
+//! The `CwdUnavailable` payload relative paths fail with when the current directory is
+//! gone, and `CanonicalizeOptions::base_dir` avoiding it.
+//!
+//! The test changes the process's current directory, so it runs in a binary of its
+//! own rather than beside tests that resolve relative paths.
+
+use proc_canonicalize::{
+    canonicalize, canonicalize_cwd_relative, CanonicalizeOptions, CwdUnavailable,
+};
+use std::io;
+use std::path::Path;
+
+fn is_cwd_error(err: &io::Error) -> bool {
+    err.get_ref()
+        .and_then(|inner| inner.downcast_ref::<CwdUnavailable>())
+        .is_some()
+}
+
+#[test]
+fn deleted_cwd_is_attributed_and_a_base_avoids_it() {
+    let original = std::env::current_dir().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let doomed = dir.path().join("doomed");
+    std::fs::create_dir(&doomed).unwrap();
+    std::env::set_current_dir(&doomed).unwrap();
+    std::fs::remove_dir(&doomed).unwrap();
+
+    let relative = canonicalize("etc").unwrap_err();
+    let cwd_relative = canonicalize_cwd_relative("etc").unwrap_err();
+    let absolute = canonicalize("/proc/self/root/etc");
+    let based = CanonicalizeOptions::new()
+        .base_dir("/proc/self/root")
+        .canonicalize("etc");
+    let empty = canonicalize("").unwrap_err();
+    std::env::set_current_dir(original).unwrap();
+
+    assert_eq!(relative.kind(), io::ErrorKind::NotFound);
+    assert!(is_cwd_error(&relative), "{relative:?}");
+    assert!(relative.to_string().contains("current directory"));
+    assert!(is_cwd_error(&cwd_relative), "{cwd_relative:?}");
+    assert_eq!(absolute.unwrap(), Path::new("/proc/self/root/etc"));
+    assert_eq!(based.unwrap(), Path::new("/proc/self/root/etc"));
+    // The empty path fails the same way with or without a current directory.
+    assert_eq!(empty.kind(), io::ErrorKind::NotFound);
+    assert!(!is_cwd_error(&empty), "{empty:?}");
+}
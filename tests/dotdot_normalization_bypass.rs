@@ -1,3 +1,5 @@
+#![cfg(feature = "fs")]
+
 //! Failing tests proving a namespace-boundary bypass via `..` in the prefix.
 //!
 //! `find_namespace_boundary` does lexical matching on `path.components()` but
@@ -189,4 +191,58 @@ mod dotdot_bypass {
             result
         );
     }
+
+    /// `/proc/self/task/<TID>` for the calling thread.
+    fn this_task() -> PathBuf {
+        Path::new("/proc").join(std::fs::read_link("/proc/thread-self").expect("thread-self"))
+    }
+
+    #[test]
+    fn symlink_to_task_root_subdir_then_dotdot_preserves_task_prefix() {
+        use std::os::unix::fs::symlink;
+
+        let task = this_task();
+        let temp = tempfile::tempdir().expect("tempdir");
+        let link = temp.path().join("task_etc");
+        symlink(task.join("root/etc"), &link).expect("create task_etc symlink");
+
+        let result = canonicalize(link.join("..")).expect("canonicalize should succeed");
+
+        assert_eq!(result, task.join("root"));
+    }
+
+    #[test]
+    fn symlink_to_task_root_subdir_then_dotdot_keeps_following_suffix() {
+        use std::os::unix::fs::symlink;
+
+        let task = this_task();
+        let temp = tempfile::tempdir().expect("tempdir");
+        let link = temp.path().join("task_etc");
+        symlink(task.join("root/etc"), &link).expect("create task_etc symlink");
+
+        let result = canonicalize(link.join("../usr")).expect("canonicalize should succeed");
+
+        assert_eq!(result, task.join("root/usr"));
+    }
+
+    #[test]
+    fn symlink_to_task_cwd_subdir_then_dotdot_preserves_task_prefix() {
+        use std::os::unix::fs::symlink;
+
+        let task = this_task();
+        let cwd = std::env::current_dir().expect("cwd");
+        let sub = std::fs::read_dir(cwd)
+            .expect("read cwd")
+            .filter_map(Result::ok)
+            .find(|entry| entry.file_type().map(|t| t.is_dir()).unwrap_or(false))
+            .expect("cwd has a subdirectory")
+            .file_name();
+        let temp = tempfile::tempdir().expect("tempdir");
+        let link = temp.path().join("task_cwd_sub");
+        symlink(task.join("cwd").join(sub), &link).expect("create task_cwd_sub symlink");
+
+        let result = canonicalize(link.join("..")).expect("canonicalize should succeed");
+
+        assert_eq!(result, task.join("cwd"));
+    }
 }
@@ -1,3 +1,5 @@
+#![cfg(feature = "fs")]
+
 #[cfg(target_os = "linux")]
 mod linux_edge_cases {
     use proc_canonicalize::canonicalize;
@@ -399,4 +401,107 @@ mod linux_edge_cases {
 
         assert!(result.starts_with("/proc/self/root"));
     }
+
+    // ==========================================================================
+    // UNUSUAL SYMLINK TARGETS
+    // `.`, self-references and empty targets must neither hang the scanner nor
+    // resolve somewhere std::fs::canonicalize would not.
+    // ==========================================================================
+
+    #[test]
+    fn dot_target_resolves_to_link_parent() {
+        use std::os::unix::fs::symlink;
+
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir(temp.path().join("sub")).unwrap();
+        symlink(".", temp.path().join("here")).unwrap();
+
+        let chained = temp.path().join("here/here/here/sub");
+        let resolved = canonicalize(&chained).unwrap();
+
+        assert_eq!(resolved, std::fs::canonicalize(&chained).unwrap());
+        assert_eq!(
+            canonicalize(temp.path().join("here")).unwrap(),
+            std::fs::canonicalize(temp.path()).unwrap()
+        );
+    }
+
+    #[test]
+    fn dot_target_chain_reaching_boundary_is_preserved() {
+        use std::os::unix::fs::symlink;
+
+        let temp = tempfile::tempdir().unwrap();
+        symlink(".", temp.path().join("here")).unwrap();
+        symlink("/proc/self/root", temp.path().join("container")).unwrap();
+
+        let resolved = canonicalize(temp.path().join("here/here/container/etc")).unwrap();
+
+        assert_eq!(resolved, PathBuf::from("/proc/self/root/etc"));
+    }
+
+    #[test]
+    fn self_referencing_symlink_errors_instead_of_hanging() {
+        use std::os::unix::fs::symlink;
+
+        let temp = tempfile::tempdir().unwrap();
+        let link = temp.path().join("ouroboros");
+        symlink("ouroboros", &link).unwrap();
+
+        let err = canonicalize(link.join("etc")).unwrap_err();
+
+        assert_eq!(
+            err.raw_os_error(),
+            std::fs::canonicalize(link.join("etc"))
+                .unwrap_err()
+                .raw_os_error()
+        );
+    }
+
+    #[test]
+    fn empty_target_cannot_resolve() {
+        use std::os::unix::fs::symlink;
+
+        let temp = tempfile::tempdir().unwrap();
+        let link = temp.path().join("empty");
+
+        // Linux rejects empty targets at creation; if a filesystem ever allows
+        // one, resolution through it must still fail.
+        match symlink("", &link) {
+            Err(err) => assert_eq!(err.kind(), std::io::ErrorKind::NotFound),
+            Ok(()) => {
+                let err = canonicalize(link.join("etc")).unwrap_err();
+                assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+            }
+        }
+    }
+
+    // ==========================================================================
+    // NUL BYTES
+    // Rejected up front, before the indirect scan or any filesystem access.
+    // ==========================================================================
+
+    #[test]
+    fn interior_nul_is_rejected_as_invalid_input() {
+        for input in ["/proc/self/root/et\0c", "/tmp/\0", "\0"] {
+            let err = canonicalize(input).unwrap_err();
+
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{input:?}");
+            assert_eq!(err.to_string(), "path contains a NUL byte");
+        }
+    }
+
+    #[test]
+    fn nul_behind_indirect_symlink_is_rejected_before_scanning() {
+        use std::os::unix::fs::symlink;
+
+        let temp = tempfile::tempdir().unwrap();
+        let link = temp.path().join("container");
+        symlink("/proc/self/root", &link).unwrap();
+        let input = format!("{}/etc\0/passwd", link.display());
+
+        let err = canonicalize(input).unwrap_err();
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "path contains a NUL byte");
+    }
 }
@@ -0,0 +1,57 @@
+#![cfg(all(target_os = "linux", feature = "fs", feature = "env-config"))]
+
+//! `PROC_CANONICALIZE_PROC_ROOT` relocates `/proc` under the `env-config` feature.
+//!
+//! The variable is read once per process, so everything lives in a single test.
+
+use proc_canonicalize::{
+    canonicalize, parse_boundary, CanonicalizeOptions, PidSelector, ProcessIdentity,
+};
+use std::path::Path;
+
+#[test]
+fn relocated_proc_root_replaces_default_everywhere() {
+    let mount = tempfile::tempdir().unwrap();
+    // A symlink to the real procfs stands in for a second mount point.
+    let relocated = mount.path().join("hostproc");
+    std::os::unix::fs::symlink("/proc", &relocated).unwrap();
+    std::env::set_var("PROC_CANONICALIZE_PROC_ROOT", &relocated);
+
+    // Boundaries under the relocated root are preserved...
+    let through_relocated = relocated.join("self/root/etc");
+    assert_eq!(canonicalize(&through_relocated).unwrap(), through_relocated);
+
+    let boundary = parse_boundary(relocated.join("self/task/7/cwd/src")).unwrap();
+    assert_eq!(boundary.pid(), &PidSelector::SelfProcess);
+    assert_eq!(boundary.tid().unwrap().as_str(), "7");
+    assert_eq!(boundary.remainder(), Path::new("src"));
+
+    // ...while /proc itself is now an ordinary directory.
+    assert!(parse_boundary("/proc/self/root").is_none());
+    assert_eq!(
+        canonicalize("/proc/self/root/etc").unwrap(),
+        Path::new("/etc")
+    );
+
+    // Memoized answers are keyed by the relocated spelling; the old one is no
+    // boundary, so nothing cached for it can leak into the other.
+    let memoized = CanonicalizeOptions::new()
+        .resolve_outer_to_host(true)
+        .memoize_boundaries(true);
+    for _ in 0..2 {
+        assert_eq!(
+            memoized
+                .canonicalize(relocated.join("self/root/etc"))
+                .unwrap(),
+            Path::new("/etc")
+        );
+        assert_eq!(
+            memoized.canonicalize("/proc/self/root/etc").unwrap(),
+            Path::new("/etc")
+        );
+    }
+
+    // Process helpers read through the relocated root too.
+    let identity = ProcessIdentity::capture(std::process::id()).unwrap();
+    assert_eq!(identity.pid(), std::process::id());
+}
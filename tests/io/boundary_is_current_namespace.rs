@@ -0,0 +1,104 @@
+#![cfg(target_os = "linux")]
+
+//! `boundary_is_current_namespace`: comparing a boundary's mount namespace with ours.
+
+use crate::common::cwd::CwdProcess;
+use proc_canonicalize::{boundary_is_current_namespace, ProcessGone};
+use std::io;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+// ==========================================================================
+// SAME NAMESPACE
+// ==========================================================================
+
+#[test]
+fn own_boundaries_are_in_the_current_namespace() {
+    let me = std::process::id();
+    let thread = std::fs::read_link("/proc/thread-self").unwrap();
+
+    for boundary in [
+        "/proc/self/root".to_string(),
+        "/proc/thread-self/cwd".to_string(),
+        format!("/proc/{me}/root/etc"),
+        format!("/proc/{me}/ns/net"),
+        format!("/proc/{}/root", thread.display()),
+    ] {
+        assert!(
+            boundary_is_current_namespace(&boundary).unwrap(),
+            "{boundary}"
+        );
+    }
+}
+
+#[test]
+fn child_without_its_own_mounts_shares_ours() {
+    let child = CwdProcess::spawn();
+    let boundary = child.cwd_boundary();
+    assert_eq!(
+        std::fs::canonicalize(&boundary).unwrap(),
+        std::fs::canonicalize(child.dir()).unwrap()
+    );
+
+    assert!(boundary_is_current_namespace(boundary).unwrap());
+}
+
+// ==========================================================================
+// OTHER NAMESPACE
+// ==========================================================================
+
+#[test]
+fn child_in_a_new_mount_namespace_does_not() {
+    // Needs privileges; skipped where `unshare` is refused.
+    let mut child = match Command::new("unshare")
+        .args(["--mount", "sleep", "30"])
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(_) => return eprintln!("skipping: no unshare here"),
+    };
+    let comm = format!("/proc/{}/comm", child.id());
+    let started = Instant::now();
+    // `unshare` execs `sleep` only after entering the new namespace.
+    while std::fs::read_to_string(&comm).is_ok_and(|name| name.trim() != "sleep") {
+        if started.elapsed() > Duration::from_secs(5) {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    if child.try_wait().unwrap().is_some() {
+        return eprintln!("skipping: cannot create a mount namespace here");
+    }
+
+    let same = boundary_is_current_namespace(format!("/proc/{}/root", child.id()));
+    let _ = child.kill();
+    let _ = child.wait();
+
+    assert!(!same.unwrap());
+}
+
+// ==========================================================================
+// ERRORS
+// ==========================================================================
+
+#[test]
+fn path_without_a_boundary_is_invalid_input() {
+    for path in ["/etc", "/proc/self", "proc/self/root"] {
+        let err = boundary_is_current_namespace(path).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{path}");
+    }
+}
+
+#[test]
+fn dead_process_is_process_gone() {
+    let mut child = Command::new("true").spawn().unwrap();
+    let pid = child.id();
+    child.wait().unwrap();
+
+    let err = boundary_is_current_namespace(format!("/proc/{pid}/root")).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.get_ref().is_some_and(|inner| inner.is::<ProcessGone>()));
+}
@@ -0,0 +1,59 @@
+#![cfg(target_os = "linux")]
+
+//! `can_access_boundary`: whether a boundary can be entered, without resolving under it.
+
+use proc_canonicalize::{can_access_boundary, canonicalize};
+use std::io;
+use std::process::Command;
+
+#[test]
+fn own_boundaries_are_accessible() {
+    let me = std::process::id();
+
+    for boundary in [
+        "/proc/self/root".to_string(),
+        "/proc/thread-self/cwd".to_string(),
+        "/proc/self/ns/net".to_string(),
+        format!("/proc/{me}/root"),
+    ] {
+        assert!(can_access_boundary(&boundary).unwrap(), "{boundary}");
+    }
+}
+
+#[test]
+fn only_the_boundary_is_checked() {
+    assert!(can_access_boundary("/proc/self/root/no/such/entry").unwrap());
+    assert!(canonicalize("/proc/self/root/no/such/entry").is_err());
+}
+
+#[test]
+fn dead_process_is_not_accessible() {
+    let mut child = Command::new("true").spawn().unwrap();
+    let pid = child.id();
+    child.wait().unwrap();
+
+    for boundary in [format!("/proc/{pid}/root"), "/proc/0/cwd".to_string()] {
+        assert!(!can_access_boundary(&boundary).unwrap(), "{boundary}");
+    }
+}
+
+#[test]
+fn agrees_with_canonicalize_on_the_bare_boundary() {
+    // PID 1 is usually another user's: inaccessible unless running as root.
+    for boundary in ["/proc/1/root", "/proc/1/cwd", "/proc/self/root"] {
+        assert_eq!(
+            can_access_boundary(boundary).unwrap(),
+            canonicalize(boundary).is_ok(),
+            "{boundary}"
+        );
+    }
+}
+
+#[test]
+fn path_without_a_boundary_is_invalid_input() {
+    for path in ["/etc", "/proc/self", "relative/proc/self/root"] {
+        let err = can_access_boundary(path).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{path}");
+    }
+}
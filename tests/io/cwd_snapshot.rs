@@ -0,0 +1,61 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_cwd_snapshot`: a pinned handle on a process's cwd survives its `chdir`.
+
+use proc_canonicalize::canonicalize_cwd_snapshot;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+#[test]
+fn snapshot_stays_on_old_cwd_after_process_moves() {
+    let before = tempfile::tempdir().unwrap();
+    let after = tempfile::tempdir().unwrap();
+    std::fs::write(before.path().join("marker"), "before").unwrap();
+
+    // Waits for a line, changes directory, reports, then waits to be killed.
+    let mut child = Command::new("sh")
+        .args(["-c", "read _; cd \"$1\"; echo moved; read _", "sh"])
+        .arg(after.path())
+        .current_dir(before.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    let (pinned, prefix) = canonicalize_cwd_snapshot(child.id()).unwrap();
+    writeln!(stdin).unwrap();
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    assert_eq!(line.trim(), "moved");
+
+    let through_pin = format!("/proc/self/fd/{}/marker", pinned.as_raw_fd());
+    assert_eq!(std::fs::read_to_string(through_pin).unwrap(), "before");
+    assert_eq!(
+        std::fs::canonicalize(prefix).unwrap(),
+        std::fs::canonicalize(after.path()).unwrap()
+    );
+
+    drop(stdin);
+    child.wait().unwrap();
+}
+
+#[test]
+fn prefix_names_the_requested_pid() {
+    let pid = std::process::id();
+
+    let (pinned, prefix) = canonicalize_cwd_snapshot(pid).unwrap();
+
+    assert_eq!(prefix, Path::new(&format!("/proc/{pid}/cwd")));
+    assert!(pinned.metadata().unwrap().is_dir());
+}
+
+#[test]
+fn missing_process_reports_not_found() {
+    let err = canonicalize_cwd_snapshot(u32::MAX).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
@@ -0,0 +1,19 @@
+#![cfg(feature = "fs")]
+
+//! Opening, reading links and probing boundaries: the helpers that act on a path
+//! rather than only resolve it.
+
+#[cfg(target_os = "linux")]
+#[path = "../common"]
+mod common {
+    pub mod cwd;
+    pub mod root;
+}
+
+mod boundary_is_current_namespace;
+mod can_access_boundary;
+mod cwd_snapshot;
+mod open;
+mod open_nofollow_last;
+mod pidfd;
+mod read_link_preserving;
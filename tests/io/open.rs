@@ -0,0 +1,74 @@
+#![cfg(target_os = "linux")]
+
+//! Integration tests for the namespace-aware `open` helper.
+
+use std::fs::OpenOptions;
+use std::io::{self, Read, Write};
+use std::os::unix::fs::symlink;
+
+fn read_all(mut file: std::fs::File) -> String {
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).unwrap();
+    contents
+}
+
+#[test]
+fn open_reads_file_through_proc_self_root() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("data.txt"), "inside").unwrap();
+    let through_root = format!("/proc/self/root{}/data.txt", dir.path().display());
+
+    let file = proc_canonicalize::open(through_root, OpenOptions::new().read(true)).unwrap();
+
+    assert_eq!(read_all(file), "inside");
+}
+
+#[test]
+fn open_reads_file_through_proc_self_cwd() {
+    let cwd_file = tempfile::NamedTempFile::new_in(".").unwrap();
+    std::fs::write(cwd_file.path(), "cwd-data").unwrap();
+    let file_name = cwd_file.path().file_name().unwrap().to_str().unwrap();
+    let through_cwd = format!("/proc/self/cwd/{file_name}");
+
+    let file = proc_canonicalize::open(through_cwd, OpenOptions::new().read(true)).unwrap();
+
+    assert_eq!(read_all(file), "cwd-data");
+}
+
+#[test]
+fn open_follows_indirect_symlink_into_namespace() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("data.txt"), "via-link").unwrap();
+    let container_link = dir.path().join("container");
+    symlink("/proc/self/root", &container_link).unwrap();
+    let through_link = container_link.join(dir.path().strip_prefix("/").unwrap());
+
+    let file =
+        proc_canonicalize::open(through_link.join("data.txt"), OpenOptions::new().read(true))
+            .unwrap();
+
+    assert_eq!(read_all(file), "via-link");
+}
+
+#[test]
+fn open_for_write_updates_existing_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("log.txt");
+    std::fs::write(&target, "").unwrap();
+    let through_root = format!("/proc/self/root{}", target.display());
+
+    let mut file = proc_canonicalize::open(through_root, OpenOptions::new().write(true)).unwrap();
+    file.write_all(b"appended").unwrap();
+
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "appended");
+}
+
+#[test]
+fn open_missing_file_returns_not_found() {
+    let result = proc_canonicalize::open(
+        "/proc/self/root/this_file_does_not_exist_12345",
+        OpenOptions::new().read(true),
+    );
+
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotFound);
+}
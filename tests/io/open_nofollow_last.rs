@@ -0,0 +1,147 @@
+#![cfg(target_os = "linux")]
+
+//! `open_nofollow_last`: the parent resolved namespace-aware, the final component
+//! opened with `O_NOFOLLOW`.
+
+use crate::common::root::through_root;
+use proc_canonicalize::open_nofollow_last;
+use std::fs::OpenOptions;
+use std::io::{self, Read};
+use std::os::unix::fs::symlink;
+
+/// `ELOOP` on Linux, what `O_NOFOLLOW` fails with on a symlink.
+const ELOOP: i32 = 40;
+
+fn read() -> OpenOptions {
+    let mut options = OpenOptions::new();
+    options.read(true);
+    options
+}
+
+// ==========================================================================
+// OPENING
+// ==========================================================================
+
+#[test]
+fn regular_file_is_opened_through_the_boundary() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("data.txt"), "inside").unwrap();
+
+    let mut file = open_nofollow_last(through_root(dir.path()).join("data.txt"), &read()).unwrap();
+
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "inside");
+}
+
+#[test]
+fn directory_is_opened() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let file = open_nofollow_last(through_root(dir.path()), &read()).unwrap();
+
+    assert!(file.metadata().unwrap().is_dir());
+}
+
+#[test]
+fn symlinks_in_the_parent_are_followed() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("real")).unwrap();
+    std::fs::write(dir.path().join("real/data.txt"), "").unwrap();
+    symlink(
+        through_root(&dir.path().join("real")),
+        dir.path().join("link"),
+    )
+    .unwrap();
+
+    assert!(open_nofollow_last(dir.path().join("link/data.txt"), &read()).is_ok());
+}
+
+#[test]
+fn missing_file_is_created_inside_the_namespace() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut create = OpenOptions::new();
+    create.write(true).create_new(true);
+
+    open_nofollow_last(through_root(dir.path()).join("new.txt"), &create).unwrap();
+
+    assert!(dir.path().join("new.txt").is_file());
+}
+
+// ==========================================================================
+// A SYMLINK AS THE FINAL COMPONENT
+// ==========================================================================
+
+#[test]
+fn symlink_to_a_file_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("target.txt"), "").unwrap();
+    symlink("target.txt", dir.path().join("link")).unwrap();
+
+    let err = open_nofollow_last(through_root(dir.path()).join("link"), &read()).unwrap_err();
+
+    assert_eq!(err.raw_os_error(), Some(ELOOP));
+}
+
+#[test]
+fn symlink_into_proc_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root/etc", dir.path().join("etc")).unwrap();
+
+    let err = open_nofollow_last(dir.path().join("etc"), &read()).unwrap_err();
+
+    assert_eq!(err.raw_os_error(), Some(ELOOP));
+}
+
+#[test]
+fn dangling_symlink_is_not_created_through() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("created-elsewhere", dir.path().join("link")).unwrap();
+    let mut create = OpenOptions::new();
+    create.write(true).create(true);
+
+    let err = open_nofollow_last(through_root(dir.path()).join("link"), &create).unwrap_err();
+
+    assert_eq!(err.raw_os_error(), Some(ELOOP));
+    assert!(!dir.path().join("created-elsewhere").exists());
+}
+
+// ==========================================================================
+// OTHER ERRORS
+// ==========================================================================
+
+#[test]
+fn path_without_a_final_component_is_invalid_input() {
+    let err = open_nofollow_last("/proc/self/root/etc/..", &read()).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn trailing_dot_is_invalid_input() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("real")).unwrap();
+    symlink("real", dir.path().join("link")).unwrap();
+
+    for written in ["link/.", "link/./", "real/."] {
+        let path = format!("{}/{written}", through_root(dir.path()).display());
+
+        let err = open_nofollow_last(&path, &read()).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{written}");
+    }
+}
+
+#[test]
+fn root_is_invalid_input() {
+    let err = open_nofollow_last("/", &read()).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn missing_parent_is_not_found() {
+    let err = open_nofollow_last("/proc/self/root/no/such/dir/file", &read()).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
@@ -0,0 +1,34 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_pidfd`: descriptors that are not pidfds are refused before any
+//! resolution. Resolution through a real pidfd is covered by the unit tests against
+//! recorded fdinfo, since opening one needs a syscall the test suite cannot make.
+
+use proc_canonicalize::{canonicalize_pidfd, NamespaceKind};
+use std::io;
+use std::os::unix::io::AsFd;
+
+#[test]
+fn regular_file_is_not_a_pidfd() {
+    let file = tempfile::tempfile().unwrap();
+
+    let err = canonicalize_pidfd(file.as_fd(), NamespaceKind::Root, "etc").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn proc_directory_handle_is_not_a_pidfd() {
+    let proc_dir = std::fs::File::open(format!("/proc/{}", std::process::id())).unwrap();
+
+    let err = canonicalize_pidfd(proc_dir.as_fd(), NamespaceKind::Cwd, "").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn kinds_name_their_magic_links() {
+    assert_eq!(NamespaceKind::Root.as_str(), "root");
+    assert_eq!(NamespaceKind::Cwd.as_str(), "cwd");
+    assert_eq!(NamespaceKind::Ns.as_str(), "ns");
+}
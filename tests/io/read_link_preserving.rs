@@ -0,0 +1,98 @@
+#![cfg(target_os = "linux")]
+
+//! `read_link_preserving`: one symlink hop, boundaries in the target kept intact.
+
+use proc_canonicalize::read_link_preserving;
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+// ==========================================================================
+// TARGETS THAT REACH A BOUNDARY
+// ==========================================================================
+
+#[test]
+fn absolute_magic_target_is_returned_as_is() {
+    let dir = tempfile::tempdir().unwrap();
+    let link = dir.path().join("container");
+    symlink("/proc/self/root/etc", &link).unwrap();
+
+    assert_eq!(
+        read_link_preserving(&link).unwrap(),
+        Path::new("/proc/self/root/etc")
+    );
+}
+
+#[test]
+fn dotdot_in_magic_target_is_normalized() {
+    let dir = tempfile::tempdir().unwrap();
+    let link = dir.path().join("container");
+    symlink("/proc/self/../self/root", &link).unwrap();
+
+    assert_eq!(
+        read_link_preserving(&link).unwrap(),
+        Path::new("/proc/self/root")
+    );
+}
+
+#[test]
+fn relative_target_landing_on_boundary_becomes_absolute() {
+    let dir = tempfile::tempdir().unwrap();
+    let link = dir.path().join("container");
+    let depth = dir.path().components().count() - 1;
+    let climb = "../".repeat(depth);
+    symlink(format!("{climb}proc/self/cwd"), &link).unwrap();
+
+    assert_eq!(
+        read_link_preserving(&link).unwrap(),
+        Path::new("/proc/self/cwd")
+    );
+}
+
+#[test]
+fn namespace_link_target_is_preserved() {
+    let dir = tempfile::tempdir().unwrap();
+    let link = dir.path().join("netns");
+    symlink("/proc/self/ns/net", &link).unwrap();
+
+    assert_eq!(
+        read_link_preserving(&link).unwrap(),
+        Path::new("/proc/self/ns/net")
+    );
+}
+
+#[test]
+fn only_one_hop_is_followed() {
+    let dir = tempfile::tempdir().unwrap();
+    let inner = dir.path().join("inner");
+    let outer = dir.path().join("outer");
+    symlink("/proc/self/root", &inner).unwrap();
+    symlink(&inner, &outer).unwrap();
+
+    assert_eq!(read_link_preserving(&outer).unwrap(), inner);
+}
+
+// ==========================================================================
+// ORDINARY TARGETS
+// ==========================================================================
+
+#[test]
+fn ordinary_relative_target_is_returned_raw() {
+    let dir = tempfile::tempdir().unwrap();
+    let link = dir.path().join("link");
+    symlink("./sub/../file", &link).unwrap();
+
+    assert_eq!(
+        read_link_preserving(&link).unwrap(),
+        std::fs::read_link(&link).unwrap()
+    );
+}
+
+#[test]
+fn non_symlink_reports_read_link_error() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = read_link_preserving(dir.path()).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
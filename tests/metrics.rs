@@ -0,0 +1,62 @@
+#![cfg(all(target_os = "linux", feature = "metrics"))]
+
+//! The `metrics` counters. They are process-wide and the tests in this file run in
+//! parallel, so every assertion is a lower bound on the growth across one call.
+
+use proc_canonicalize::{canonicalize, metrics, CanonicalizeOptions, EscapeBehavior};
+use std::os::unix::fs::symlink;
+
+#[test]
+fn plain_path_counts_a_call() {
+    let before = metrics();
+
+    canonicalize("/").unwrap();
+
+    assert!(metrics().calls() > before.calls());
+}
+
+#[test]
+fn boundary_path_counts_a_boundary() {
+    let before = metrics();
+
+    canonicalize("/proc/self/root/etc").unwrap();
+
+    assert!(metrics().boundaries() > before.boundaries());
+}
+
+#[test]
+fn indirect_link_into_proc_counts_a_boundary() {
+    let temp = tempfile::tempdir().unwrap();
+    let link = temp.path().join("container");
+    symlink("/proc/self/root", &link).unwrap();
+    let before = metrics();
+
+    canonicalize(&link).unwrap();
+
+    assert!(metrics().boundaries() > before.boundaries());
+}
+
+#[test]
+fn escape_is_counted_even_when_it_is_an_error() {
+    let before = metrics();
+
+    // The test runs from the crate directory, so /proc/self/cwd/.. climbs above the cwd boundary.
+    let options = CanonicalizeOptions::new().escape_behavior(EscapeBehavior::Error);
+    options.canonicalize("/proc/self/cwd/..").unwrap_err();
+
+    assert!(metrics().escapes() > before.escapes());
+}
+
+#[test]
+fn symlink_loop_is_counted() {
+    let temp = tempfile::tempdir().unwrap();
+    symlink("b", temp.path().join("a")).unwrap();
+    symlink("a", temp.path().join("b")).unwrap();
+    let before = metrics();
+
+    canonicalize(temp.path().join("a")).unwrap_err();
+
+    let after = metrics();
+    assert!(after.loops() > before.loops());
+    assert!(after.calls() > before.calls());
+}
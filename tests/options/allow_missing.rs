@@ -0,0 +1,153 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::allow_missing`: `realpath -m` resolution, with dangling
+//! symlinks reported by where they point inside the namespace.
+
+use crate::common::root::through_root;
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::{canonicalize, CanonicalizeOptions};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+fn lenient() -> CanonicalizeOptions {
+    CanonicalizeOptions::new().allow_missing(true)
+}
+
+/// A canonical temporary directory and its path seen through `/proc/self/root`.
+fn namespace_tempdir() -> (tempfile::TempDir, PathBuf) {
+    let (dir, host) = canonical_tempdir();
+    (dir, through_root(&host))
+}
+
+// ==========================================================================
+// DANGLING FINAL SYMLINK
+// ==========================================================================
+
+#[test]
+fn strict_mode_rejects_dangling_symlink_in_namespace() {
+    let (dir, through_root) = namespace_tempdir();
+    symlink("/no/such/target", dir.path().join("broken")).unwrap();
+
+    let err = canonicalize(through_root.join("broken")).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn absolute_dangling_target_resolves_inside_namespace() {
+    let (dir, through_root) = namespace_tempdir();
+    symlink("/no/such/target", dir.path().join("broken")).unwrap();
+
+    assert_eq!(
+        lenient().canonicalize(through_root.join("broken")).unwrap(),
+        Path::new("/proc/self/root/no/such/target")
+    );
+}
+
+#[test]
+fn relative_dangling_target_resolves_beside_the_link() {
+    let (dir, through_root) = namespace_tempdir();
+    symlink("gone/../missing", dir.path().join("broken")).unwrap();
+
+    assert_eq!(
+        lenient().canonicalize(through_root.join("broken")).unwrap(),
+        through_root.join("missing")
+    );
+}
+
+#[test]
+fn dangling_target_in_container_root_stays_in_container() {
+    let (_dir, rootfs) = canonical_tempdir();
+    std::fs::create_dir(rootfs.join("etc")).unwrap();
+    symlink("/usr/lib/missing.so", rootfs.join("etc/broken")).unwrap();
+    let options = lenient().stand_in_root(&rootfs);
+
+    assert_eq!(
+        options.canonicalize("/proc/4242/root/etc/broken").unwrap(),
+        Path::new("/proc/4242/root/usr/lib/missing.so")
+    );
+    assert_eq!(
+        CanonicalizeOptions::new()
+            .stand_in_root(&rootfs)
+            .canonicalize("/proc/4242/root/etc/broken")
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::NotFound
+    );
+}
+
+// ==========================================================================
+// MISSING COMPONENTS
+// ==========================================================================
+
+#[test]
+fn missing_components_are_appended_lexically() {
+    assert_eq!(
+        lenient()
+            .canonicalize("/proc/self/root/no/such/./dir/../file/")
+            .unwrap(),
+        Path::new("/proc/self/root/no/such/file")
+    );
+}
+
+#[test]
+fn dotdot_through_missing_components_stops_at_boundary() {
+    assert_eq!(
+        lenient()
+            .canonicalize("/proc/self/root/nope/../../../x")
+            .unwrap(),
+        Path::new("/proc/self/root/x")
+    );
+}
+
+#[test]
+fn existing_paths_resolve_as_in_strict_mode() {
+    for path in ["/proc/self/root/etc", "/proc/self/cwd", "/proc/self/root"] {
+        assert_eq!(
+            lenient().canonicalize(path).unwrap(),
+            canonicalize(path).unwrap(),
+            "{path}"
+        );
+    }
+}
+
+#[test]
+fn plain_paths_resolve_like_realpath_m() {
+    let (_dir, host) = canonical_tempdir();
+    symlink("/no/such/target", host.join("broken")).unwrap();
+
+    assert_eq!(
+        lenient().canonicalize(host.join("broken")).unwrap(),
+        Path::new("/no/such/target")
+    );
+    assert_eq!(
+        lenient().canonicalize(host.join("a/b/../c")).unwrap(),
+        host.join("a/c")
+    );
+}
+
+// ==========================================================================
+// STILL ERRORS
+// ==========================================================================
+
+#[test]
+fn file_used_as_directory_still_fails() {
+    let (dir, through_root) = namespace_tempdir();
+    std::fs::write(dir.path().join("file"), "").unwrap();
+
+    let err = lenient()
+        .canonicalize(through_root.join("file/below"))
+        .unwrap_err();
+
+    assert_eq!(err.raw_os_error(), Some(20)); // ENOTDIR
+}
+
+#[test]
+fn missing_process_still_fails() {
+    let err = lenient()
+        .canonicalize("/proc/999999999/root/etc")
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
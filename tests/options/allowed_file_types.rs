@@ -0,0 +1,146 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::allowed_file_types` and `FileTypeSet`.
+
+use proc_canonicalize::{CanonicalizeOptions, FileTypeSet};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::os::unix::net::UnixListener;
+
+fn only(types: FileTypeSet) -> CanonicalizeOptions {
+    CanonicalizeOptions::new().allowed_file_types(types)
+}
+
+// ==========================================================================
+// FILE TYPE SETS
+// ==========================================================================
+
+#[test]
+fn default_set_is_all() {
+    assert_eq!(FileTypeSet::default(), FileTypeSet::ALL);
+}
+
+#[test]
+fn union_contains_both_members() {
+    let set = FileTypeSet::FILE | FileTypeSet::DIR;
+
+    assert!(set.contains(FileTypeSet::FILE));
+    assert!(set.contains(FileTypeSet::DIR));
+    assert!(!set.contains(FileTypeSet::CHAR_DEVICE));
+    assert!(FileTypeSet::ALL.contains(set));
+    assert!(!FileTypeSet::empty().contains(FileTypeSet::FILE));
+}
+
+#[test]
+fn debug_lists_member_names() {
+    let set = FileTypeSet::DIR | FileTypeSet::SOCKET;
+
+    assert_eq!(format!("{set:?}"), r#"{"directory", "socket"}"#);
+}
+
+// ==========================================================================
+// CHECKING THE RESULT
+// ==========================================================================
+
+#[test]
+fn default_allows_devices() {
+    let resolved = CanonicalizeOptions::new()
+        .canonicalize("/proc/self/root/dev/null")
+        .unwrap();
+
+    assert_eq!(resolved, std::path::Path::new("/proc/self/root/dev/null"));
+}
+
+#[test]
+fn allowed_type_is_returned_unchanged() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("file"), b"").unwrap();
+    let path = dir.path().join("file");
+
+    assert_eq!(
+        only(FileTypeSet::FILE).canonicalize(&path).unwrap(),
+        CanonicalizeOptions::new().canonicalize(&path).unwrap()
+    );
+}
+
+#[test]
+fn character_device_is_rejected_as_invalid_input() {
+    let err = only(FileTypeSet::FILE | FileTypeSet::DIR)
+        .canonicalize("/proc/self/root/dev/null")
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("character device"), "{err}");
+}
+
+#[test]
+fn directory_is_rejected_when_only_files_are_allowed() {
+    let err = only(FileTypeSet::FILE)
+        .canonicalize("/proc/self/root/etc")
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn socket_is_rejected_when_not_allowed() {
+    let dir = tempfile::tempdir().unwrap();
+    let _listener = UnixListener::bind(dir.path().join("sock")).unwrap();
+
+    let err = only(FileTypeSet::FILE | FileTypeSet::DIR)
+        .canonicalize(dir.path().join("sock"))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    assert!(only(FileTypeSet::SOCKET)
+        .canonicalize(dir.path().join("sock"))
+        .is_ok());
+}
+
+#[test]
+fn type_is_that_of_the_symlink_target() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root/dev/null", dir.path().join("null")).unwrap();
+
+    let err = only(FileTypeSet::FILE)
+        .canonicalize(dir.path().join("null"))
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn namespace_link_counts_as_a_regular_file() {
+    assert!(only(FileTypeSet::FILE)
+        .canonicalize("/proc/self/ns/net")
+        .is_ok());
+}
+
+#[test]
+fn missing_result_passes_under_allow_missing() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = only(FileTypeSet::DIR).allow_missing(true);
+
+    assert!(options.canonicalize(dir.path().join("not-yet")).is_ok());
+}
+
+#[test]
+fn missing_result_still_fails_as_not_found_without_allow_missing() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = only(FileTypeSet::DIR)
+        .canonicalize(dir.path().join("not-yet"))
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn rejection_is_not_hidden_by_sanitize_errors() {
+    let err = only(FileTypeSet::DIR)
+        .sanitize_errors(true)
+        .canonicalize("/proc/self/root/dev/null")
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
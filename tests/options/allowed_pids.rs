@@ -0,0 +1,157 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::allowed_pids`, `allow_self` and `allow_thread_self`: only
+//! whitelisted selectors count as boundaries; the rest resolve like ordinary paths.
+
+use crate::common::cwd::CwdProcess;
+use proc_canonicalize::{canonicalize, CanonicalizeOptions};
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+fn own_root(rest: &str) -> String {
+    format!("/proc/{}/root{rest}", std::process::id())
+}
+
+fn host(path: &str) -> std::path::PathBuf {
+    std::fs::canonicalize(path).unwrap()
+}
+
+// ==========================================================================
+// NUMERIC PIDS
+// ==========================================================================
+
+#[test]
+fn default_preserves_every_pid() {
+    assert_eq!(
+        canonicalize(own_root("/etc")).unwrap(),
+        Path::new(&own_root("/etc"))
+    );
+}
+
+#[test]
+fn pid_not_on_the_list_is_resolved_like_a_normal_path() {
+    let options = CanonicalizeOptions::new().allowed_pids(&[]);
+
+    assert_eq!(
+        options.canonicalize(own_root("/etc")).unwrap(),
+        host("/etc")
+    );
+    assert_eq!(options.canonicalize(own_root("")).unwrap(), host("/"));
+}
+
+#[test]
+fn pid_on_the_list_is_preserved() {
+    let options = CanonicalizeOptions::new().allowed_pids(&[1, std::process::id()]);
+
+    assert_eq!(
+        options.canonicalize(own_root("/etc")).unwrap(),
+        Path::new(&own_root("/etc"))
+    );
+}
+
+#[test]
+fn unlisted_cwd_resolves_to_the_directory() {
+    let process = CwdProcess::spawn();
+    let options = CanonicalizeOptions::new().allowed_pids(&[]);
+
+    assert_eq!(
+        options.canonicalize(process.cwd_boundary()).unwrap(),
+        std::fs::canonicalize(process.dir()).unwrap()
+    );
+}
+
+#[test]
+fn task_boundary_is_governed_by_its_pid() {
+    let pid = std::process::id();
+    let task = format!("/proc/{pid}/task/{pid}/root/etc");
+
+    assert_eq!(
+        CanonicalizeOptions::new()
+            .allowed_pids(&[pid])
+            .canonicalize(&task)
+            .unwrap(),
+        Path::new(&task)
+    );
+    assert_eq!(
+        CanonicalizeOptions::new()
+            .allowed_pids(&[])
+            .canonicalize(&task)
+            .unwrap(),
+        host("/etc")
+    );
+}
+
+#[test]
+fn symlink_to_unlisted_boundary_is_followed() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink(own_root(""), dir.path().join("link")).unwrap();
+    let options = CanonicalizeOptions::new().allowed_pids(&[]);
+
+    assert_eq!(
+        options.canonicalize(dir.path().join("link/etc")).unwrap(),
+        host("/etc")
+    );
+}
+
+// ==========================================================================
+// SELF AND THREAD-SELF
+// ==========================================================================
+
+#[test]
+fn self_stays_allowed_when_only_pids_are_listed() {
+    let options = CanonicalizeOptions::new().allowed_pids(&[]);
+
+    assert_eq!(
+        options.canonicalize("/proc/self/root/etc").unwrap(),
+        Path::new("/proc/self/root/etc")
+    );
+    assert_eq!(
+        options.canonicalize("/proc/thread-self/root/etc").unwrap(),
+        Path::new("/proc/thread-self/root/etc")
+    );
+}
+
+#[test]
+fn disallowed_self_is_resolved_like_a_normal_path() {
+    let options = CanonicalizeOptions::new().allow_self(false);
+
+    assert_eq!(
+        options.canonicalize("/proc/self/root/etc").unwrap(),
+        host("/etc")
+    );
+    // Numeric PIDs and thread-self are untouched.
+    assert_eq!(
+        options.canonicalize(own_root("/etc")).unwrap(),
+        Path::new(&own_root("/etc"))
+    );
+    assert_eq!(
+        options.canonicalize("/proc/thread-self/root/etc").unwrap(),
+        Path::new("/proc/thread-self/root/etc")
+    );
+}
+
+#[test]
+fn disallowed_thread_self_is_resolved_like_a_normal_path() {
+    let options = CanonicalizeOptions::new().allow_thread_self(false);
+
+    assert_eq!(
+        options.canonicalize("/proc/thread-self/root").unwrap(),
+        host("/")
+    );
+    assert_eq!(
+        options.canonicalize("/proc/self/root").unwrap(),
+        Path::new("/proc/self/root")
+    );
+}
+
+#[test]
+fn expanded_self_is_not_filtered_again() {
+    let options = CanonicalizeOptions::new()
+        .allowed_pids(&[])
+        .expand_self(true);
+
+    assert_eq!(
+        options.canonicalize("/proc/self/root/etc").unwrap(),
+        Path::new(&own_root("/etc"))
+    );
+}
@@ -0,0 +1,77 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::base_dir`: relative paths resolved against an explicit base.
+//! What a base avoids when the current directory is gone is in `tests/deleted_cwd.rs`.
+
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::CanonicalizeOptions;
+use std::io;
+use std::path::Path;
+
+// ==========================================================================
+// EXPLICIT BASE
+// ==========================================================================
+
+#[test]
+fn relative_path_resolves_against_the_base() {
+    let (_dir, host) = canonical_tempdir();
+    std::fs::create_dir(host.join("sub")).unwrap();
+    let options = CanonicalizeOptions::new().base_dir(&host);
+
+    assert_eq!(options.canonicalize("sub").unwrap(), host.join("sub"));
+    assert_eq!(options.canonicalize("sub/..").unwrap(), host);
+}
+
+#[test]
+fn base_inside_a_boundary_keeps_it() {
+    let options = CanonicalizeOptions::new().base_dir("/proc/self/root");
+
+    assert_eq!(
+        options.canonicalize("etc").unwrap(),
+        Path::new("/proc/self/root/etc")
+    );
+    assert_eq!(
+        options.canonicalize("./etc/..").unwrap(),
+        Path::new("/proc/self/root")
+    );
+}
+
+#[test]
+fn absolute_paths_ignore_the_base() {
+    let options = CanonicalizeOptions::new().base_dir("/nonexistent");
+
+    assert_eq!(
+        options.canonicalize("/proc/self/root/etc").unwrap(),
+        Path::new("/proc/self/root/etc")
+    );
+}
+
+#[test]
+fn missing_path_under_the_base_is_an_ordinary_not_found() {
+    let options = CanonicalizeOptions::new().base_dir("/proc/self/root");
+
+    let err = options.canonicalize("no/such/entry").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.get_ref().is_none());
+}
+
+#[test]
+fn leading_dotdot_is_still_rejected_when_asked() {
+    let options = CanonicalizeOptions::new()
+        .base_dir("/proc/self/root/etc")
+        .reject_leading_dotdot(true);
+
+    let err = options.canonicalize("../etc").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn empty_path_is_not_the_base() {
+    let options = CanonicalizeOptions::new().base_dir("/proc/self/root");
+
+    let err = options.canonicalize("").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
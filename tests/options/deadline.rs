@@ -0,0 +1,55 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::deadline` and `canonicalize_with_deadline`: a wall-clock
+//! budget checked before each filesystem operation.
+
+use proc_canonicalize::{canonicalize, canonicalize_with_deadline, CanonicalizeOptions};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+fn generous() -> Instant {
+    Instant::now() + Duration::from_secs(60)
+}
+
+#[test]
+fn result_within_budget_matches_canonicalize() {
+    let temp = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", temp.path().join("container")).unwrap();
+
+    for path in [
+        temp.path().join("container/etc"),
+        Path::new("/proc/self/cwd").to_path_buf(),
+        temp.path().to_path_buf(),
+    ] {
+        assert_eq!(
+            canonicalize_with_deadline(&path, generous()).unwrap(),
+            canonicalize(&path).unwrap(),
+            "{}",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn expired_deadline_times_out_before_any_lookup() {
+    let past = Instant::now();
+
+    for path in ["/", "/proc/self/root/etc", "/nonexistent"] {
+        let err = canonicalize_with_deadline(path, past).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut, "{path}");
+    }
+}
+
+#[test]
+fn deadline_composes_with_other_options() {
+    let options = CanonicalizeOptions::new()
+        .resolve_outer_to_host(true)
+        .deadline(generous());
+
+    assert_eq!(
+        options.canonicalize("/proc/self/root/etc").unwrap(),
+        std::fs::canonicalize("/etc").unwrap()
+    );
+}
@@ -0,0 +1,136 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::escape_behavior`: return the host path, clamp at the
+//! boundary, or fail when a path leaves the boundary it entered through.
+
+use crate::common::cwd::CwdProcess;
+use proc_canonicalize::{BoundaryEscaped, CanonicalizeOptions, EscapeBehavior};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+fn with(behavior: EscapeBehavior) -> CanonicalizeOptions {
+    CanonicalizeOptions::new().escape_behavior(behavior)
+}
+
+// ==========================================================================
+// RETURN HOST (DEFAULT)
+// ==========================================================================
+
+#[test]
+fn default_returns_host_path_on_escape() {
+    let process = CwdProcess::spawn();
+    let host_parent = std::fs::canonicalize(process.dir().parent().unwrap()).unwrap();
+
+    let resolved = CanonicalizeOptions::new()
+        .canonicalize(process.cwd_boundary().join(".."))
+        .unwrap();
+
+    assert_eq!(resolved, host_parent);
+    assert_eq!(
+        with(EscapeBehavior::ReturnHost)
+            .canonicalize(process.cwd_boundary().join(".."))
+            .unwrap(),
+        host_parent
+    );
+}
+
+// ==========================================================================
+// CLAMP TO ROOT
+// ==========================================================================
+
+#[test]
+fn clamp_stops_dotdot_at_boundary() {
+    let process = CwdProcess::spawn();
+    std::fs::create_dir(process.dir().join("sub")).unwrap();
+
+    let resolved = with(EscapeBehavior::ClampToRoot)
+        .canonicalize(process.cwd_boundary().join("../../sub"))
+        .unwrap();
+
+    assert_eq!(resolved, process.cwd_boundary().join("sub"));
+}
+
+#[test]
+fn clamp_restarts_absolute_symlink_at_boundary() {
+    let process = CwdProcess::spawn();
+    std::fs::create_dir(process.dir().join("etc")).unwrap();
+    symlink("/etc", process.dir().join("config")).unwrap();
+
+    let resolved = with(EscapeBehavior::ClampToRoot)
+        .canonicalize(process.cwd_boundary().join("config"))
+        .unwrap();
+
+    assert_eq!(resolved, process.cwd_boundary().join("etc"));
+}
+
+#[test]
+fn clamp_reports_missing_clamped_target() {
+    let process = CwdProcess::spawn();
+    symlink(
+        "/nonexistent-inside-boundary",
+        process.dir().join("dangling"),
+    )
+    .unwrap();
+
+    let err = with(EscapeBehavior::ClampToRoot)
+        .canonicalize(process.cwd_boundary().join("dangling"))
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn clamp_detects_symlink_loops() {
+    let process = CwdProcess::spawn();
+    symlink("/loop", process.dir().join("loop")).unwrap();
+
+    let err = with(EscapeBehavior::ClampToRoot)
+        .canonicalize(process.cwd_boundary().join("../loop"))
+        .unwrap_err();
+
+    assert_eq!(err.raw_os_error(), Some(40)); // ELOOP
+}
+
+#[test]
+fn clamp_leaves_paths_inside_boundary_alone() {
+    let process = CwdProcess::spawn();
+    std::fs::create_dir(process.dir().join("sub")).unwrap();
+
+    let resolved = with(EscapeBehavior::ClampToRoot)
+        .canonicalize(process.cwd_boundary().join("sub/../sub"))
+        .unwrap();
+
+    assert_eq!(resolved, process.cwd_boundary().join("sub"));
+}
+
+// ==========================================================================
+// ERROR
+// ==========================================================================
+
+#[test]
+fn error_reports_boundary_and_host_path() {
+    let process = CwdProcess::spawn();
+    let host_parent = std::fs::canonicalize(process.dir().parent().unwrap()).unwrap();
+
+    let err = with(EscapeBehavior::Error)
+        .canonicalize(process.cwd_boundary().join(".."))
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    let escaped = err
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<BoundaryEscaped>())
+        .unwrap();
+    assert_eq!(escaped.boundary(), process.cwd_boundary());
+    assert_eq!(escaped.host_path(), host_parent);
+}
+
+#[test]
+fn error_does_not_fire_inside_boundary() {
+    let resolved = with(EscapeBehavior::Error)
+        .canonicalize("/proc/self/root/etc")
+        .unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+}
@@ -0,0 +1,90 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::expand_self`: `/proc/self` named by this process's PID.
+
+use crate::common::pid::this_process;
+use proc_canonicalize::{canonicalize, CanonicalizeOptions};
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+fn expanded() -> CanonicalizeOptions {
+    CanonicalizeOptions::new().expand_self(true)
+}
+
+// ==========================================================================
+// BOTH MODES
+// ==========================================================================
+
+#[test]
+fn default_keeps_self_verbatim() {
+    for path in ["/proc/self/root/etc", "/proc/thread-self/cwd"] {
+        assert_eq!(canonicalize(path).unwrap(), Path::new(path), "{path}");
+    }
+}
+
+#[test]
+fn self_boundaries_expand_to_this_pid() {
+    let me = this_process();
+
+    for suffix in ["root", "root/etc", "cwd", "ns/net"] {
+        assert_eq!(
+            expanded()
+                .canonicalize(Path::new("/proc/self").join(suffix))
+                .unwrap(),
+            me.join(suffix),
+            "{suffix}"
+        );
+    }
+}
+
+#[test]
+fn thread_self_expands_to_pid_and_tid() {
+    let me = Path::new("/proc").join(std::fs::read_link("/proc/thread-self").unwrap());
+
+    let resolved = expanded().canonicalize("/proc/thread-self/root").unwrap();
+
+    assert_eq!(resolved, me.join("root"));
+    assert!(resolved.starts_with(this_process().join("task")));
+}
+
+#[test]
+fn both_modes_round_trip_to_the_same_target() {
+    let literal = canonicalize("/proc/self/root/etc").unwrap();
+    let numeric = expanded().canonicalize("/proc/self/root/etc").unwrap();
+
+    assert_eq!(canonicalize(&literal).unwrap(), literal);
+    assert_eq!(canonicalize(&numeric).unwrap(), numeric);
+    assert_eq!(expanded().canonicalize(&numeric).unwrap(), numeric);
+    assert_eq!(
+        std::fs::canonicalize(&literal).unwrap(),
+        std::fs::canonicalize(&numeric).unwrap()
+    );
+}
+
+// ==========================================================================
+// WHAT IS LEFT ALONE
+// ==========================================================================
+
+#[test]
+fn numeric_pids_and_plain_paths_are_unchanged() {
+    let numeric = this_process().join("root/etc");
+
+    assert_eq!(expanded().canonicalize(&numeric).unwrap(), numeric);
+    assert_eq!(
+        expanded().canonicalize("/etc").unwrap(),
+        std::fs::canonicalize("/etc").unwrap()
+    );
+}
+
+#[test]
+fn symlink_to_self_root_is_expanded() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", dir.path().join("host_root")).unwrap();
+
+    assert_eq!(
+        expanded()
+            .canonicalize(dir.path().join("host_root/etc"))
+            .unwrap(),
+        this_process().join("root/etc")
+    );
+}
@@ -0,0 +1,134 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::follow_terminal_boundary`: a path ending exactly on a
+//! boundary resolves to the host directory behind it.
+
+use crate::common::cwd::CwdProcess;
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::{canonicalize, CanonicalizeOptions};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+fn follow() -> CanonicalizeOptions {
+    CanonicalizeOptions::new().follow_terminal_boundary(true)
+}
+
+// ==========================================================================
+// BARE BOUNDARIES: PRESERVED BY DEFAULT, FOLLOWED WHEN ASKED
+// ==========================================================================
+
+#[test]
+fn self_root_is_preserved_by_default_and_followed_on_request() {
+    assert_eq!(
+        canonicalize("/proc/self/root").unwrap(),
+        Path::new("/proc/self/root")
+    );
+    assert_eq!(
+        follow().canonicalize("/proc/self/root").unwrap(),
+        std::fs::canonicalize("/proc/self/root").unwrap()
+    );
+}
+
+#[test]
+fn pid_1_root_matches_kernel_resolution_when_accessible() {
+    let kernel = match std::fs::canonicalize("/proc/1/root") {
+        Ok(kernel) => kernel,
+        // Unprivileged runs may not look into PID 1; the option changes nothing then.
+        Err(err) => {
+            let ours = follow().canonicalize("/proc/1/root").unwrap_err();
+            assert_eq!(ours.kind(), err.kind());
+            return;
+        }
+    };
+
+    assert_eq!(
+        CanonicalizeOptions::new()
+            .canonicalize("/proc/1/root")
+            .unwrap(),
+        Path::new("/proc/1/root")
+    );
+    assert_eq!(follow().canonicalize("/proc/1/root").unwrap(), kernel);
+}
+
+#[test]
+fn cwd_boundary_follows_to_the_directory() {
+    let process = CwdProcess::spawn();
+
+    assert_eq!(
+        follow().canonicalize(process.cwd_boundary()).unwrap(),
+        std::fs::canonicalize(process.dir()).unwrap()
+    );
+}
+
+// ==========================================================================
+// OTHER WAYS OF ENDING ON A BOUNDARY
+// ==========================================================================
+
+#[test]
+fn dotdot_back_onto_boundary_is_followed() {
+    let process = CwdProcess::spawn();
+    std::fs::create_dir(process.dir().join("sub")).unwrap();
+
+    assert_eq!(
+        follow()
+            .canonicalize(process.cwd_boundary().join("sub/.."))
+            .unwrap(),
+        std::fs::canonicalize(process.dir()).unwrap()
+    );
+}
+
+#[test]
+fn symlink_to_boundary_is_followed() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", dir.path().join("host_root")).unwrap();
+
+    assert_eq!(
+        canonicalize(dir.path().join("host_root")).unwrap(),
+        Path::new("/proc/self/root")
+    );
+    assert_eq!(
+        follow().canonicalize(dir.path().join("host_root")).unwrap(),
+        Path::new("/")
+    );
+}
+
+#[test]
+fn stand_in_root_follows_to_the_stand_in() {
+    let (_dir, rootfs) = canonical_tempdir();
+
+    assert_eq!(
+        follow()
+            .stand_in_root(&rootfs)
+            .canonicalize("/proc/4242/root")
+            .unwrap(),
+        rootfs
+    );
+}
+
+// ==========================================================================
+// LEFT ALONE
+// ==========================================================================
+
+#[test]
+fn paths_below_a_boundary_keep_it() {
+    assert_eq!(
+        follow().canonicalize("/proc/self/root/etc").unwrap(),
+        Path::new("/proc/self/root/etc")
+    );
+}
+
+#[test]
+fn namespace_links_are_kept() {
+    assert_eq!(
+        follow().canonicalize("/proc/self/ns/net").unwrap(),
+        Path::new("/proc/self/ns/net")
+    );
+}
+
+#[test]
+fn missing_process_still_fails() {
+    let err = follow().canonicalize("/proc/999999999/root").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "fs")]
+
+//! `CanonicalizeOptions`: each module covers one builder setting.
+
+#[cfg(target_os = "linux")]
+#[path = "../common"]
+mod common {
+    pub mod cwd;
+    pub mod pid;
+    pub mod root;
+    pub mod temp;
+}
+
+mod allow_missing;
+mod allowed_file_types;
+mod allowed_pids;
+mod base_dir;
+mod deadline;
+mod escape_behavior;
+mod expand_self;
+mod follow_terminal_boundary;
+mod max_symlinks_per_component;
+mod memoize_boundaries;
+mod no_cross;
+mod pin_thread_self;
+mod preserve_on_inaccessible_boundary;
+mod preserve_unknown_proc_links;
+mod read_link_with;
+mod reject_leading_dotdot;
+mod reject_pid_zero;
+mod resolve_outer_to_host;
+mod security_max;
+mod stand_in_root;
+mod verify_process;
@@ -0,0 +1,162 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::max_symlinks_per_component`: each component's symlink chain
+//! is bounded on its own, separately from the 40-link total.
+
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::CanonicalizeOptions;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+/// `ELOOP` on Linux.
+const ELOOP: i32 = 40;
+
+/// `dir/<name>1 -> <name>2 -> ... -> <name><hops> -> target`: `hops` links to `target`.
+fn chain(dir: &Path, name: &str, hops: usize, target: &Path) -> PathBuf {
+    for hop in 1..hops {
+        symlink(
+            format!("{name}{}", hop + 1),
+            dir.join(format!("{name}{hop}")),
+        )
+        .unwrap();
+    }
+    symlink(target, dir.join(format!("{name}{hops}"))).unwrap();
+    dir.join(format!("{name}1"))
+}
+
+fn capped(limit: u32) -> CanonicalizeOptions {
+    CanonicalizeOptions::new().max_symlinks_per_component(limit)
+}
+
+/// `base/a1` is a 3-hop chain to `base/inner`, whose `b1` is a 3-hop chain to `base/leaf`.
+struct TwoChains {
+    _dir: tempfile::TempDir,
+    base: PathBuf,
+    path: PathBuf,
+}
+
+impl TwoChains {
+    fn new() -> Self {
+        let (dir, base) = canonical_tempdir();
+        std::fs::create_dir(base.join("inner")).unwrap();
+        std::fs::create_dir(base.join("leaf")).unwrap();
+        let first = chain(&base, "a", 3, Path::new("inner"));
+        chain(&base.join("inner"), "b", 3, &base.join("leaf"));
+        let path = first.join("b1");
+        TwoChains {
+            _dir: dir,
+            base,
+            path,
+        }
+    }
+}
+
+// ==========================================================================
+// EACH COMPONENT HAS ITS OWN BUDGET
+// ==========================================================================
+
+#[test]
+fn two_chains_each_within_limit_resolve() {
+    let chains = TwoChains::new();
+
+    // Six hops in total, but no component takes more than three.
+    assert_eq!(
+        capped(3).canonicalize(&chains.path).unwrap(),
+        chains.base.join("leaf")
+    );
+}
+
+#[test]
+fn either_chain_over_limit_fails_with_eloop() {
+    let chains = TwoChains::new();
+
+    let err = capped(2).canonicalize(chains.path).unwrap_err();
+
+    assert_eq!(err.raw_os_error(), Some(ELOOP));
+}
+
+#[test]
+fn only_the_long_chain_trips_the_limit() {
+    let (_dir, base) = canonical_tempdir();
+    std::fs::create_dir(base.join("inner")).unwrap();
+    let long = chain(&base, "long", 5, Path::new("inner"));
+    let short = chain(&base, "short", 2, Path::new("inner"));
+
+    assert_eq!(capped(4).canonicalize(short).unwrap(), base.join("inner"));
+    assert_eq!(
+        capped(4).canonicalize(&long).unwrap_err().raw_os_error(),
+        Some(ELOOP)
+    );
+    assert_eq!(capped(5).canonicalize(&long).unwrap(), base.join("inner"));
+}
+
+#[test]
+fn links_inside_a_target_count_toward_its_component() {
+    let (_dir, base) = canonical_tempdir();
+    std::fs::create_dir_all(base.join("real/sub")).unwrap();
+    symlink("real", base.join("via")).unwrap();
+    // `entry` -> `via/sub`, and `via` is itself a link: two hops for `entry`.
+    symlink("via/sub", base.join("entry")).unwrap();
+
+    assert_eq!(
+        capped(1)
+            .canonicalize(base.join("entry"))
+            .unwrap_err()
+            .raw_os_error(),
+        Some(ELOOP)
+    );
+    assert_eq!(
+        capped(2).canonicalize(base.join("entry")).unwrap(),
+        base.join("real/sub")
+    );
+}
+
+// ==========================================================================
+// DEFAULTS AND BOUNDARIES
+// ==========================================================================
+
+#[test]
+fn default_allows_long_chains() {
+    let chains = TwoChains::new();
+
+    assert_eq!(
+        CanonicalizeOptions::new()
+            .canonicalize(&chains.path)
+            .unwrap(),
+        chains.base.join("leaf")
+    );
+}
+
+#[test]
+fn chain_leading_to_a_boundary_is_capped() {
+    let dir = tempfile::tempdir().unwrap();
+    let link = chain(dir.path(), "ns", 3, Path::new("/proc/self/root"));
+
+    assert_eq!(
+        capped(3).canonicalize(link.join("etc")).unwrap(),
+        Path::new("/proc/self/root/etc")
+    );
+    assert_eq!(
+        capped(2)
+            .canonicalize(link.join("etc"))
+            .unwrap_err()
+            .raw_os_error(),
+        Some(ELOOP)
+    );
+}
+
+#[test]
+fn zero_rejects_any_symlink() {
+    let (_dir, base) = canonical_tempdir();
+    std::fs::create_dir(base.join("real")).unwrap();
+    let link = chain(&base, "l", 1, Path::new("real"));
+
+    assert_eq!(
+        capped(0).canonicalize(link).unwrap_err().raw_os_error(),
+        Some(ELOOP)
+    );
+    assert_eq!(
+        capped(0).canonicalize(base.join("real")).unwrap(),
+        base.join("real")
+    );
+}
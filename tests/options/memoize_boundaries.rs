@@ -0,0 +1,195 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::memoize_boundaries`: host paths of root boundaries reused
+//! across calls while the boundary enters the same directory, or until `clear_cache`.
+//!
+//! The boundary is stood in for by a symlink, so "the container restarted onto a new
+//! root" is a retargeted link.
+
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::CanonicalizeOptions;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+struct Roots {
+    _dir: tempfile::TempDir,
+    /// The stand-in for every boundary: a link to `old` or `new`.
+    link: PathBuf,
+    old: PathBuf,
+    new: PathBuf,
+}
+
+impl Roots {
+    fn new() -> Self {
+        let (dir, base) = canonical_tempdir();
+        let old = base.join("old");
+        let new = base.join("new");
+        std::fs::create_dir_all(old.join("etc")).unwrap();
+        std::fs::create_dir_all(new.join("etc")).unwrap();
+        let link = base.join("current");
+        symlink(&old, &link).unwrap();
+        Roots {
+            _dir: dir,
+            link,
+            old,
+            new,
+        }
+    }
+
+    fn restart(&self) {
+        std::fs::remove_file(&self.link).unwrap();
+        symlink(&self.new, &self.link).unwrap();
+    }
+
+    fn options(&self) -> CanonicalizeOptions {
+        CanonicalizeOptions::new().stand_in_root(&self.link)
+    }
+}
+
+// ==========================================================================
+// WITHOUT MEMOIZATION: EVERY CALL SEES THE CURRENT ROOT
+// ==========================================================================
+
+#[test]
+fn default_options_follow_a_retargeted_root() {
+    let roots = Roots::new();
+    let options = roots.options();
+    options.canonicalize("/proc/4242/root/etc").unwrap();
+
+    roots.restart();
+
+    assert_eq!(
+        options.canonicalize("/proc/4242/root/etc").unwrap(),
+        Path::new("/proc/4242/root/etc")
+    );
+}
+
+// ==========================================================================
+// MEMOIZED: REVALIDATED BY DEVICE AND INODE
+// ==========================================================================
+
+#[test]
+fn retargeted_root_is_noticed() {
+    let roots = Roots::new();
+    let options = roots.options().memoize_boundaries(true);
+    assert_eq!(
+        options.canonicalize("/proc/4242/root/etc").unwrap(),
+        Path::new("/proc/4242/root/etc")
+    );
+
+    roots.restart();
+
+    // The link now enters a different directory, so the remembered answer is not used.
+    assert_eq!(
+        options.canonicalize("/proc/4242/root/etc").unwrap(),
+        Path::new("/proc/4242/root/etc")
+    );
+}
+
+#[test]
+fn renamed_root_goes_stale_until_cleared() {
+    let roots = Roots::new();
+    let options = roots.options().memoize_boundaries(true);
+    options.canonicalize("/proc/4242/root/etc").unwrap();
+    let moved = roots.old.with_file_name("moved");
+    std::fs::rename(&roots.old, &moved).unwrap();
+    std::fs::remove_file(&roots.link).unwrap();
+    symlink(&moved, &roots.link).unwrap();
+
+    // Same directory, same inode: the old host path is served, and `etc` under the
+    // new name looks like an escape from it.
+    assert_eq!(
+        options.canonicalize("/proc/4242/root/etc").unwrap(),
+        moved.join("etc")
+    );
+
+    options.clear_cache();
+    assert_eq!(
+        options.canonicalize("/proc/4242/root/etc").unwrap(),
+        Path::new("/proc/4242/root/etc")
+    );
+}
+
+#[test]
+fn clones_share_one_cache() {
+    let roots = Roots::new();
+    let options = roots.options().memoize_boundaries(true);
+    let clone = options.clone();
+    options.canonicalize("/proc/4242/root/etc").unwrap();
+    std::fs::rename(&roots.old, roots.old.with_file_name("moved")).unwrap();
+    std::fs::remove_file(&roots.link).unwrap();
+    symlink(roots.old.with_file_name("moved"), &roots.link).unwrap();
+
+    // The clone sees the answer `options` stored, stale as it is, from any thread.
+    let moved = roots.old.with_file_name("moved").join("etc");
+    std::thread::spawn(move || {
+        assert_eq!(clone.canonicalize("/proc/4242/root/etc").unwrap(), moved);
+        clone.clear_cache();
+    })
+    .join()
+    .unwrap();
+    assert_eq!(
+        options.canonicalize("/proc/4242/root/etc").unwrap(),
+        Path::new("/proc/4242/root/etc")
+    );
+}
+
+#[test]
+fn clones_with_different_stand_ins_do_not_share_answers() {
+    let roots = Roots::new();
+    let on_old = CanonicalizeOptions::new()
+        .stand_in_root(&roots.old)
+        .resolve_outer_to_host(true)
+        .memoize_boundaries(true);
+    let on_new = on_old.clone().stand_in_root(&roots.new);
+
+    for _ in 0..2 {
+        assert_eq!(
+            on_old.canonicalize("/proc/4242/root/etc").unwrap(),
+            roots.old.join("etc")
+        );
+        assert_eq!(
+            on_new.canonicalize("/proc/4242/root/etc").unwrap(),
+            roots.new.join("etc")
+        );
+    }
+}
+
+#[test]
+fn cwd_boundaries_are_never_remembered() {
+    let roots = Roots::new();
+    let options = roots.options().memoize_boundaries(true);
+    options.canonicalize("/proc/4242/cwd/etc").unwrap();
+
+    roots.restart();
+
+    assert_eq!(
+        options.canonicalize("/proc/4242/cwd/etc").unwrap(),
+        Path::new("/proc/4242/cwd/etc")
+    );
+}
+
+#[test]
+fn vanished_root_still_fails_despite_cache() {
+    let roots = Roots::new();
+    let options = roots.options().memoize_boundaries(true);
+    options.canonicalize("/proc/4242/root/etc").unwrap();
+
+    std::fs::remove_file(&roots.link).unwrap();
+
+    let err = options.canonicalize("/proc/4242/root/etc").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    assert!(roots.old.exists());
+}
+
+#[test]
+fn real_root_boundary_is_memoized_transparently() {
+    let options = CanonicalizeOptions::new().memoize_boundaries(true);
+
+    for _ in 0..3 {
+        assert_eq!(
+            options.canonicalize("/proc/self/root/etc").unwrap(),
+            Path::new("/proc/self/root/etc")
+        );
+    }
+}
@@ -0,0 +1,113 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::no_cross`: the boundary is checked, not followed, and the
+//! remainder is appended lexically.
+
+use crate::common::root::through_root;
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::{CanonicalizeOptions, ProcessGone};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+fn outside() -> CanonicalizeOptions {
+    CanonicalizeOptions::new().no_cross(true)
+}
+
+// ==========================================================================
+// THE REMAINDER IS FOLDED, NOT VERIFIED
+// ==========================================================================
+
+#[test]
+fn missing_remainder_is_returned() {
+    assert_eq!(
+        outside()
+            .canonicalize("/proc/self/root/no/such/file")
+            .unwrap(),
+        Path::new("/proc/self/root/no/such/file")
+    );
+}
+
+#[test]
+fn dot_and_dotdot_are_folded_and_clamped_at_the_boundary() {
+    for (input, expected) in [
+        ("/proc/self/root/./etc/../srv/", "/proc/self/root/srv"),
+        ("/proc/self/root/../../etc", "/proc/self/root/etc"),
+        ("/proc/self/cwd/..", "/proc/self/cwd"),
+        ("/proc/self/root", "/proc/self/root"),
+    ] {
+        assert_eq!(
+            outside().canonicalize(input).unwrap(),
+            Path::new(expected),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn symlinks_in_the_remainder_are_not_followed() {
+    let (_dir, host) = canonical_tempdir();
+    symlink("/etc", host.join("link")).unwrap();
+    let path = through_root(&host.join("link/hostname"));
+
+    assert_eq!(outside().canonicalize(&path).unwrap(), path);
+}
+
+#[test]
+fn file_used_as_a_directory_is_not_caught() {
+    assert_eq!(
+        outside()
+            .canonicalize("/proc/self/root/etc/hostname/x")
+            .unwrap(),
+        Path::new("/proc/self/root/etc/hostname/x")
+    );
+}
+
+// ==========================================================================
+// THE BOUNDARY IS STILL CHECKED
+// ==========================================================================
+
+#[test]
+fn missing_process_fails_as_process_gone() {
+    let err = outside()
+        .canonicalize("/proc/4294967294/root/etc")
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.get_ref().is_some_and(|inner| inner.is::<ProcessGone>()));
+}
+
+#[test]
+fn paths_without_a_boundary_resolve_as_usual() {
+    assert!(outside().canonicalize("/no/such/file").is_err());
+    assert_eq!(
+        outside().canonicalize("/etc/.").unwrap(),
+        std::fs::canonicalize("/etc").unwrap()
+    );
+}
+
+#[test]
+fn symlink_into_proc_is_followed_up_to_the_boundary() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", dir.path().join("root")).unwrap();
+
+    assert_eq!(
+        outside()
+            .canonicalize(dir.path().join("root/no/such"))
+            .unwrap(),
+        Path::new("/proc/self/root/no/such")
+    );
+}
+
+#[test]
+fn boundary_is_kept_even_when_the_host_form_is_asked_for() {
+    for options in [
+        outside().resolve_outer_to_host(true),
+        outside().follow_terminal_boundary(true),
+    ] {
+        assert_eq!(
+            options.canonicalize("/proc/self/root").unwrap(),
+            Path::new("/proc/self/root")
+        );
+    }
+}
@@ -0,0 +1,80 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::pin_thread_self`: `/proc/thread-self` expanded to the calling
+//! thread's `/proc/PID/task/TID`.
+
+use proc_canonicalize::{canonicalize, CanonicalizeOptions};
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+fn pinned() -> CanonicalizeOptions {
+    CanonicalizeOptions::new().pin_thread_self(true)
+}
+
+/// `/proc/PID/task/TID` for the calling thread.
+fn this_thread() -> PathBuf {
+    Path::new("/proc").join(std::fs::read_link("/proc/thread-self").unwrap())
+}
+
+#[test]
+fn default_keeps_thread_self_verbatim() {
+    assert_eq!(
+        canonicalize("/proc/thread-self/root/etc").unwrap(),
+        Path::new("/proc/thread-self/root/etc")
+    );
+}
+
+#[test]
+fn thread_self_boundaries_expand_to_calling_thread() {
+    let me = this_thread();
+
+    for suffix in ["root", "root/etc", "cwd", "ns/net"] {
+        assert_eq!(
+            pinned()
+                .canonicalize(Path::new("/proc/thread-self").join(suffix))
+                .unwrap(),
+            me.join(suffix),
+            "{suffix}"
+        );
+    }
+}
+
+#[test]
+fn expanded_form_round_trips_to_same_target() {
+    let expanded = pinned().canonicalize("/proc/thread-self/root/etc").unwrap();
+
+    assert_eq!(canonicalize(&expanded).unwrap(), expanded);
+    assert_eq!(
+        std::fs::canonicalize(&expanded).unwrap(),
+        std::fs::canonicalize("/proc/thread-self/root/etc").unwrap()
+    );
+}
+
+#[test]
+fn indirect_link_to_thread_self_is_pinned() {
+    let temp = tempfile::tempdir().unwrap();
+    symlink("/proc/thread-self/root", temp.path().join("mine")).unwrap();
+
+    let resolved = pinned().canonicalize(temp.path().join("mine/etc")).unwrap();
+
+    assert_eq!(resolved, this_thread().join("root/etc"));
+}
+
+#[test]
+fn each_thread_pins_to_itself() {
+    let here = pinned().canonicalize("/proc/thread-self/cwd").unwrap();
+    let there = std::thread::spawn(|| pinned().canonicalize("/proc/thread-self/cwd").unwrap())
+        .join()
+        .unwrap();
+
+    assert_ne!(here, there);
+    assert_eq!(here, this_thread().join("cwd"));
+}
+
+#[test]
+fn other_boundaries_are_untouched() {
+    assert_eq!(
+        pinned().canonicalize("/proc/self/root/etc").unwrap(),
+        Path::new("/proc/self/root/etc")
+    );
+}
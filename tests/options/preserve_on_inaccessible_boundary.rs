@@ -0,0 +1,70 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::preserve_on_inaccessible_boundary`, against `/proc/1/root`.
+//!
+//! Only an unprivileged caller is denied PID 1's root; run as root, the denial cases
+//! return early. The resolver's unit tests simulate the denial instead.
+
+use proc_canonicalize::{canonicalize, CanonicalizeOptions, ProcessGone};
+use std::io;
+use std::path::Path;
+
+fn deferred() -> CanonicalizeOptions {
+    CanonicalizeOptions::new().preserve_on_inaccessible_boundary(true)
+}
+
+/// Whether this process is denied `/proc/1/root`.
+fn pid_one_root_is_denied() -> bool {
+    std::fs::metadata("/proc/1/root")
+        .is_err_and(|err| err.kind() == io::ErrorKind::PermissionDenied)
+}
+
+#[test]
+fn denied_root_of_pid_one_fails_by_default() {
+    if !pid_one_root_is_denied() {
+        return;
+    }
+
+    let err = canonicalize("/proc/1/root/etc").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn denied_root_of_pid_one_is_returned_folded() {
+    if !pid_one_root_is_denied() {
+        return;
+    }
+
+    assert_eq!(
+        deferred().canonicalize("/proc/1/root").unwrap(),
+        Path::new("/proc/1/root")
+    );
+    assert_eq!(
+        deferred()
+            .canonicalize("/proc/1/root/etc/./../srv/")
+            .unwrap(),
+        Path::new("/proc/1/root/srv")
+    );
+}
+
+#[test]
+fn accessible_boundary_resolves_as_usual() {
+    assert_eq!(
+        deferred().canonicalize("/proc/self/root/etc/..").unwrap(),
+        Path::new("/proc/self/root")
+    );
+    assert!(deferred()
+        .canonicalize("/proc/self/root/no/such/file")
+        .is_err());
+}
+
+#[test]
+fn missing_process_still_fails() {
+    let err = deferred()
+        .canonicalize("/proc/4294967294/root/etc")
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(err.get_ref().is_some_and(|inner| inner.is::<ProcessGone>()));
+}
@@ -0,0 +1,173 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::preserve_unknown_proc_links`: symlinks directly in
+//! `/proc/PID` other than `root`, `cwd` and `ns/TYPE` are kept as written.
+
+use proc_canonicalize::{canonicalize, CanonicalizeOptions};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+fn generic() -> CanonicalizeOptions {
+    CanonicalizeOptions::new().preserve_unknown_proc_links(true)
+}
+
+// ==========================================================================
+// OFF BY DEFAULT
+// ==========================================================================
+
+#[test]
+fn exe_is_followed_by_default() {
+    assert_eq!(
+        canonicalize("/proc/self/exe").unwrap(),
+        std::fs::canonicalize("/proc/self/exe").unwrap()
+    );
+}
+
+// ==========================================================================
+// LINKS ARE PRESERVED, OTHER ENTRIES ARE NOT
+// ==========================================================================
+
+#[test]
+fn exe_is_preserved() {
+    assert_eq!(
+        generic().canonicalize("/proc/self/exe").unwrap(),
+        Path::new("/proc/self/exe")
+    );
+}
+
+#[test]
+fn exe_of_a_numeric_pid_and_a_task_is_preserved() {
+    let pid = std::process::id();
+    for path in [
+        format!("/proc/{pid}/exe"),
+        format!("/proc/{pid}/task/{pid}/exe"),
+        "/proc/thread-self/exe".to_string(),
+    ] {
+        assert_eq!(generic().canonicalize(&path).unwrap(), Path::new(&path));
+    }
+}
+
+#[test]
+fn status_is_a_regular_file_and_not_preserved() {
+    let pid = std::process::id();
+
+    assert_eq!(
+        generic().canonicalize("/proc/self/status").unwrap(),
+        PathBuf::from(format!("/proc/{pid}/status"))
+    );
+}
+
+#[test]
+fn directories_in_the_process_directory_are_not_preserved() {
+    let pid = std::process::id();
+
+    assert_eq!(
+        generic().canonicalize("/proc/self/fd").unwrap(),
+        PathBuf::from(format!("/proc/{pid}/fd"))
+    );
+}
+
+#[test]
+fn known_boundaries_behave_as_without_the_option() {
+    for path in [
+        "/proc/self/root",
+        "/proc/self/root/etc",
+        "/proc/self/ns/net",
+    ] {
+        assert_eq!(
+            generic().canonicalize(path).unwrap(),
+            canonicalize(path).unwrap(),
+            "{path}"
+        );
+    }
+}
+
+#[test]
+fn separators_are_tidied_like_a_boundary() {
+    assert_eq!(
+        generic().canonicalize("//proc/./self//exe").unwrap(),
+        Path::new("/proc/self/exe")
+    );
+}
+
+// ==========================================================================
+// NOTHING MAY FOLLOW THE LINK
+// ==========================================================================
+
+#[test]
+fn trailing_slash_is_resolved_as_usual() {
+    let err = generic().canonicalize("/proc/self/exe/").unwrap_err();
+
+    assert_eq!(
+        err.raw_os_error(),
+        std::fs::canonicalize("/proc/self/exe/")
+            .unwrap_err()
+            .raw_os_error()
+    );
+}
+
+#[test]
+fn links_one_directory_lower_are_resolved_as_usual() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let fd = std::os::unix::io::AsRawFd::as_raw_fd(file.as_file());
+    let path = format!("/proc/self/fd/{fd}");
+
+    assert_eq!(
+        generic().canonicalize(path).unwrap(),
+        std::fs::canonicalize(file.path()).unwrap()
+    );
+}
+
+#[test]
+fn a_symlink_to_the_link_is_followed() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("/proc/self/exe", dir.path().join("exe")).unwrap();
+
+    assert_eq!(
+        generic().canonicalize(dir.path().join("exe")).unwrap(),
+        std::fs::canonicalize("/proc/self/exe").unwrap()
+    );
+}
+
+// ==========================================================================
+// THE BOUNDARY OPTIONS APPLY
+// ==========================================================================
+
+#[test]
+fn pids_not_allowed_are_followed() {
+    let pid = std::process::id();
+    let path = format!("/proc/{pid}/exe");
+
+    assert_eq!(
+        generic().allowed_pids(&[]).canonicalize(&path).unwrap(),
+        std::fs::canonicalize(&path).unwrap()
+    );
+    assert_eq!(
+        generic().allowed_pids(&[pid]).canonicalize(&path).unwrap(),
+        Path::new(&path)
+    );
+}
+
+#[test]
+fn pid_zero_is_rejected_when_asked() {
+    let err = generic()
+        .reject_pid_zero(true)
+        .canonicalize("/proc/0/exe")
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn expand_self_names_the_pid() {
+    let pid = std::process::id();
+
+    assert_eq!(
+        generic()
+            .expand_self(true)
+            .canonicalize("/proc/self/exe")
+            .unwrap(),
+        PathBuf::from(format!("/proc/{pid}/exe"))
+    );
+}
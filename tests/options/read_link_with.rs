@@ -0,0 +1,98 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::read_link_with`: symlink reads routed through a caller's
+//! function, as a sandbox broker would.
+
+use proc_canonicalize::{CanonicalizeOptions, EscapeBehavior};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn indirect_link_is_read_through_the_hook() {
+    let temp = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", temp.path().join("container")).unwrap();
+    let reads = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&reads);
+
+    let options = CanonicalizeOptions::new().read_link_with(move |path: &Path| {
+        counter.fetch_add(1, Ordering::Relaxed);
+        std::fs::read_link(path)
+    });
+    let resolved = options
+        .canonicalize(temp.path().join("container/etc"))
+        .unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+    assert!(reads.load(Ordering::Relaxed) >= 1);
+}
+
+#[test]
+fn hook_answer_decides_where_the_walk_goes() {
+    let temp = tempfile::tempdir().unwrap();
+    symlink("/nonexistent", temp.path().join("brokered")).unwrap();
+
+    // The broker knows better than the local filesystem where this link points.
+    let options = CanonicalizeOptions::new()
+        .read_link_with(|_: &Path| -> io::Result<PathBuf> { Ok(PathBuf::from("/proc/self/root")) });
+
+    assert_eq!(
+        options.canonicalize(temp.path().join("brokered")).unwrap(),
+        Path::new("/proc/self/root")
+    );
+}
+
+#[test]
+fn hook_error_is_returned() {
+    let temp = tempfile::tempdir().unwrap();
+    symlink("target", temp.path().join("link")).unwrap();
+
+    let options = CanonicalizeOptions::new().read_link_with(|_: &Path| -> io::Result<PathBuf> {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "broker refused",
+        ))
+    });
+    let err = options.canonicalize(temp.path().join("link")).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn clamped_walk_reads_links_through_the_hook() {
+    let reads = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&reads);
+    let temp = tempfile::tempdir().unwrap();
+    std::fs::create_dir(temp.path().join("etc")).unwrap();
+    symlink("/etc", temp.path().join("abs")).unwrap();
+
+    let options = CanonicalizeOptions::new()
+        .stand_in_root(temp.path())
+        .escape_behavior(EscapeBehavior::ClampToRoot)
+        .read_link_with(move |path: &Path| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            std::fs::read_link(path)
+        });
+    let before = reads.load(Ordering::Relaxed);
+
+    assert_eq!(
+        options.canonicalize("/proc/4242/root/abs").unwrap(),
+        Path::new("/proc/4242/root/etc")
+    );
+    assert!(reads.load(Ordering::Relaxed) > before);
+}
+
+#[test]
+fn options_with_a_hook_are_clone_and_debug() {
+    let options = CanonicalizeOptions::new().read_link_with(|path: &Path| std::fs::read_link(path));
+
+    let cloned = options.clone();
+    let debug = std::thread::spawn(move || format!("{cloned:?}"))
+        .join()
+        .unwrap();
+
+    assert!(debug.contains("ReadLinkHook"));
+    assert_eq!(debug, format!("{options:?}"));
+}
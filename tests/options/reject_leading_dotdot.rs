@@ -0,0 +1,81 @@
+//! `CanonicalizeOptions::reject_leading_dotdot`: relative paths whose `..` climbs
+//! above their start refused before the current directory is consulted.
+
+use proc_canonicalize::CanonicalizeOptions;
+use std::io;
+
+fn strict() -> CanonicalizeOptions {
+    CanonicalizeOptions::new().reject_leading_dotdot(true)
+}
+
+// ==========================================================================
+// REJECTED
+// ==========================================================================
+
+#[test]
+fn leading_dotdot_is_invalid_input() {
+    for path in ["..", "../", "../../proc/self/root", "./../etc", "././../x"] {
+        let err = strict().canonicalize(path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{path}");
+    }
+}
+
+#[test]
+fn dotdot_climbing_above_the_start_later_is_invalid_input() {
+    for path in [
+        "src/../../etc",
+        "src/./../..",
+        "a/b/../../../proc/self/root",
+    ] {
+        let err = strict().canonicalize(path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{path}");
+    }
+}
+
+#[test]
+fn rejection_does_not_depend_on_the_target_existing() {
+    let err = strict()
+        .canonicalize("../no/such/path/anywhere")
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+// ==========================================================================
+// ACCEPTED
+// ==========================================================================
+
+#[test]
+fn default_resolves_leading_dotdot_against_cwd() {
+    let expected = std::fs::canonicalize("..").unwrap();
+
+    assert_eq!(
+        CanonicalizeOptions::new().canonicalize("..").unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn dotdot_staying_below_the_start_is_allowed() {
+    let expected = std::fs::canonicalize("src/..").unwrap();
+
+    assert_eq!(strict().canonicalize("src/..").unwrap(), expected);
+    assert_eq!(strict().canonicalize("./src/..").unwrap(), expected);
+}
+
+#[test]
+fn absolute_dotdot_paths_are_allowed() {
+    assert_eq!(
+        strict().canonicalize("/../tmp").unwrap(),
+        std::fs::canonicalize("/tmp").unwrap()
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn absolute_boundary_paths_are_unaffected() {
+    assert_eq!(
+        strict().canonicalize("/proc/self/root/../etc").unwrap(),
+        std::path::Path::new("/proc/self/root/etc")
+    );
+}
@@ -0,0 +1,76 @@
+#![cfg(target_os = "linux")]
+
+//! `reject_pid_zero`: PID 0 boundaries as invalid input instead of a missing process.
+
+use proc_canonicalize::{canonicalize, parse_boundary, CanonicalizeOptions, ProcessGone};
+use std::io;
+use std::os::unix::fs::symlink;
+
+fn strict() -> CanonicalizeOptions {
+    CanonicalizeOptions::new().reject_pid_zero(true)
+}
+
+// ==========================================================================
+// DEFAULT
+// ==========================================================================
+
+#[test]
+fn pid_zero_parses_but_is_a_missing_process() {
+    assert!(parse_boundary("/proc/0/root").is_some());
+
+    for path in ["/proc/0/root", "/proc/0/cwd/x", "/proc/0/ns/net"] {
+        let err = canonicalize(path).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::NotFound, "{path}");
+        let gone = err.get_ref().and_then(|e| e.downcast_ref::<ProcessGone>());
+        assert_eq!(gone.map(ProcessGone::pid), Some(0), "{path}");
+    }
+}
+
+// ==========================================================================
+// REJECTED
+// ==========================================================================
+
+#[test]
+fn pid_zero_boundaries_are_invalid_input() {
+    for path in [
+        "/proc/0/root",
+        "/proc/0/cwd/x",
+        "/proc/00/root/etc",
+        "/proc/0/task/1/root",
+        "/proc/0/ns/net",
+    ] {
+        let err = strict().canonicalize(path).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{path}");
+    }
+}
+
+#[test]
+fn pid_zero_reached_through_a_symlink_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("/proc/0/root", dir.path().join("container")).unwrap();
+
+    let err = strict()
+        .canonicalize(dir.path().join("container/etc"))
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn other_pids_are_unaffected() {
+    let me = format!("/proc/{}/root/etc", std::process::id());
+
+    for path in [
+        "/proc/self/root/etc",
+        me.as_str(),
+        "/proc/1/../self/root/etc",
+    ] {
+        assert_eq!(
+            strict().canonicalize(path).unwrap(),
+            canonicalize(path).unwrap(),
+            "{path}"
+        );
+    }
+}
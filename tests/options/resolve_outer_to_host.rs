@@ -0,0 +1,89 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::resolve_outer_to_host`: the outermost boundary becomes its
+//! host path, any boundary reached after it is preserved.
+
+use proc_canonicalize::CanonicalizeOptions;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+fn outer_to_host() -> CanonicalizeOptions {
+    CanonicalizeOptions::new().resolve_outer_to_host(true)
+}
+
+#[test]
+fn root_subpath_resolves_to_host_path() {
+    let resolved = outer_to_host().canonicalize("/proc/self/root/etc").unwrap();
+
+    assert_eq!(resolved, Path::new("/etc"));
+}
+
+#[test]
+fn bare_root_boundary_resolves_to_host_root() {
+    let resolved = outer_to_host().canonicalize("/proc/self/root").unwrap();
+
+    assert_eq!(resolved, Path::new("/"));
+}
+
+#[test]
+fn cwd_subpath_resolves_to_host_cwd() {
+    let subdir = tempfile::tempdir_in(".").unwrap();
+    let subdir_name = subdir.path().file_name().unwrap().to_str().unwrap();
+
+    let resolved = outer_to_host()
+        .canonicalize(format!("/proc/self/cwd/{subdir_name}"))
+        .unwrap();
+
+    assert_eq!(resolved, std::fs::canonicalize(subdir.path()).unwrap());
+}
+
+#[test]
+fn deeper_boundary_in_remainder_is_preserved() {
+    let resolved = outer_to_host()
+        .canonicalize("/proc/self/root/proc/self/cwd")
+        .unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/cwd"));
+}
+
+#[test]
+fn deeper_boundary_reached_through_symlink_is_preserved() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", dir.path().join("inner")).unwrap();
+    let through_outer = format!("/proc/self/root{}/inner/etc", dir.path().display());
+
+    let resolved = outer_to_host().canonicalize(through_outer).unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+}
+
+#[test]
+fn outer_boundary_reached_through_symlink_resolves_to_host() {
+    let dir = tempfile::tempdir().unwrap();
+    let container_link = dir.path().join("container");
+    symlink("/proc/self/root", &container_link).unwrap();
+
+    let resolved = outer_to_host()
+        .canonicalize(container_link.join("etc"))
+        .unwrap();
+
+    assert_eq!(resolved, Path::new("/etc"));
+}
+
+#[test]
+fn default_options_still_preserve_outer_boundary() {
+    let resolved = CanonicalizeOptions::new()
+        .canonicalize("/proc/self/root/etc")
+        .unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+}
+
+#[test]
+fn normal_paths_unaffected() {
+    let temp = std::env::temp_dir();
+
+    let resolved = outer_to_host().canonicalize(&temp).unwrap();
+
+    assert_eq!(resolved, std::fs::canonicalize(&temp).unwrap());
+}
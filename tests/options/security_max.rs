@@ -0,0 +1,131 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::security_max` and `sanitize_errors`.
+
+use proc_canonicalize::{canonicalize, CanonicalizeOptions, Inaccessible};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+fn is_inaccessible(err: &io::Error) -> bool {
+    err.kind() == io::ErrorKind::Other
+        && err
+            .get_ref()
+            .is_some_and(|inner| inner.is::<Inaccessible>())
+}
+
+// ==========================================================================
+// WHAT THE PRESET ENABLES
+// ==========================================================================
+
+#[test]
+fn escapes_fail() {
+    let err = CanonicalizeOptions::security_max()
+        .canonicalize("/proc/self/cwd/..")
+        .unwrap_err();
+
+    assert!(!is_inaccessible(&err), "{err:?}");
+}
+
+#[test]
+fn long_symlink_chains_fail() {
+    let dir = tempfile::tempdir().unwrap();
+    for hop in 0..9 {
+        symlink(
+            format!("hop{}", hop + 1),
+            dir.path().join(format!("hop{hop}")),
+        )
+        .unwrap();
+    }
+    std::fs::create_dir(dir.path().join("hop9")).unwrap();
+
+    let err = CanonicalizeOptions::security_max()
+        .canonicalize(dir.path().join("hop0"))
+        .unwrap_err();
+
+    assert_eq!(err.raw_os_error(), Some(40)); // ELOOP
+    assert!(canonicalize(dir.path().join("hop0")).is_ok());
+}
+
+#[test]
+fn leading_dotdot_and_pid_zero_are_invalid_input() {
+    for path in ["../etc", "/proc/0/root"] {
+        let err = CanonicalizeOptions::security_max()
+            .canonicalize(path)
+            .unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{path}");
+    }
+}
+
+#[test]
+fn only_self_boundaries_are_trusted() {
+    let me = format!("/proc/{}/root/etc", std::process::id());
+    let strict = CanonicalizeOptions::security_max();
+
+    assert_eq!(
+        strict.canonicalize("/proc/self/root/etc").unwrap(),
+        Path::new("/proc/self/root/etc")
+    );
+    assert_eq!(
+        strict.canonicalize(me).unwrap(),
+        std::fs::canonicalize("/etc").unwrap()
+    );
+}
+
+#[test]
+fn errors_are_sanitized() {
+    let err = CanonicalizeOptions::security_max()
+        .canonicalize("/proc/self/root/no/such/file")
+        .unwrap_err();
+
+    assert!(is_inaccessible(&err), "{err:?}");
+}
+
+// ==========================================================================
+// ADJUSTING THE PRESET
+// ==========================================================================
+
+#[test]
+fn allowed_pids_can_be_added() {
+    let me = format!("/proc/{}/root/etc", std::process::id());
+
+    let strict = CanonicalizeOptions::security_max().allowed_pids(&[std::process::id()]);
+
+    assert_eq!(strict.canonicalize(&me).unwrap(), Path::new(&me));
+}
+
+#[test]
+fn each_setting_can_be_relaxed() {
+    let relaxed = CanonicalizeOptions::security_max()
+        .sanitize_errors(false)
+        .reject_leading_dotdot(false);
+
+    let missing = relaxed
+        .canonicalize("/proc/self/root/no/such/file")
+        .unwrap_err();
+
+    assert_eq!(missing.kind(), io::ErrorKind::NotFound);
+    assert!(relaxed.canonicalize("../..").is_ok());
+}
+
+// ==========================================================================
+// SANITIZE_ERRORS ON ITS OWN
+// ==========================================================================
+
+#[test]
+fn sanitize_errors_matches_canonicalize_sanitized() {
+    let sanitized = CanonicalizeOptions::new().sanitize_errors(true);
+
+    for path in ["/proc/self/root/no/such", "/proc/self/root/etc/hostname/x"] {
+        let err = sanitized.canonicalize(path).unwrap_err();
+        let expected = proc_canonicalize::canonicalize_sanitized(path).unwrap_err();
+
+        assert_eq!(err.kind(), expected.kind(), "{path}");
+        assert_eq!(err.to_string(), expected.to_string(), "{path}");
+    }
+    assert_eq!(
+        sanitized.canonicalize("/proc/self/root/etc").unwrap(),
+        Path::new("/proc/self/root/etc")
+    );
+}
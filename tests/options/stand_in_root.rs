@@ -0,0 +1,92 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::stand_in_root`: resolve container paths offline against a
+//! directory, keeping the `/proc/<pid>/root` prefix the online code would produce.
+
+use proc_canonicalize::{CanonicalizeOptions, EscapeBehavior};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+/// A PID that is never assigned (above the kernel's `pid_max` ceiling of 2^22).
+const NO_SUCH_PID: u32 = 4_194_305;
+
+fn offline(image: &Path) -> CanonicalizeOptions {
+    CanonicalizeOptions::new().stand_in_root(image)
+}
+
+#[test]
+fn subpath_resolves_inside_image_without_process() {
+    let image = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(image.path().join("etc/nginx")).unwrap();
+    symlink("nginx", image.path().join("etc/web")).unwrap();
+
+    let resolved = offline(image.path())
+        .canonicalize(format!("/proc/{NO_SUCH_PID}/root/etc/web"))
+        .unwrap();
+
+    assert_eq!(
+        resolved,
+        Path::new(&format!("/proc/{NO_SUCH_PID}/root/etc/nginx"))
+    );
+}
+
+#[test]
+fn bare_boundary_returns_chosen_prefix() {
+    let image = tempfile::tempdir().unwrap();
+
+    let resolved = offline(image.path())
+        .canonicalize("/proc/1234/root")
+        .unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/1234/root"));
+}
+
+#[test]
+fn absolute_image_symlink_clamps_with_clamp_to_root() {
+    let image = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(image.path().join("usr/lib")).unwrap();
+    symlink("/usr/lib", image.path().join("lib")).unwrap();
+
+    let resolved = offline(image.path())
+        .escape_behavior(EscapeBehavior::ClampToRoot)
+        .canonicalize("/proc/1234/root/lib")
+        .unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/1234/root/usr/lib"));
+}
+
+#[test]
+fn absolute_image_symlink_returns_host_path_by_default() {
+    let image = tempfile::tempdir().unwrap();
+    symlink("/tmp", image.path().join("scratch")).unwrap();
+
+    let resolved = offline(image.path())
+        .canonicalize("/proc/1234/root/scratch")
+        .unwrap();
+
+    assert_eq!(resolved, std::fs::canonicalize("/tmp").unwrap());
+}
+
+#[test]
+fn missing_image_directory_reports_not_found() {
+    let parent = tempfile::tempdir().unwrap();
+
+    let err = offline(&parent.path().join("unpacked"))
+        .canonicalize("/proc/1234/root/etc")
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn paths_without_boundary_are_unaffected() {
+    let image = tempfile::tempdir().unwrap();
+    let elsewhere = tempfile::tempdir().unwrap();
+
+    let resolved = offline(image.path())
+        .canonicalize(elsewhere.path())
+        .unwrap();
+
+    assert_eq!(resolved, std::fs::canonicalize(elsewhere.path()).unwrap());
+}
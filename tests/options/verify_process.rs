@@ -0,0 +1,142 @@
+#![cfg(target_os = "linux")]
+
+//! `CanonicalizeOptions::verify_process`: boundaries under a captured PID fail once the
+//! PID belongs to a different process.
+
+use proc_canonicalize::{CanonicalizeOptions, ProcessIdentity, ProcessReused};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+use std::process::Command;
+
+fn verifying(identity: ProcessIdentity) -> CanonicalizeOptions {
+    CanonicalizeOptions::new().verify_process(identity)
+}
+
+fn stale_identity_of_self() -> ProcessIdentity {
+    let current = ProcessIdentity::capture(std::process::id()).unwrap();
+    ProcessIdentity::from_parts(current.pid(), current.start_time() + 1)
+}
+
+fn process_reused_payload(err: &io::Error) -> Option<&ProcessReused> {
+    err.get_ref()?.downcast_ref::<ProcessReused>()
+}
+
+// ==========================================================================
+// SAME PROCESS
+// ==========================================================================
+
+#[test]
+fn matching_identity_resolves_normally() {
+    let pid = std::process::id();
+    let identity = ProcessIdentity::capture(pid).unwrap();
+
+    let resolved = verifying(identity)
+        .canonicalize(format!("/proc/{pid}/root/etc"))
+        .unwrap();
+
+    assert_eq!(resolved, Path::new(&format!("/proc/{pid}/root/etc")));
+}
+
+#[test]
+fn capture_is_stable_for_a_live_process() {
+    let pid = std::process::id();
+
+    assert_eq!(
+        ProcessIdentity::capture(pid).unwrap(),
+        ProcessIdentity::capture(pid).unwrap()
+    );
+}
+
+// ==========================================================================
+// REUSED OR EXITED PROCESS
+// ==========================================================================
+
+#[test]
+fn mismatched_start_time_reports_process_reused() {
+    let stale = stale_identity_of_self();
+
+    let err = verifying(stale)
+        .canonicalize(format!("/proc/{}/root/etc", stale.pid()))
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    let reused = process_reused_payload(&err).unwrap();
+    assert_eq!(reused.expected(), stale);
+    assert_eq!(reused.found_start_time() + 1, stale.start_time());
+}
+
+#[test]
+fn mismatch_is_caught_on_bare_boundary_and_task_form() {
+    let stale = stale_identity_of_self();
+    let pid = stale.pid();
+
+    for path in [
+        format!("/proc/{pid}/cwd"),
+        format!("/proc/{pid}/task/{pid}/root"),
+    ] {
+        let err = verifying(stale).canonicalize(&path).unwrap_err();
+        assert!(process_reused_payload(&err).is_some(), "{path}");
+    }
+}
+
+#[test]
+fn mismatch_is_caught_through_indirect_symlink() {
+    let stale = stale_identity_of_self();
+    let dir = tempfile::tempdir().unwrap();
+    let container_link = dir.path().join("container");
+    symlink(format!("/proc/{}/root", stale.pid()), &container_link).unwrap();
+
+    let err = verifying(stale)
+        .canonicalize(container_link.join("etc"))
+        .unwrap_err();
+
+    assert!(process_reused_payload(&err).is_some());
+}
+
+#[test]
+fn exited_process_reports_not_found() {
+    let mut child = Command::new("true").spawn().unwrap();
+    let identity = ProcessIdentity::capture(child.id()).unwrap();
+    child.wait().unwrap();
+
+    let err = verifying(identity)
+        .canonicalize(format!("/proc/{}/root", identity.pid()))
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+// ==========================================================================
+// UNGUARDED BOUNDARIES
+// ==========================================================================
+
+#[test]
+fn self_boundary_is_not_checked() {
+    let resolved = verifying(stale_identity_of_self())
+        .canonicalize("/proc/self/root/etc")
+        .unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+}
+
+#[test]
+fn boundary_under_other_pid_is_not_checked() {
+    let unrelated = ProcessIdentity::from_parts(u32::MAX, 0);
+
+    let resolved = verifying(unrelated)
+        .canonicalize(format!("/proc/{}/root/etc", std::process::id()))
+        .unwrap();
+
+    assert_eq!(
+        resolved,
+        Path::new(&format!("/proc/{}/root/etc", std::process::id()))
+    );
+}
+
+#[test]
+fn capture_of_missing_pid_fails() {
+    let err = ProcessIdentity::capture(u32::MAX).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
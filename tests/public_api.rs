@@ -1,4 +1,4 @@
-#![cfg(target_os = "linux")]
+#![cfg(all(target_os = "linux", feature = "fs"))]
 
 //! Integration tests exercising the public `canonicalize` API.
 //!
@@ -117,9 +117,13 @@ fn nonexistent_pid_returns_not_found() {
 
 #[test]
 fn empty_path_returns_error() {
-    let result = canonicalize("");
+    let err = canonicalize("").unwrap_err();
 
-    assert!(result.is_err());
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(
+        err.raw_os_error(),
+        std::fs::canonicalize("").unwrap_err().raw_os_error()
+    );
 }
 
 // ==========================================================================
@@ -149,6 +153,20 @@ fn dot_is_normalized_out() {
     }
 }
 
+#[test]
+fn dot_only_remainder_returns_bare_root_boundary() {
+    let canonical = canonicalize("/proc/self/root/.").unwrap();
+
+    assert_eq!(canonical, Path::new("/proc/self/root"));
+}
+
+#[test]
+fn dot_only_remainder_returns_bare_cwd_boundary() {
+    let canonical = canonicalize("/proc/self/cwd/./.").unwrap();
+
+    assert_eq!(canonical, Path::new("/proc/self/cwd"));
+}
+
 #[test]
 fn deep_path_preserves_namespace() {
     let path = "/proc/self/root/usr/share/doc";
@@ -379,11 +397,11 @@ mod security {
 
     #[test]
     fn excessive_dotdot_cannot_escape_root_namespace() {
-        let path = "/proc/self/root/../../../../../../../etc/passwd";
+        let path = "/proc/self/root/../../../../../../../etc";
 
-        if let Ok(result) = canonicalize(path) {
-            assert!(result.starts_with("/proc/self/root"));
-        }
+        let result = canonicalize(path).unwrap();
+
+        assert_eq!(result, PathBuf::from("/proc/self/root/etc"));
     }
 
     #[test]
@@ -418,6 +436,28 @@ mod security {
         }
     }
 
+    #[test]
+    fn extra_separators_before_proc_match_plain_spelling() {
+        // `..` right after the boundary is only kept under the prefix when the
+        // boundary is detected up front, so these must not fall through to the scanner.
+        for (odd, plain) in [
+            ("//proc/self/root/etc/..", "/proc/self/root/etc/.."),
+            ("//proc/self/root/..", "/proc/self/root/.."),
+            ("/./proc/self/root/..", "/proc/self/root/.."),
+            ("///proc/self/root/../etc", "/proc/self/root/../etc"),
+        ] {
+            assert_eq!(
+                canonicalize(odd).unwrap(),
+                canonicalize(plain).unwrap(),
+                "{odd}"
+            );
+        }
+        assert_eq!(
+            canonicalize("//proc/self/root/etc/..").unwrap(),
+            Path::new("/proc/self/root")
+        );
+    }
+
     #[test]
     fn relative_proc_path_not_magic() {
         // "proc/self/root" (no leading /) is relative, not magic
@@ -429,11 +469,34 @@ mod security {
         let temp = tempfile::tempdir().unwrap();
         let link = temp.path().join("link");
 
-        symlink("/proc/self/root/etc", &link).unwrap();
+        symlink("/proc/self/root/usr", &link).unwrap();
+        // The host's own spelling of /usr/share, in case part of it is a symlink.
+        let host = std::fs::canonicalize("/usr/share").unwrap();
+        let expected = Path::new("/proc/self/root").join(host.strip_prefix("/").unwrap());
 
-        if let Ok(result) = canonicalize(&link) {
-            assert!(result.starts_with("/proc/self/root"));
-        }
+        // Both the target's subpath (`usr`) and the remainder (`share`) are kept.
+        assert_eq!(canonicalize(link.join("share")).unwrap(), expected);
+        assert_eq!(canonicalize(link.join("share/.")).unwrap(), expected);
+        assert_eq!(canonicalize(link.join("lib/../share")).unwrap(), expected);
+    }
+
+    #[test]
+    fn symlink_to_deep_proc_path_in_a_chain_composes_exactly() {
+        let temp = tempfile::tempdir().unwrap();
+        let host = std::fs::canonicalize(temp.path()).unwrap();
+        std::fs::create_dir_all(host.join("a/b/c")).unwrap();
+        let inside = Path::new("/proc/self/root").join(host.strip_prefix("/").unwrap());
+        symlink(inside.join("a"), host.join("deep")).unwrap();
+        symlink("deep/b", host.join("chain")).unwrap();
+
+        assert_eq!(
+            canonicalize(host.join("chain/c")).unwrap(),
+            inside.join("a/b/c")
+        );
+        assert_eq!(
+            canonicalize(host.join("deep/b/../b/c/")).unwrap(),
+            inside.join("a/b/c")
+        );
     }
 
     #[test]
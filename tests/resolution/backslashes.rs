@@ -0,0 +1,81 @@
+#![cfg(target_os = "linux")]
+
+//! Backslashes are ordinary filename bytes on Linux, never separators.
+//!
+//! `/proc\self\root` is one oddly named entry under `/`, not a boundary. Code that
+//! rewrites `\` to `/` before calling in (e.g. for Windows-style config paths) changes
+//! what the path means; these tests pin that the crate itself never does.
+
+use crate::common::root::through_root;
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::{canonicalize, canonicalize_lexical, parse_boundary};
+use std::io;
+use std::path::Path;
+
+// ==========================================================================
+// NEVER A BOUNDARY
+// ==========================================================================
+
+#[test]
+fn backslash_spellings_are_not_boundaries() {
+    for path in [
+        r"/proc\self\root",
+        r"/proc/self\root",
+        r"/proc\self/root",
+        r"/proc/self/root\etc",
+        r"\proc\self\root",
+        r"/proc/1234\task/5678/root",
+    ] {
+        assert!(parse_boundary(path).is_none(), "{path}");
+    }
+}
+
+#[test]
+fn backslash_spelling_resolves_as_a_literal_name_under_root() {
+    for path in [r"/proc\self\root", r"/proc/self\root", r"/proc\self/root"] {
+        let err = canonicalize(path).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::NotFound, "{path}");
+    }
+}
+
+#[test]
+fn backslash_in_remainder_is_not_split() {
+    let err = canonicalize(r"/proc/self/root/etc\hostname").unwrap_err();
+
+    // `etc\hostname` is looked up as one name in `/`, which does not exist; as two
+    // names it would have found /etc/hostname.
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+// ==========================================================================
+// KEPT BYTE FOR BYTE
+// ==========================================================================
+
+#[test]
+fn names_containing_backslashes_survive_resolution() {
+    let (_dir, host) = canonical_tempdir();
+    std::fs::create_dir_all(host.join(r"self\root").join(r"a\b")).unwrap();
+    let inside = through_root(&host);
+
+    let resolved = canonicalize(inside.join(r"self\root/./a\b")).unwrap();
+
+    assert_eq!(resolved, inside.join(r"self\root").join(r"a\b"));
+}
+
+#[test]
+fn directory_named_like_a_boundary_is_resolved_as_a_directory() {
+    let (_dir, host) = canonical_tempdir();
+    let odd = host.join(r"proc\self\root");
+    std::fs::create_dir(&odd).unwrap();
+
+    assert_eq!(canonicalize(&odd).unwrap(), odd);
+    assert_eq!(canonicalize(odd.join("..")).unwrap(), host);
+}
+
+#[test]
+fn lexical_canonicalization_keeps_backslashes() {
+    let lexical = canonicalize_lexical(r"/proc\self\root/..\etc").unwrap();
+
+    assert_eq!(lexical, Path::new(r"/proc\self\root/..\etc"));
+}
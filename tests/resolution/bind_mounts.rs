@@ -0,0 +1,121 @@
+#![cfg(target_os = "linux")]
+
+//! Bind-mounted subtrees under a boundary: the prefix is kept and the result names
+//! the mount point, never the bind source.
+//!
+//! Creating a bind mount needs privileges. Each test that mounts returns early, without
+//! asserting anything, when `mount --bind` is refused.
+
+use crate::common::root::through_root;
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::{canonicalize, same_file, CanonicalizeOptions};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// A bind mount of `source` at `target`, unmounted on drop.
+struct BindMount {
+    target: PathBuf,
+}
+
+impl BindMount {
+    fn try_new(source: &Path, target: &Path) -> Option<Self> {
+        let mounted = Command::new("mount")
+            .arg("--bind")
+            .arg(source)
+            .arg(target)
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+        if !mounted {
+            eprintln!("skipping: cannot bind-mount here");
+            return None;
+        }
+        Some(BindMount {
+            target: target.to_path_buf(),
+        })
+    }
+}
+
+impl Drop for BindMount {
+    fn drop(&mut self) {
+        let _ = Command::new("umount").arg(&self.target).status();
+    }
+}
+
+/// `base/source/inner/file` and an empty `base/target` to mount it on.
+fn layout() -> (tempfile::TempDir, PathBuf, PathBuf) {
+    let (dir, base) = canonical_tempdir();
+    let source = base.join("source");
+    let target = base.join("target");
+    std::fs::create_dir_all(source.join("inner")).unwrap();
+    std::fs::write(source.join("inner/file"), b"x").unwrap();
+    std::fs::create_dir(&target).unwrap();
+    (dir, source, target)
+}
+
+// ==========================================================================
+// SUBTREE BIND-MOUNTED UNDER THE BOUNDARY
+// ==========================================================================
+
+#[test]
+fn path_through_the_mount_keeps_prefix_and_mount_point() {
+    let (_dir, source, target) = layout();
+    let Some(_mount) = BindMount::try_new(&source, &target) else {
+        return;
+    };
+    let input = through_root(&target).join("inner/./file");
+
+    let resolved = canonicalize(input).unwrap();
+
+    assert_eq!(resolved, through_root(&target).join("inner/file"));
+    assert!(!resolved.starts_with(through_root(&source)));
+}
+
+#[test]
+fn source_and_mount_point_keep_their_own_names() {
+    let (_dir, source, target) = layout();
+    let Some(_mount) = BindMount::try_new(&source, &target) else {
+        return;
+    };
+    let via_source = canonicalize(through_root(&source).join("inner/file")).unwrap();
+    let via_target = canonicalize(through_root(&target).join("inner/file")).unwrap();
+
+    assert_ne!(via_source, via_target);
+    assert!(same_file(&via_source, &via_target).unwrap());
+}
+
+#[test]
+fn dotdot_out_of_the_mount_returns_to_the_mount_point_parent() {
+    let (_dir, source, target) = layout();
+    let Some(_mount) = BindMount::try_new(&source, &target) else {
+        return;
+    };
+
+    assert_eq!(
+        canonicalize(through_root(&target).join("inner/../..")).unwrap(),
+        through_root(target.parent().unwrap())
+    );
+}
+
+// ==========================================================================
+// BOUNDARY THAT IS ITSELF A BIND MOUNT
+// ==========================================================================
+
+#[test]
+fn stand_in_root_on_a_mount_point_names_paths_inside_it() {
+    let (_dir, source, target) = layout();
+    let Some(_mount) = BindMount::try_new(&source, &target) else {
+        return;
+    };
+    // As for a container whose root directory is mounted from an image directory.
+    let options = CanonicalizeOptions::new().stand_in_root(&target);
+
+    assert_eq!(
+        options.canonicalize("/proc/4242/root/inner/file").unwrap(),
+        Path::new("/proc/4242/root/inner/file")
+    );
+    assert_eq!(
+        options.canonicalize("/proc/4242/root/inner/..").unwrap(),
+        Path::new("/proc/4242/root")
+    );
+}
@@ -0,0 +1,66 @@
+#![cfg(target_os = "linux")]
+
+//! Symlinks under a process's cwd that lead back into that cwd. The cwd is never `/`,
+//! so unlike most root cases the re-basing onto `/proc/PID/cwd` actually strips a
+//! host prefix, and must put the boundary back.
+
+use crate::common::cwd::CwdProcess;
+use proc_canonicalize::canonicalize;
+use std::os::unix::fs::symlink;
+
+#[test]
+fn absolute_host_target_inside_cwd_keeps_cwd_prefix() {
+    let process = CwdProcess::spawn();
+    let host_cwd = std::fs::canonicalize(process.dir()).unwrap();
+    std::fs::create_dir_all(host_cwd.join("data/current")).unwrap();
+    symlink(host_cwd.join("data/current"), host_cwd.join("latest")).unwrap();
+
+    let resolved = canonicalize(process.cwd_boundary().join("latest")).unwrap();
+
+    assert_eq!(resolved, process.cwd_boundary().join("data/current"));
+}
+
+#[test]
+fn target_through_same_cwd_boundary_keeps_cwd_prefix() {
+    let process = CwdProcess::spawn();
+    std::fs::create_dir(process.dir().join("other")).unwrap();
+    symlink(
+        process.cwd_boundary().join("other"),
+        process.dir().join("back"),
+    )
+    .unwrap();
+
+    let resolved = canonicalize(process.cwd_boundary().join("back/./")).unwrap();
+
+    assert_eq!(resolved, process.cwd_boundary().join("other"));
+}
+
+#[test]
+fn chain_of_reentering_links_keeps_cwd_prefix() {
+    let process = CwdProcess::spawn();
+    let host_cwd = std::fs::canonicalize(process.dir()).unwrap();
+    std::fs::create_dir_all(host_cwd.join("a/b")).unwrap();
+    std::fs::write(host_cwd.join("a/b/file"), b"").unwrap();
+    symlink(host_cwd.join("a"), host_cwd.join("first")).unwrap();
+    symlink(
+        process.cwd_boundary().join("first/b"),
+        host_cwd.join("second"),
+    )
+    .unwrap();
+
+    let resolved = canonicalize(process.cwd_boundary().join("second/file")).unwrap();
+
+    assert_eq!(resolved, process.cwd_boundary().join("a/b/file"));
+}
+
+#[test]
+fn absolute_target_outside_cwd_is_an_escape_to_host() {
+    let process = CwdProcess::spawn();
+    let host_cwd = std::fs::canonicalize(process.dir()).unwrap();
+    let host_parent = host_cwd.parent().unwrap().to_path_buf();
+    symlink(&host_parent, host_cwd.join("up")).unwrap();
+
+    let resolved = canonicalize(process.cwd_boundary().join("up")).unwrap();
+
+    assert_eq!(resolved, host_parent);
+}
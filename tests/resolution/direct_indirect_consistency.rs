@@ -0,0 +1,177 @@
+#![cfg(target_os = "linux")]
+
+//! A boundary reached directly (`/proc/self/root/...`) and one reached through a
+//! symlink into `/proc` go through different code: the boundary parser for the first,
+//! the indirect scanner for the second. Both must give byte-identical results.
+
+use crate::common::root::through_root;
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::{canonicalize, CanonicalizeOptions, EscapeBehavior};
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+/// Subpaths below the fixture directory, each tried both ways.
+const SUBPATHS: &[&str] = &[
+    "",
+    ".",
+    "real",
+    "real/",
+    "real/.",
+    "real/./",
+    "real//file",
+    "real/file",
+    "real/../real/file",
+    "relative",
+    "relative/file",
+    "absolute/file",
+    "real/missing",
+    "real/missing/deeper",
+    "real/file/below-a-file",
+    "..",
+    "real/../..",
+];
+
+/// A fixture directory on the host, as its canonical host path.
+///
+/// `real/file` is a regular file, `relative` links to `real`, and `absolute` links to
+/// `real` through `/proc/self/root`.
+fn fixture() -> (tempfile::TempDir, PathBuf) {
+    let (temp, host) = canonical_tempdir();
+    std::fs::create_dir(host.join("real")).unwrap();
+    std::fs::write(host.join("real/file"), b"").unwrap();
+    symlink("real", host.join("relative")).unwrap();
+    symlink(through_root(&host.join("real")), host.join("absolute")).unwrap();
+    (temp, host)
+}
+
+/// `base` with `sub` appended as text, so trailing slashes and `.` survive.
+fn join(base: &Path, sub: &str) -> PathBuf {
+    if sub.is_empty() {
+        return base.to_path_buf();
+    }
+    let mut joined = base.as_os_str().to_os_string();
+    joined.push("/");
+    joined.push(sub);
+    PathBuf::from(joined)
+}
+
+fn assert_same(direct: &Path, indirect: &Path, resolve: impl Fn(&Path) -> io::Result<PathBuf>) {
+    match (resolve(direct), resolve(indirect)) {
+        (Ok(a), Ok(b)) => assert_eq!(
+            a.as_os_str().as_bytes(),
+            b.as_os_str().as_bytes(),
+            "{direct:?} vs {indirect:?}"
+        ),
+        (Err(a), Err(b)) => {
+            assert_eq!(a.kind(), b.kind(), "{direct:?} vs {indirect:?}");
+            assert_eq!(
+                a.raw_os_error(),
+                b.raw_os_error(),
+                "{direct:?} vs {indirect:?}"
+            );
+        }
+        (a, b) => panic!("{direct:?} gave {a:?} but {indirect:?} gave {b:?}"),
+    }
+}
+
+// ==========================================================================
+// A LINK TO THE FIXTURE THROUGH THE BOUNDARY
+// ==========================================================================
+
+#[test]
+fn link_to_the_directory_through_the_boundary_matches_direct() {
+    let (_temp, host) = fixture();
+    let link_dir = tempfile::tempdir().unwrap();
+    let link = link_dir.path().join("link");
+    symlink(through_root(&host), &link).unwrap();
+
+    for sub in SUBPATHS {
+        assert_same(&join(&through_root(&host), sub), &join(&link, sub), |p| {
+            canonicalize(p)
+        });
+    }
+}
+
+#[test]
+fn link_to_the_boundary_itself_matches_direct() {
+    let (_temp, host) = fixture();
+    let link_dir = tempfile::tempdir().unwrap();
+    let link = link_dir.path().join("root");
+    symlink("/proc/self/root", &link).unwrap();
+    let below = host.strip_prefix("/").unwrap().to_str().unwrap();
+
+    for sub in SUBPATHS {
+        let rest = join(Path::new(below), sub);
+        assert_same(
+            &join(Path::new("/proc/self/root"), rest.to_str().unwrap()),
+            &join(&link, rest.to_str().unwrap()),
+            |p| canonicalize(p),
+        );
+    }
+}
+
+#[test]
+fn system_paths_match_through_a_link_to_the_boundary() {
+    let link_dir = tempfile::tempdir().unwrap();
+    let link = link_dir.path().join("root");
+    symlink("/proc/self/root", &link).unwrap();
+
+    for sub in ["", "etc", "etc/", "etc/.", "usr/../etc", "usr/share", ".."] {
+        assert_same(
+            &join(Path::new("/proc/self/root"), sub),
+            &join(&link, sub),
+            |p| canonicalize(p),
+        );
+    }
+}
+
+#[test]
+fn link_to_the_cwd_boundary_matches_direct() {
+    let link_dir = tempfile::tempdir().unwrap();
+    let link = link_dir.path().join("cwd");
+    symlink("/proc/self/cwd", &link).unwrap();
+    let escaping = CanonicalizeOptions::new().escape_behavior(EscapeBehavior::Error);
+
+    for sub in ["", ".", "src", "src/..", "..", "../.."] {
+        let direct = join(Path::new("/proc/self/cwd"), sub);
+        let indirect = join(&link, sub);
+        assert_same(&direct, &indirect, |p| canonicalize(p));
+        assert_same(&direct, &indirect, |p| escaping.canonicalize(p));
+    }
+}
+
+// ==========================================================================
+// WITH OPTIONS THAT CHANGE THE RESULT
+// ==========================================================================
+
+#[test]
+fn allow_missing_matches_direct() {
+    let (_temp, host) = fixture();
+    let link_dir = tempfile::tempdir().unwrap();
+    let link = link_dir.path().join("link");
+    symlink(through_root(&host), &link).unwrap();
+    let options = CanonicalizeOptions::new().allow_missing(true);
+
+    for sub in SUBPATHS {
+        assert_same(&join(&through_root(&host), sub), &join(&link, sub), |p| {
+            options.canonicalize(p)
+        });
+    }
+}
+
+#[test]
+fn resolve_outer_to_host_matches_direct() {
+    let (_temp, host) = fixture();
+    let link_dir = tempfile::tempdir().unwrap();
+    let link = link_dir.path().join("link");
+    symlink(through_root(&host), &link).unwrap();
+    let options = CanonicalizeOptions::new().resolve_outer_to_host(true);
+
+    for sub in SUBPATHS {
+        assert_same(&join(&through_root(&host), sub), &join(&link, sub), |p| {
+            options.canonicalize(p)
+        });
+    }
+}
@@ -0,0 +1,70 @@
+#![cfg(target_os = "linux")]
+
+//! Remainders ending in `..` chains of every length, from zero up to well past the
+//! filesystem root. Through `/proc/self/root` the kernel clamps at `/`, so the prefix
+//! must survive every length; through `/proc/self/cwd` the chain escapes to the host.
+
+use crate::common::root::through_root;
+use proc_canonicalize::canonicalize;
+use std::path::{Path, PathBuf};
+
+/// Extra `..` beyond the depth needed to reach `/`.
+const OVERSHOOT: usize = 5;
+
+fn dotdots(count: usize) -> PathBuf {
+    std::iter::repeat("..").take(count).collect()
+}
+
+/// `path` with its last `count` components removed, never above `/`.
+fn ancestor(path: &Path, count: usize) -> &Path {
+    path.ancestors().nth(count).unwrap_or(Path::new("/"))
+}
+
+#[test]
+fn dotdot_chain_on_bare_root_boundary_stays_at_boundary() {
+    for count in 1..=8 {
+        let path = Path::new("/proc/self/root").join(dotdots(count));
+
+        assert_eq!(
+            canonicalize(&path).unwrap(),
+            Path::new("/proc/self/root"),
+            "{}",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn dotdot_chain_below_known_subpath_clamps_at_root_boundary() {
+    let temp = tempfile::tempdir().unwrap();
+    let deep = temp.path().join("a/b/c");
+    std::fs::create_dir_all(&deep).unwrap();
+    let deep_host = std::fs::canonicalize(&deep).unwrap();
+    let depth = deep_host.components().count() - 1;
+    let inside = through_root(&deep_host);
+
+    for count in 0..=depth + OVERSHOOT {
+        let path = inside.join(dotdots(count));
+
+        let resolved = canonicalize(&path).unwrap();
+
+        let expected_host = ancestor(&deep_host, count);
+        let expected = through_root(expected_host);
+        assert_eq!(resolved, expected, "{}", path.display());
+        assert!(resolved.starts_with("/proc/self/root"));
+    }
+}
+
+#[test]
+fn dotdot_chain_below_cwd_boundary_matches_host_resolution() {
+    let cwd = std::fs::canonicalize(std::env::current_dir().unwrap()).unwrap();
+    let depth = cwd.components().count() - 1;
+
+    for count in 1..=depth + OVERSHOOT {
+        let path = Path::new("/proc/self/cwd").join(dotdots(count));
+
+        let resolved = canonicalize(&path).unwrap();
+
+        assert_eq!(resolved, ancestor(&cwd, count), "{}", path.display());
+    }
+}
@@ -0,0 +1,138 @@
+#![cfg(target_os = "linux")]
+
+//! `/proc/PID/fd/N` links whose open file has no name the caller can reach: kept as
+//! written, since the link is the only way to the file.
+
+use proc_canonicalize::{canonicalize, CanonicalizeOptions};
+use std::io::{Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+
+fn fd_path(file: &impl AsRawFd) -> String {
+    format!("/proc/self/fd/{}", file.as_raw_fd())
+}
+
+/// A file in a fresh directory, open for writing, already unlinked.
+fn unlinked_file() -> (tempfile::TempDir, std::fs::File) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("file");
+    let file = std::fs::File::create(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    (dir, file)
+}
+
+// ==========================================================================
+// FILES WITHOUT A NAME
+// ==========================================================================
+
+#[test]
+fn unlinked_file_is_kept_as_its_fd_link() {
+    let (_dir, file) = unlinked_file();
+    let path = fd_path(&file);
+
+    assert_eq!(canonicalize(&path).unwrap(), Path::new(&path));
+}
+
+#[test]
+fn kept_fd_link_still_reaches_the_unlinked_file() {
+    let (_dir, mut file) = unlinked_file();
+    file.write_all(b"still here").unwrap();
+
+    let resolved = canonicalize(fd_path(&file)).unwrap();
+
+    let mut contents = String::new();
+    std::fs::File::open(resolved)
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert_eq!(contents, "still here");
+}
+
+#[test]
+fn file_named_like_the_deleted_target_is_not_returned() {
+    let (dir, file) = unlinked_file();
+    std::fs::write(dir.path().join("file (deleted)"), b"decoy").unwrap();
+    let path = fd_path(&file);
+
+    assert_eq!(canonicalize(&path).unwrap(), Path::new(&path));
+}
+
+#[test]
+fn socket_is_kept_as_its_fd_link() {
+    let (socket, _peer) = UnixStream::pair().unwrap();
+    let path = fd_path(&socket);
+
+    assert_eq!(canonicalize(&path).unwrap(), Path::new(&path));
+}
+
+// ==========================================================================
+// FILES WITH A NAME, AND OTHER ENTRIES
+// ==========================================================================
+
+#[test]
+fn named_file_resolves_to_its_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = std::fs::File::create(dir.path().join("file")).unwrap();
+
+    assert_eq!(
+        canonicalize(fd_path(&file)).unwrap(),
+        std::fs::canonicalize(dir.path().join("file")).unwrap()
+    );
+}
+
+#[test]
+fn closed_fd_is_not_found() {
+    let err = canonicalize("/proc/self/fd/999999").unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn fd_directory_itself_resolves_as_before() {
+    let pid = std::process::id();
+
+    assert_eq!(
+        canonicalize("/proc/self/fd").unwrap(),
+        PathBuf::from(format!("/proc/{pid}/fd"))
+    );
+}
+
+// ==========================================================================
+// THE BOUNDARY OPTIONS APPLY
+// ==========================================================================
+
+#[test]
+fn pid_not_allowed_is_resolved_as_before() {
+    let (_dir, file) = unlinked_file();
+    let pid = std::process::id();
+    let path = format!("/proc/{pid}/fd/{}", file.as_raw_fd());
+
+    let err = CanonicalizeOptions::new()
+        .allowed_pids(&[])
+        .canonicalize(&path)
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+
+    assert_eq!(
+        CanonicalizeOptions::new()
+            .allowed_pids(&[pid])
+            .canonicalize(&path)
+            .unwrap(),
+        Path::new(&path)
+    );
+}
+
+#[test]
+fn expand_self_names_the_pid() {
+    let (_dir, file) = unlinked_file();
+    let pid = std::process::id();
+
+    assert_eq!(
+        CanonicalizeOptions::new()
+            .expand_self(true)
+            .canonicalize(fd_path(&file))
+            .unwrap(),
+        PathBuf::from(format!("/proc/{pid}/fd/{}", file.as_raw_fd()))
+    );
+}
@@ -0,0 +1,110 @@
+#![cfg(target_os = "linux")]
+
+//! Symlinks into `/proc` whose targets lead through further symlinks. Resolution must
+//! finish in bounded depth: deep chains succeed, cycles fail with `ELOOP`.
+
+use crate::common::root::through_root;
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::{canonicalize, canonicalize_trace_to_writer};
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+/// `ELOOP` on Linux: too many levels of symbolic links.
+const ELOOP: i32 = 40;
+
+#[test]
+fn link_into_proc_through_another_link_keeps_prefix() {
+    let (_temp, host) = canonical_tempdir();
+    std::fs::create_dir(host.join("real")).unwrap();
+    std::fs::write(host.join("real/file"), b"").unwrap();
+    symlink("real", host.join("inner")).unwrap();
+    symlink(through_root(&host.join("inner/file")), host.join("outer")).unwrap();
+
+    let resolved = canonicalize(host.join("outer")).unwrap();
+
+    assert_eq!(resolved, through_root(&host.join("real/file")));
+}
+
+#[test]
+fn long_chain_of_links_through_proc_resolves() {
+    // Each hop costs the kernel several follows (the link, /proc/self/root, the
+    // next link) out of its 40, so stay well below that.
+    let (_temp, host) = canonical_tempdir();
+    std::fs::create_dir(host.join("end")).unwrap();
+    symlink(through_root(&host.join("end")), host.join("link0")).unwrap();
+    for hop in 1..10 {
+        let previous = through_root(&host.join(format!("link{}", hop - 1)));
+        symlink(previous, host.join(format!("link{hop}"))).unwrap();
+    }
+
+    let resolved = canonicalize(host.join("link9")).unwrap();
+
+    assert_eq!(resolved, through_root(&host.join("end")));
+}
+
+#[test]
+fn cycle_through_proc_fails_with_eloop() {
+    let (_temp, host) = canonical_tempdir();
+    symlink(through_root(&host.join("b")), host.join("a")).unwrap();
+    symlink(through_root(&host.join("a")), host.join("b")).unwrap();
+
+    let err = canonicalize(host.join("a")).unwrap_err();
+
+    assert_eq!(err.raw_os_error(), Some(ELOOP));
+}
+
+#[test]
+fn cycle_through_proc_below_a_subpath_fails_with_eloop() {
+    let (_temp, host) = canonical_tempdir();
+    std::fs::create_dir(host.join("dir")).unwrap();
+    symlink(through_root(&host.join("dir/self")), host.join("dir/self")).unwrap();
+
+    let err = canonicalize(host.join("dir/self/x")).unwrap_err();
+
+    assert_eq!(err.raw_os_error(), Some(ELOOP));
+}
+
+/// Symlinks followed while resolving `path`, and the error it failed with.
+fn follows_before_failing(path: &Path) -> (usize, std::io::Error) {
+    let mut out = Vec::new();
+    let err = canonicalize_trace_to_writer(path, &mut out).unwrap_err();
+    let text = String::from_utf8(out).unwrap();
+    (text.matches("followed symlink").count(), err)
+}
+
+#[test]
+fn direct_self_loop_fails_on_the_first_revisit() {
+    let (_temp, host) = canonical_tempdir();
+    symlink(host.join("x"), host.join("x")).unwrap();
+    symlink("y", host.join("y")).unwrap();
+
+    for path in [host.join("x"), host.join("y"), host.join("y/z")] {
+        let (follows, err) = follows_before_failing(&path);
+
+        assert_eq!(err.raw_os_error(), Some(ELOOP), "{path:?}");
+        assert_eq!(follows, 1, "{path:?}");
+    }
+}
+
+#[test]
+fn two_link_cycle_fails_after_one_round() {
+    let (_temp, host) = canonical_tempdir();
+    symlink("b", host.join("a")).unwrap();
+    symlink("a", host.join("b")).unwrap();
+
+    let (follows, err) = follows_before_failing(&host.join("a"));
+
+    assert_eq!(err.raw_os_error(), Some(ELOOP));
+    assert_eq!(follows, 2);
+}
+
+#[test]
+fn revisiting_a_link_with_a_shorter_rest_is_not_a_loop() {
+    let (_temp, host) = canonical_tempdir();
+    std::fs::create_dir(host.join("real")).unwrap();
+    symlink(".", host.join("real/here")).unwrap();
+
+    let path = host.join("real/here/here/here");
+
+    assert_eq!(canonicalize(path).unwrap(), host.join("real"));
+}
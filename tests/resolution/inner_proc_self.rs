@@ -0,0 +1,158 @@
+#![cfg(target_os = "linux")]
+
+//! `/proc/self` inside a process's root (`/proc/PID/root/proc/self/...`): the
+//! process's own procfs, where `self` is the caller as that procfs numbers it. The
+//! outer boundary is kept and so is `self`, as written.
+
+use proc_canonicalize::{canonicalize, canonicalize_both, CanonicalizeOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+// ==========================================================================
+// OUR OWN ROOT: `self` IS KEPT, NOT REPLACED BY OUR PID
+// ==========================================================================
+
+#[test]
+fn self_inside_a_root_boundary_is_kept() {
+    let pid = std::process::id();
+    let path = format!("/proc/{pid}/root/proc/self/status");
+
+    assert_eq!(canonicalize(&path).unwrap(), Path::new(&path));
+}
+
+#[test]
+fn thread_self_inside_a_root_boundary_is_kept() {
+    let path = "/proc/self/root/proc/thread-self/status";
+
+    assert_eq!(canonicalize(path).unwrap(), Path::new(path));
+}
+
+#[test]
+fn bare_inner_self_is_kept_without_its_trailing_slash() {
+    assert_eq!(
+        canonicalize("/proc/self/root/proc/self/").unwrap(),
+        Path::new("/proc/self/root/proc/self")
+    );
+}
+
+#[test]
+fn inner_self_is_checked_to_exist() {
+    let path = "/proc/self/root/proc/self/no-such-entry";
+
+    let err = canonicalize(path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+    assert_eq!(
+        CanonicalizeOptions::new()
+            .allow_missing(true)
+            .canonicalize(path)
+            .unwrap(),
+        Path::new(path)
+    );
+}
+
+#[test]
+fn numeric_pid_inside_a_root_boundary_resolves_as_before() {
+    let pid = std::process::id();
+    let path = format!("/proc/self/root/proc/{pid}/status");
+
+    assert_eq!(canonicalize(&path).unwrap(), Path::new(&path));
+}
+
+// ==========================================================================
+// BELOW INNER `self`: `..` AND FOLLOWED LINKS ARE RESOLVED
+// ==========================================================================
+
+#[test]
+fn dotdot_below_inner_self_is_resolved() {
+    let path = "/proc/self/root/proc/self/../../etc";
+
+    assert_eq!(
+        canonicalize(path).unwrap(),
+        Path::new("/proc/self/root/etc")
+    );
+    assert_eq!(canonicalize_both(path).unwrap().host(), Path::new("/etc"));
+}
+
+#[test]
+fn link_followed_below_inner_self_is_resolved() {
+    let cwd = std::env::current_dir().unwrap().canonicalize().unwrap();
+    let parent = cwd.parent().unwrap().strip_prefix("/").unwrap();
+
+    assert_eq!(
+        canonicalize("/proc/self/root/proc/self/cwd/..").unwrap(),
+        Path::new("/proc/self/root").join(parent)
+    );
+}
+
+#[test]
+fn link_as_the_last_component_below_inner_self_is_kept() {
+    let path = "/proc/self/root/proc/self/cwd";
+
+    assert_eq!(canonicalize(path).unwrap(), Path::new(path));
+}
+
+// ==========================================================================
+// A ROOT WITH A PROCFS OF ITS OWN
+// ==========================================================================
+
+/// `unshare --pid --fork --mount-proc sleep 30`, and the PID of its `sleep`, which
+/// sees a procfs where this process does not exist.
+fn spawn_in_own_pid_namespace() -> Option<(std::process::Child, u32)> {
+    let mut unshare = Command::new("unshare")
+        .args([
+            "--pid",
+            "--fork",
+            "--kill-child",
+            "--mount-proc",
+            "sleep",
+            "30",
+        ])
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let children = format!("/proc/{0}/task/{0}/children", unshare.id());
+    let started = Instant::now();
+    while started.elapsed() < Duration::from_secs(5) {
+        if unshare.try_wait().unwrap().is_some() {
+            return None;
+        }
+        let sleeper = std::fs::read_to_string(&children)
+            .ok()
+            .and_then(|pids| pids.split_whitespace().next()?.parse::<u32>().ok());
+        if let Some(sleeper) = sleeper {
+            let comm = std::fs::read_to_string(format!("/proc/{sleeper}/comm"));
+            if comm.is_ok_and(|name| name.trim() == "sleep") {
+                return Some((unshare, sleeper));
+            }
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let _ = unshare.kill();
+    let _ = unshare.wait();
+    None
+}
+
+#[test]
+fn self_in_a_procfs_that_cannot_see_us_is_not_found() {
+    // Needs privileges; skipped where `unshare` is refused.
+    let (mut unshare, sleeper) = match spawn_in_own_pid_namespace() {
+        Some(spawned) => spawned,
+        None => return eprintln!("skipping: cannot create a PID namespace here"),
+    };
+
+    let inner_self = canonicalize(format!("/proc/{sleeper}/root/proc/self/status"));
+    let inner_init = canonicalize(format!("/proc/{sleeper}/root/proc/1/status"));
+    let _ = unshare.kill();
+    let _ = unshare.wait();
+
+    // The kernel agrees: opening the path fails the same way. Resolving `self` in
+    // our procfs instead would have named our host PID in theirs.
+    assert_eq!(inner_self.unwrap_err().kind(), io::ErrorKind::NotFound);
+    assert_eq!(
+        inner_init.unwrap(),
+        PathBuf::from(format!("/proc/{sleeper}/root/proc/1/status"))
+    );
+}
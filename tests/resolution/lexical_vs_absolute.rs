@@ -0,0 +1,123 @@
+#![cfg(unix)]
+
+//! How `canonicalize_lexical` relates to `std::path::absolute` and to `canonicalize`.
+//!
+//! `std::path::absolute` needs Rust 1.79, above this crate's MSRV, so its outputs are
+//! pinned here as data rather than called. They were taken from its Unix behavior: `.`
+//! and repeated separators dropped, `..` and trailing separators kept.
+
+use proc_canonicalize::{canonicalize, canonicalize_lexical};
+use std::path::Path;
+
+/// `(input, std::path::absolute(input), canonicalize_lexical(input))`
+const ABSOLUTE_INPUTS: &[(&str, &str, &str)] = &[
+    // Agreement: no `..`, no trailing separator.
+    ("/", "/", "/"),
+    ("/a//b", "/a/b", "/a/b"),
+    ("/a/./b", "/a/b", "/a/b"),
+    // Trailing separators are kept by `absolute`, dropped here.
+    ("/a/./b/", "/a/b/", "/a/b"),
+    // `..` is kept by `absolute`, folded here.
+    ("/a/b/../c", "/a/b/../c", "/a/c"),
+    ("/a/b/..", "/a/b/..", "/a"),
+    ("/..", "/..", "/"),
+    // Namespace clamping: `..` never climbs out of a boundary.
+    (
+        "/proc/self/root/..",
+        "/proc/self/root/..",
+        "/proc/self/root",
+    ),
+    (
+        "/proc/self/root/../etc",
+        "/proc/self/root/../etc",
+        "/proc/self/root/etc",
+    ),
+    (
+        "/proc/7/cwd/a/../../b",
+        "/proc/7/cwd/a/../../b",
+        "/proc/7/cwd/b",
+    ),
+];
+
+/// Lexical folding of `absolute`'s output, as a plain `..`-popping walk would do it.
+fn fold_without_clamping(absolute: &str) -> std::path::PathBuf {
+    let mut folded = std::path::PathBuf::new();
+    for component in Path::new(absolute).components() {
+        match component {
+            std::path::Component::ParentDir => {
+                folded.pop();
+            }
+            component => folded.push(component),
+        }
+    }
+    folded
+}
+
+// ==========================================================================
+// ABSOLUTE INPUTS
+// ==========================================================================
+
+#[test]
+fn lexical_matches_pinned_table() {
+    for (input, _, lexical) in ABSOLUTE_INPUTS {
+        assert_eq!(
+            canonicalize_lexical(input).unwrap(),
+            Path::new(lexical),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn lexical_is_folded_absolute_except_where_clamped() {
+    for (input, absolute, lexical) in ABSOLUTE_INPUTS {
+        let clamped = input.starts_with("/proc/") && input.contains("..");
+        let same = fold_without_clamping(absolute) == Path::new(lexical);
+        assert_eq!(same, !clamped, "{input}");
+    }
+}
+
+// ==========================================================================
+// RELATIVE INPUTS
+// ==========================================================================
+
+#[test]
+fn relative_input_is_anchored_at_cwd() {
+    let cwd = std::env::current_dir().unwrap();
+
+    assert_eq!(canonicalize_lexical(".").unwrap(), cwd);
+    assert_eq!(canonicalize_lexical("a/./b/").unwrap(), cwd.join("a/b"));
+    assert_eq!(
+        canonicalize_lexical("../x").unwrap(),
+        cwd.parent().unwrap().join("x")
+    );
+}
+
+// ==========================================================================
+// AGAINST CANONICALIZE
+// ==========================================================================
+
+#[test]
+fn symlink_free_existing_path_matches_canonicalize() {
+    let temp = tempfile::tempdir().unwrap();
+    let host = std::fs::canonicalize(temp.path()).unwrap();
+    std::fs::create_dir_all(host.join("a/b")).unwrap();
+    let path = host.join("a/./b/../b/");
+
+    assert_eq!(
+        canonicalize_lexical(&path).unwrap(),
+        canonicalize(&path).unwrap()
+    );
+}
+
+#[test]
+fn symlink_then_dotdot_is_where_lexical_and_canonicalize_disagree() {
+    let temp = tempfile::tempdir().unwrap();
+    let host = std::fs::canonicalize(temp.path()).unwrap();
+    std::fs::create_dir_all(host.join("real/deep")).unwrap();
+    std::os::unix::fs::symlink("real/deep", host.join("link")).unwrap();
+    let path = host.join("link/..");
+
+    assert_eq!(canonicalize_lexical(&path).unwrap(), host);
+    assert_eq!(canonicalize(&path).unwrap(), host.join("real"));
+}
@@ -0,0 +1,118 @@
+#![cfg(target_os = "linux")]
+
+//! A symlink below a boundary that points at another magic link, e.g.
+//! `/proc/self/root/run/link` with `link -> /proc/self/cwd`.
+//!
+//! Decision: the first boundary wins. The kernel follows the link (resolving its
+//! absolute target from *our* root, with *our* `/proc/self`), and the result is where
+//! it lands, expressed under the first boundary's prefix, or handled as an escape when
+//! it lands outside that boundary. The result never switches to the second boundary's
+//! prefix, since that boundary is not part of the path the caller gave.
+
+use crate::common::cwd::CwdProcess;
+use crate::common::root::through_root;
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::{canonicalize, BoundaryEscaped, CanonicalizeOptions, EscapeBehavior};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+// ==========================================================================
+// LANDS INSIDE THE FIRST BOUNDARY: ITS PREFIX IS KEPT
+// ==========================================================================
+
+#[test]
+fn link_to_cwd_below_root_keeps_the_root_prefix() {
+    let (_dir, host) = canonical_tempdir();
+    std::fs::create_dir(host.join("workdir")).unwrap();
+    // One child working beside the link, one elsewhere.
+    let beside = CwdProcess::spawn_in(&host.join("workdir"));
+    let elsewhere = CwdProcess::spawn();
+
+    for (name, child) in [("beside", &beside), ("elsewhere", &elsewhere)] {
+        std::fs::create_dir(child.dir().join("src")).unwrap();
+        let cwd = std::fs::canonicalize(child.dir()).unwrap();
+        symlink(child.cwd_boundary(), host.join(name)).unwrap();
+        let link = through_root(&host).join(name);
+
+        assert_eq!(canonicalize(&link).unwrap(), through_root(&cwd), "{name}");
+        assert_eq!(
+            canonicalize(link.join("src")).unwrap(),
+            through_root(&cwd).join("src"),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn result_is_not_the_second_boundary() {
+    let (_dir, host) = canonical_tempdir();
+    symlink("/proc/self/cwd", host.join("link")).unwrap();
+
+    let resolved = canonicalize(through_root(&host).join("link")).unwrap();
+
+    assert!(resolved.starts_with("/proc/self/root"), "{resolved:?}");
+    assert_eq!(resolved, through_root(&std::env::current_dir().unwrap()));
+}
+
+#[test]
+fn without_the_outer_boundary_the_link_target_is_the_boundary() {
+    let (_dir, host) = canonical_tempdir();
+    symlink("/proc/self/cwd", host.join("link")).unwrap();
+
+    assert_eq!(
+        canonicalize(host.join("link")).unwrap(),
+        Path::new("/proc/self/cwd")
+    );
+}
+
+// ==========================================================================
+// LANDS OUTSIDE THE FIRST BOUNDARY: AN ESCAPE
+// ==========================================================================
+
+/// A stand-in container root holding `link -> /proc/self/cwd`; our cwd is outside it.
+fn container_with_link_to_cwd() -> (tempfile::TempDir, PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let rootfs = std::fs::canonicalize(dir.path()).unwrap().join("rootfs");
+    std::fs::create_dir(&rootfs).unwrap();
+    symlink("/proc/self/cwd", rootfs.join("link")).unwrap();
+    (dir, rootfs)
+}
+
+#[test]
+fn landing_outside_is_an_escape() {
+    let (_dir, rootfs) = container_with_link_to_cwd();
+    let cwd = std::env::current_dir().unwrap();
+    let options = |behavior| {
+        CanonicalizeOptions::new()
+            .stand_in_root(&rootfs)
+            .escape_behavior(behavior)
+    };
+
+    assert_eq!(
+        options(EscapeBehavior::ReturnHost)
+            .canonicalize("/proc/4242/root/link")
+            .unwrap(),
+        cwd
+    );
+    let err = options(EscapeBehavior::Error)
+        .canonicalize("/proc/4242/root/link")
+        .unwrap_err();
+    assert!(err
+        .get_ref()
+        .is_some_and(|inner| inner.is::<BoundaryEscaped>()));
+}
+
+#[test]
+fn clamping_reads_the_target_inside_the_container() {
+    let (_dir, rootfs) = container_with_link_to_cwd();
+
+    // `/proc/self/cwd` inside the stand-in root does not exist.
+    let err = CanonicalizeOptions::new()
+        .stand_in_root(rootfs)
+        .escape_behavior(EscapeBehavior::ClampToRoot)
+        .canonicalize("/proc/4242/root/link")
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
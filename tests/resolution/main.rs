@@ -0,0 +1,33 @@
+#![cfg(feature = "fs")]
+
+//! How paths resolve: boundaries, symlinks into `/proc`, `..`, and the shape of the
+//! result. Each module covers one behavior of `canonicalize` itself.
+
+#[cfg(target_os = "linux")]
+#[path = "../common"]
+mod common {
+    pub mod cwd;
+    pub mod pid;
+    pub mod root;
+    pub mod temp;
+}
+
+mod backslashes;
+mod bind_mounts;
+mod cwd_reentry;
+mod direct_indirect_consistency;
+mod dotdot_chains;
+mod fd_links;
+mod indirect_chains;
+mod inner_proc_self;
+mod lexical_vs_absolute;
+mod magic_link_below_boundary;
+mod memory_fs;
+mod normalized_output;
+mod ns_links;
+mod os_str_input;
+mod path_max;
+mod process_gone;
+mod selector_spelling;
+mod trailing_slashes;
+mod unicode_normalization;
@@ -0,0 +1,56 @@
+#![cfg(all(target_os = "linux", feature = "testing"))]
+
+//! `MemoryFs` (testing feature): resolve crafted topologies without touching disk.
+
+use proc_canonicalize::MemoryFs;
+use std::io;
+use std::path::Path;
+
+#[test]
+fn crafted_container_topology_resolves_like_a_real_one() {
+    let fs = MemoryFs::new()
+        .dir("/var/lib/containers/app/srv/data")
+        .symlink("/var/lib/containers/app/srv/latest", "data")
+        .symlink("/proc/4242/root", "/var/lib/containers/app")
+        .symlink("/run/app-root", "/proc/4242/root");
+
+    assert_eq!(
+        fs.canonicalize("/run/app-root/srv/latest").unwrap(),
+        Path::new("/proc/4242/root/srv/data")
+    );
+    assert_eq!(
+        fs.canonicalize("/proc/4242/root").unwrap(),
+        Path::new("/proc/4242/root")
+    );
+}
+
+#[test]
+fn dotdot_escape_through_cwd_returns_host_path() {
+    let fs = MemoryFs::new()
+        .dir("/home/alice/project")
+        .symlink("/proc/77/cwd", "/home/alice/project");
+
+    assert_eq!(
+        fs.canonicalize("/proc/77/cwd/../..").unwrap(),
+        Path::new("/home")
+    );
+}
+
+#[test]
+fn does_not_touch_the_real_filesystem() {
+    let fs = MemoryFs::new().dir("/definitely/not/on/this/host");
+
+    assert_eq!(
+        fs.canonicalize("/definitely/not/on/this/host").unwrap(),
+        Path::new("/definitely/not/on/this/host")
+    );
+    let err = fs.canonicalize("/etc").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn relative_input_resolves_from_root() {
+    let fs = MemoryFs::new().dir("/etc");
+
+    assert_eq!(fs.canonicalize("etc").unwrap(), Path::new("/etc"));
+}
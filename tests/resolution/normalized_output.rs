@@ -0,0 +1,171 @@
+#![cfg(target_os = "linux")]
+
+//! Every successful result is fully normalized: no `.` or `..` segments, no empty
+//! segments, and no trailing separator.
+//!
+//! `Path` equality and `Path::components` both ignore interior `.` and trailing
+//! separators, so these checks read the raw bytes instead.
+
+use proc_canonicalize::{canonicalize, canonicalize_both, CanonicalizeOptions, EscapeBehavior};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+/// Inputs covering boundaries, `.`/`..` in every position, and trailing separators.
+const INPUTS: &[&str] = &[
+    "/proc/self/root/./etc/../tmp",
+    "/proc/self/root/.",
+    "/proc/self/root/./",
+    "/proc/self/root/..",
+    "/proc/self/root/../",
+    "/proc/self/root/etc/..",
+    "/proc/self/root/etc/../",
+    "/proc/self/root/etc/../..",
+    "/proc/self/root///",
+    "/proc/self/root/./etc/./",
+    "/proc/self/root/etc/./.",
+    "/proc/./self/root/etc",
+    "/proc/self/./root",
+    "/proc/self/task/../root",
+    "//proc/self/root/..",
+    "/proc/self/cwd/.",
+    "/proc/self/cwd/./",
+    "/proc/self/cwd/..",
+    "/proc/self/cwd/src/..",
+    "/proc/thread-self/root/.",
+    "/proc/self/root/proc/self/../../etc",
+    "/proc/self/root/proc/self/cwd/..",
+    "/proc/thread-self/cwd/src/../",
+    "/proc/self/ns/net",
+    "/./etc/../tmp/",
+    "/",
+    "/..",
+    ".",
+    "src/..",
+    "./src/./",
+];
+
+/// Whether `path` has a `.`, `..` or empty segment, or a trailing separator.
+fn unnormalized_segment(path: &Path) -> Option<String> {
+    let bytes = path.as_os_str().as_bytes();
+    if bytes == b"/" {
+        return None;
+    }
+    let relative = bytes.strip_prefix(b"/")?;
+    relative
+        .split(|&byte| byte == b'/')
+        .find(|segment| matches!(*segment, b"" | b"." | b".."))
+        .map(|segment| String::from_utf8_lossy(segment).into_owned())
+}
+
+fn assert_normalized(input: &str, result: &Path) {
+    assert!(result.is_absolute(), "{input} => {result:?} is relative");
+    if let Some(segment) = unnormalized_segment(result) {
+        panic!("{input} => {result:?} has segment {segment:?}");
+    }
+}
+
+fn check_all(label: &str, resolve: impl Fn(&str) -> std::io::Result<PathBuf>) {
+    let mut resolved = 0;
+    for input in INPUTS {
+        if let Ok(result) = resolve(input) {
+            assert_normalized(&format!("{label}: {input}"), &result);
+            resolved += 1;
+        }
+    }
+    assert!(
+        resolved > INPUTS.len() / 2,
+        "{label}: most inputs should resolve"
+    );
+}
+
+// ==========================================================================
+// ENTRY POINTS AND OPTIONS
+// ==========================================================================
+
+#[test]
+fn canonicalize_output_is_normalized() {
+    check_all("canonicalize", |input| canonicalize(input));
+}
+
+#[test]
+fn both_forms_are_normalized() {
+    check_all("namespace", |input| {
+        canonicalize_both(input).map(|both| both.namespace().to_path_buf())
+    });
+    check_all("host", |input| {
+        canonicalize_both(input).map(|both| both.host().to_path_buf())
+    });
+}
+
+#[test]
+fn every_escape_behavior_output_is_normalized() {
+    for behavior in [
+        EscapeBehavior::ReturnHost,
+        EscapeBehavior::ClampToRoot,
+        EscapeBehavior::Error,
+    ] {
+        let options = CanonicalizeOptions::new().escape_behavior(behavior);
+        check_all(&format!("{behavior:?}"), |input| {
+            options.canonicalize(input)
+        });
+    }
+}
+
+#[test]
+fn lenient_and_rewriting_options_output_is_normalized() {
+    let options = [
+        CanonicalizeOptions::new().allow_missing(true),
+        CanonicalizeOptions::new().expand_self(true),
+        CanonicalizeOptions::new().pin_thread_self(true),
+        CanonicalizeOptions::new().resolve_outer_to_host(true),
+    ];
+    for (index, options) in options.iter().enumerate() {
+        check_all(&format!("options #{index}"), |input| {
+            options.canonicalize(input)
+        });
+    }
+}
+
+#[test]
+fn missing_components_under_allow_missing_are_normalized() {
+    let options = CanonicalizeOptions::new().allow_missing(true);
+
+    for input in [
+        "/proc/self/root/no/such/./x/../",
+        "/proc/self/root/nope/..",
+        "/proc/self/cwd/nope/../",
+        "/no/such/./path/..",
+    ] {
+        assert_normalized(input, &options.canonicalize(input).unwrap());
+    }
+}
+
+// ==========================================================================
+// THROUGH SYMLINKS
+// ==========================================================================
+
+#[test]
+fn results_through_indirect_links_are_normalized() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", dir.path().join("root")).unwrap();
+    symlink("/proc/self/root/etc/..", dir.path().join("root_up")).unwrap();
+    symlink("/proc/self/root/./etc/.", dir.path().join("dotted")).unwrap();
+
+    for name in [
+        "root", "root/.", "root/..", "root_up", "root_up/", "dotted/.",
+    ] {
+        let input = dir.path().join(name);
+        let result = canonicalize(&input).unwrap();
+        assert_normalized(&input.display().to_string(), &result);
+    }
+}
+
+#[test]
+fn checker_catches_what_path_equality_hides() {
+    for path in ["/proc/self/root/", "/a/./b", "/a/../b", "/a//b"] {
+        assert!(unnormalized_segment(Path::new(path)).is_some(), "{path}");
+    }
+    assert_eq!(unnormalized_segment(Path::new("/")), None);
+    assert_eq!(unnormalized_segment(Path::new("/proc/self/root")), None);
+}
@@ -0,0 +1,98 @@
+#![cfg(target_os = "linux")]
+
+//! Namespace links (`/proc/PID/ns/TYPE`) are preserved like `root` and `cwd`.
+//!
+//! The kernel's `readlink` on these returns a pseudo-target such as `net:[4026531840]`,
+//! which `std::fs::canonicalize` cannot resolve into any usable path.
+
+use proc_canonicalize::canonicalize;
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+// ==========================================================================
+// DIRECT ACCESS
+// ==========================================================================
+
+#[test]
+fn proc_self_ns_net_preserved_where_std_fails() {
+    let path = "/proc/self/ns/net";
+
+    let our_result = canonicalize(path).unwrap();
+    let std_result = std::fs::canonicalize(path);
+
+    assert!(std_result.is_err());
+    assert_eq!(our_result, Path::new("/proc/self/ns/net"));
+}
+
+#[test]
+fn explicit_pid_ns_mnt_preserved() {
+    let pid = std::process::id();
+    let namespace_link = format!("/proc/{pid}/ns/mnt");
+
+    let canonical = canonicalize(&namespace_link).unwrap();
+
+    assert_eq!(canonical, Path::new(&namespace_link));
+}
+
+#[test]
+fn path_through_ns_link_is_not_a_directory_error() {
+    let result = canonicalize("/proc/self/ns/net/anything");
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn unknown_ns_type_behaves_like_std() {
+    let path = "/proc/self/ns/not_a_namespace";
+
+    let our_result = canonicalize(path);
+
+    assert_eq!(our_result.unwrap_err().kind(), io::ErrorKind::NotFound);
+}
+
+// ==========================================================================
+// INDIRECT SYMLINKS: Symlinks outside /proc pointing TO ns links
+// ==========================================================================
+
+#[test]
+fn symlink_to_proc_pid_ns_net_preserves_namespace_link() {
+    let pid = std::process::id();
+    let namespace_link = format!("/proc/{pid}/ns/net");
+    let temp = tempfile::tempdir().unwrap();
+    let netns_link = temp.path().join("netns");
+    symlink(&namespace_link, &netns_link).unwrap();
+
+    let canonical = canonicalize(&netns_link).unwrap();
+
+    assert_eq!(canonical, Path::new(&namespace_link));
+}
+
+#[test]
+fn relative_symlink_to_ns_link_preserves_namespace_link() {
+    let temp = tempfile::tempdir().unwrap();
+    let depth = temp.path().components().count() - 1;
+    let relative_target = format!("{}proc/self/ns/uts", "../".repeat(depth));
+    let utsns_link = temp.path().join("utsns");
+    symlink(relative_target, &utsns_link).unwrap();
+
+    let canonical = canonicalize(&utsns_link).unwrap();
+
+    assert_eq!(canonical, Path::new("/proc/self/ns/uts"));
+}
+
+// ==========================================================================
+// PUBLISHED TYPE SET (NS_TYPES)
+// ==========================================================================
+
+#[test]
+fn every_ns_type_present_on_this_kernel_is_preserved() {
+    for ns_type in proc_canonicalize::NS_TYPES {
+        let path = format!("/proc/self/ns/{ns_type}");
+        if std::fs::symlink_metadata(&path).is_err() {
+            continue; // older kernel without this namespace type
+        }
+
+        assert_eq!(canonicalize(&path).unwrap(), Path::new(&path));
+    }
+}
@@ -0,0 +1,48 @@
+#![cfg(target_os = "linux")]
+
+//! Raw OS strings as input: accepted directly, and not assumed to be UTF-8.
+
+use proc_canonicalize::{canonicalize, canonicalize_both, to_host, CanonicalizeOptions};
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::Path;
+
+#[test]
+fn os_str_and_os_string_are_accepted_directly() {
+    let borrowed = OsStr::new("/proc/self/root/etc");
+    let owned = OsString::from("/proc/self/root/etc");
+
+    assert_eq!(canonicalize(borrowed).unwrap(), Path::new(borrowed));
+    assert_eq!(canonicalize(&owned).unwrap(), Path::new(&owned));
+    assert_eq!(
+        CanonicalizeOptions::new().canonicalize(owned).unwrap(),
+        Path::new(borrowed)
+    );
+}
+
+#[test]
+fn non_utf8_names_round_trip_byte_for_byte() {
+    let dir = tempfile::tempdir().unwrap();
+    let name = OsStr::from_bytes(b"caf\xe9");
+    std::fs::create_dir(dir.path().join(name)).unwrap();
+    let mut input = b"/proc/self/root".to_vec();
+    input.extend_from_slice(dir.path().as_os_str().as_bytes());
+    input.extend_from_slice(b"/./caf\xe9");
+    let input = OsString::from_vec(input);
+
+    let resolved = canonicalize(&input).unwrap();
+
+    assert_eq!(resolved.file_name(), Some(name));
+    assert!(resolved.starts_with("/proc/self/root"));
+    assert_eq!(
+        canonicalize_both(&input).unwrap().host(),
+        to_host(&input).unwrap()
+    );
+}
+
+#[test]
+fn non_utf8_pid_segment_is_not_a_boundary() {
+    let err = canonicalize(OsStr::from_bytes(b"/proc/\xff/root")).unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
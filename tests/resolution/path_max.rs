@@ -0,0 +1,127 @@
+#![cfg(target_os = "linux")]
+
+//! Results near and beyond `PATH_MAX`: the kernel checks each path it is given, and
+//! the crate checks the preserved paths it assembles from them.
+
+use crate::common::root::through_root;
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::{canonicalize, CanonicalizeOptions};
+use std::fs::File;
+use std::os::unix::fs::symlink;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+/// `PATH_MAX` on Linux, counting the terminating NUL.
+const PATH_MAX: usize = 4096;
+
+/// `ENAMETOOLONG` on Linux.
+const ENAMETOOLONG: i32 = 36;
+
+/// Bytes `/proc/self/root` adds in front of a host path.
+const ROOT_PREFIX: usize = "/proc/self/root".len();
+
+/// Nested directories below a fresh temporary directory, making a host path of
+/// exactly `len` bytes.
+///
+/// Each level is created through `/proc/self/fd` of the one above, so no path handed
+/// to the kernel grows with the depth and the tree can pass `PATH_MAX`.
+fn deep_dir(len: usize) -> (tempfile::TempDir, PathBuf) {
+    let (temp, mut host) = canonical_tempdir();
+    let mut dir = File::open(&host).unwrap();
+    while host.as_os_str().len() < len {
+        let room = len - host.as_os_str().len() - 1;
+        // Never leave room for just the `/` of one more level.
+        let name = "d".repeat(match room {
+            0..=200 => room,
+            201 => 199,
+            _ => 200,
+        });
+        let level = format!("/proc/self/fd/{}/{name}", dir.as_raw_fd());
+        std::fs::create_dir(&level).unwrap();
+        dir = File::open(&level).unwrap();
+        host.push(name);
+    }
+    assert_eq!(host.as_os_str().len(), len);
+    (temp, host)
+}
+
+fn assert_too_long(result: std::io::Result<PathBuf>) {
+    let err = result.unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(ENAMETOOLONG), "{err}");
+}
+
+// ==========================================================================
+// UNDER THE LIMIT
+// ==========================================================================
+
+#[test]
+fn longest_preserved_result_is_returned() {
+    let (_temp, host) = deep_dir(PATH_MAX - 1 - ROOT_PREFIX);
+    let path = through_root(&host);
+
+    let resolved = canonicalize(&path).unwrap();
+
+    assert_eq!(resolved, path);
+    assert_eq!(resolved.as_os_str().len(), PATH_MAX - 1);
+}
+
+#[test]
+fn longest_host_result_is_returned() {
+    let (_temp, host) = deep_dir(PATH_MAX - 1);
+
+    assert_eq!(canonicalize(&host).unwrap(), host);
+}
+
+// ==========================================================================
+// OVER THE LIMIT
+// ==========================================================================
+
+#[test]
+fn boundary_prefix_pushing_result_past_the_limit_is_too_long() {
+    // The host path fits; with `/proc/self/root` in front of it, it does not.
+    let (_temp, host) = deep_dir(PATH_MAX - ROOT_PREFIX);
+    let path = through_root(&host);
+
+    assert!(std::fs::canonicalize(&host).is_ok());
+    assert_too_long(canonicalize(path));
+}
+
+#[test]
+fn short_symlink_expanding_past_the_limit_is_too_long() {
+    // The link covers the top of the tree through `/proc/self/root`; the input names
+    // the rest. The host path fits, the preserved result does not.
+    let (_temp, host) = deep_dir(PATH_MAX - 10);
+    let top = host
+        .ancestors()
+        .find(|dir| dir.as_os_str().len() <= 3000)
+        .unwrap();
+    let links = tempfile::tempdir().unwrap();
+    let target = through_root(top);
+    symlink(target, links.path().join("deep")).unwrap();
+
+    let path = links
+        .path()
+        .join("deep")
+        .join(host.strip_prefix(top).unwrap());
+    assert!(path.as_os_str().len() < PATH_MAX);
+    assert!(std::fs::canonicalize(&host).is_ok());
+    assert_too_long(canonicalize(&path));
+}
+
+#[test]
+fn host_path_past_the_limit_is_too_long() {
+    let (_temp, host) = deep_dir(PATH_MAX + 100);
+
+    assert_too_long(canonicalize(host));
+}
+
+#[test]
+fn missing_result_past_the_limit_is_too_long() {
+    let (_temp, host) = deep_dir(PATH_MAX - ROOT_PREFIX - 10);
+    let path = Path::new("/proc/self/root")
+        .join(host.strip_prefix("/").unwrap())
+        .join("not-created-yet");
+    let options = CanonicalizeOptions::new().allow_missing(true);
+
+    assert_too_long(options.canonicalize(path));
+}
@@ -0,0 +1,91 @@
+#![cfg(target_os = "linux")]
+
+//! `ProcessGone`: a boundary under a PID with no process says so, directly or
+//! through a symlink, instead of a bare "No such file or directory".
+
+use proc_canonicalize::{canonicalize, CanonicalizeOptions, ProcessGone, ProcessIdentity};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+use std::process::Command;
+
+/// Above the kernel's largest possible `pid_max` (2^22), so never a live process.
+const IMPLAUSIBLE_PID: u32 = 99_999_999;
+
+fn gone_pid(err: &io::Error) -> Option<u32> {
+    err.get_ref()
+        .and_then(|e| e.downcast_ref::<ProcessGone>())
+        .map(ProcessGone::pid)
+}
+
+// ==========================================================================
+// DEAD PIDS
+// ==========================================================================
+
+#[test]
+fn symlink_to_dead_pid_root_reports_process_gone() {
+    let dir = tempfile::tempdir().unwrap();
+    let link = dir.path().join("link");
+    symlink(format!("/proc/{IMPLAUSIBLE_PID}/root"), &link).unwrap();
+
+    for path in [link.clone(), link.join("etc")] {
+        let err = canonicalize(&path).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert_eq!(gone_pid(&err), Some(IMPLAUSIBLE_PID), "{}", path.display());
+        assert!(err.to_string().contains("no longer exists"));
+    }
+}
+
+#[test]
+fn direct_path_under_dead_pid_reports_process_gone() {
+    for suffix in ["root", "cwd/src", "task/1/root", "ns/net"] {
+        let path = format!("/proc/{IMPLAUSIBLE_PID}/{suffix}");
+
+        let err = canonicalize(&path).unwrap_err();
+
+        assert_eq!(gone_pid(&err), Some(IMPLAUSIBLE_PID), "{path}");
+    }
+}
+
+#[test]
+fn exited_child_reports_process_gone_when_verified() {
+    let mut child = Command::new("true").spawn().unwrap();
+    let identity = ProcessIdentity::capture(child.id()).unwrap();
+    child.wait().unwrap();
+
+    let err = CanonicalizeOptions::new()
+        .verify_process(identity)
+        .canonicalize(format!("/proc/{}/root", identity.pid()))
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(gone_pid(&err), Some(identity.pid()));
+}
+
+// ==========================================================================
+// LIVE PIDS: PLAIN NOT FOUND
+// ==========================================================================
+
+#[test]
+fn missing_file_in_live_process_is_plain_not_found() {
+    let err = canonicalize("/proc/self/root/no/such/file").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(gone_pid(&err), None);
+
+    let own = format!("/proc/{}/root/no/such/file", std::process::id());
+    let err = canonicalize(own).unwrap_err();
+    assert_eq!(gone_pid(&err), None);
+}
+
+#[test]
+fn missing_thread_of_live_process_is_plain_not_found() {
+    let path = Path::new("/proc/self/task")
+        .join(IMPLAUSIBLE_PID.to_string())
+        .join("root");
+
+    let err = canonicalize(path).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(gone_pid(&err), None);
+}
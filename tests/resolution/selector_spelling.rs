@@ -0,0 +1,85 @@
+#![cfg(target_os = "linux")]
+
+//! The PID selector comes back spelled as the caller wrote it.
+//!
+//! `/proc/self` and `/proc/<own PID>` reach the same directory, so collapsing one into
+//! the other looks like a harmless normalization. It is not: a caller that stored or
+//! compares the numeric form would see a different path back. Only `expand_self` may
+//! rewrite a selector, and only in the `self` to PID direction.
+
+use crate::common::pid::this_process;
+use proc_canonicalize::{canonicalize, CanonicalizeOptions};
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+// ==========================================================================
+// OWN PID STAYS NUMERIC
+// ==========================================================================
+
+#[test]
+fn own_pid_is_not_collapsed_to_self() {
+    for suffix in ["root", "root/etc", "cwd", "ns/net"] {
+        let path = this_process().join(suffix);
+
+        assert_eq!(canonicalize(&path).unwrap(), path, "{suffix}");
+    }
+}
+
+#[test]
+fn own_task_boundary_is_not_collapsed_to_thread_self() {
+    let me = Path::new("/proc").join(std::fs::read_link("/proc/thread-self").unwrap());
+
+    assert_eq!(canonicalize(me.join("root")).unwrap(), me.join("root"));
+    assert_eq!(canonicalize(me.join("cwd/.")).unwrap(), me.join("cwd"));
+}
+
+#[test]
+fn own_pid_reached_through_a_symlink_stays_numeric() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink(this_process().join("root"), dir.path().join("container")).unwrap();
+
+    assert_eq!(
+        canonicalize(dir.path().join("container/etc")).unwrap(),
+        this_process().join("root/etc")
+    );
+}
+
+#[test]
+fn own_pid_after_dotdot_stays_numeric() {
+    let path = Path::new("/proc/self/..")
+        .join(std::process::id().to_string())
+        .join("root/etc");
+
+    assert_eq!(canonicalize(path).unwrap(), this_process().join("root/etc"));
+}
+
+#[test]
+fn options_leave_a_numeric_pid_alone() {
+    let path = this_process().join("root/etc");
+
+    for options in [
+        CanonicalizeOptions::new().expand_self(true),
+        CanonicalizeOptions::new().pin_thread_self(true),
+        CanonicalizeOptions::new().memoize_boundaries(true),
+    ] {
+        assert_eq!(options.canonicalize(&path).unwrap(), path, "{options:?}");
+    }
+}
+
+// ==========================================================================
+// SELF STAYS SELF
+// ==========================================================================
+
+#[test]
+fn self_and_thread_self_are_not_expanded_to_pids() {
+    for path in ["/proc/self/root/etc", "/proc/thread-self/root/etc"] {
+        assert_eq!(canonicalize(path).unwrap(), Path::new(path), "{path}");
+    }
+}
+
+#[test]
+fn miscased_self_is_not_a_boundary() {
+    let err = canonicalize("/proc/Self/root").unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
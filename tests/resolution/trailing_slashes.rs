@@ -0,0 +1,93 @@
+#![cfg(target_os = "linux")]
+
+//! Trailing-slash noise from naive string concatenation must not change results.
+//!
+//! `Path::components` collapses trailing separators, so the boundary parser never sees
+//! them. The one thing a trailing slash does mean — "this must be a directory" — is kept,
+//! matching `std::fs::canonicalize`.
+
+use proc_canonicalize::canonicalize;
+use std::path::Path;
+
+#[test]
+fn many_trailing_slashes_on_bare_root_boundary() {
+    let canonical = canonicalize("/proc/self/root///").unwrap();
+
+    assert_eq!(canonical, Path::new("/proc/self/root"));
+}
+
+#[test]
+fn many_trailing_slashes_on_bare_cwd_boundary() {
+    let canonical = canonicalize("/proc/self/cwd///").unwrap();
+
+    assert_eq!(canonical, Path::new("/proc/self/cwd"));
+}
+
+#[test]
+fn many_trailing_slashes_on_root_subpath() {
+    let with_slashes = canonicalize("/proc/self/root/etc///").unwrap();
+    let without_slashes = canonicalize("/proc/self/root/etc").unwrap();
+
+    assert_eq!(with_slashes, without_slashes);
+    assert_eq!(with_slashes, Path::new("/proc/self/root/etc"));
+}
+
+#[test]
+fn many_trailing_slashes_on_cwd_subpath() {
+    let subdir = tempfile::tempdir_in(".").unwrap();
+    let subdir_name = subdir.path().file_name().unwrap().to_str().unwrap();
+    let through_cwd = format!("/proc/self/cwd/{subdir_name}");
+
+    let with_slashes = canonicalize(format!("{through_cwd}///")).unwrap();
+    let without_slashes = canonicalize(&through_cwd).unwrap();
+
+    assert_eq!(with_slashes, without_slashes);
+    assert_eq!(with_slashes, Path::new(&through_cwd));
+}
+
+#[test]
+fn interior_repeated_slashes_collapse() {
+    let canonical = canonicalize("/proc/self/root//etc//").unwrap();
+
+    assert_eq!(canonical, Path::new("/proc/self/root/etc"));
+}
+
+#[test]
+fn trailing_slash_on_file_through_namespace_errors_like_std() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("plain.txt");
+    std::fs::write(&file, "").unwrap();
+    let file_with_slash = format!("{}/", file.display());
+    let through_root_with_slash = format!("/proc/self/root{file_with_slash}");
+
+    let std_error = std::fs::canonicalize(&file_with_slash).unwrap_err();
+    let our_error = canonicalize(through_root_with_slash).unwrap_err();
+
+    assert_eq!(our_error.raw_os_error(), std_error.raw_os_error());
+}
+
+#[test]
+fn trailing_slash_on_bare_ns_link_errors_like_non_directory() {
+    let result = canonicalize("/proc/self/ns/net/");
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn trailing_slash_through_indirect_symlink_matches_direct() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("plain.txt"), "").unwrap();
+    let container_link = dir.path().join("container");
+    std::os::unix::fs::symlink("/proc/self/root", &container_link).unwrap();
+    let relative_dir = dir.path().strip_prefix("/").unwrap();
+    let through_link = container_link.join(relative_dir);
+
+    let dir_with_slashes = canonicalize(format!("{}///", through_link.display())).unwrap();
+    let file_with_slash = canonicalize(format!("{}/plain.txt/", through_link.display()));
+
+    assert_eq!(
+        dir_with_slashes,
+        Path::new("/proc/self/root").join(relative_dir)
+    );
+    assert!(file_with_slash.is_err());
+}
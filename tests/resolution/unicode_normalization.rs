@@ -0,0 +1,101 @@
+#![cfg(target_os = "linux")]
+
+//! No Unicode normalization: names are compared and returned as bytes.
+//!
+//! `é` as U+00E9 (NFC) and as U+0065 U+0301 (NFD) look the same but are different
+//! names on Linux. Normalizing either would let one file be reached under the other's
+//! name, or a lookalike pass for a boundary.
+
+use crate::common::root::through_root;
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::{canonicalize, canonicalize_lexical, parse_boundary};
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::symlink;
+
+const NFC: &str = "caf\u{e9}";
+const NFD: &str = "cafe\u{301}";
+
+// ==========================================================================
+// DISTINCT FILES
+// ==========================================================================
+
+#[test]
+fn composed_and_decomposed_names_stay_distinct() {
+    let (_dir, host) = canonical_tempdir();
+    std::fs::write(host.join(NFC), b"composed").unwrap();
+    std::fs::write(host.join(NFD), b"decomposed").unwrap();
+
+    let composed = canonicalize(through_root(&host).join(NFC)).unwrap();
+    let decomposed = canonicalize(through_root(&host).join(NFD)).unwrap();
+
+    assert!(composed.as_os_str().as_bytes().ends_with(NFC.as_bytes()));
+    assert!(decomposed.as_os_str().as_bytes().ends_with(NFD.as_bytes()));
+    assert_ne!(composed, decomposed);
+    assert_eq!(std::fs::read(&composed).unwrap(), b"composed");
+    assert_eq!(std::fs::read(&decomposed).unwrap(), b"decomposed");
+}
+
+#[test]
+fn one_form_does_not_find_the_other() {
+    let (_dir, host) = canonical_tempdir();
+    std::fs::create_dir(host.join(NFC)).unwrap();
+
+    let err = canonicalize(through_root(&host).join(NFD)).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn symlink_target_bytes_are_kept() {
+    let (_dir, host) = canonical_tempdir();
+    std::fs::create_dir(host.join(NFD)).unwrap();
+    symlink(NFD, host.join("link")).unwrap();
+
+    let resolved = canonicalize(through_root(&host).join("link")).unwrap();
+
+    assert_eq!(resolved, through_root(&host).join(NFD));
+}
+
+// ==========================================================================
+// LOOKALIKE BOUNDARIES
+// ==========================================================================
+
+/// Spellings that render like a boundary but are not one byte for byte.
+const LOOKALIKES: &[&str] = &[
+    "/proc/\u{455}elf/root",       // Cyrillic dze for `s`
+    "/proc/self/r\u{43e}ot",       // Cyrillic o
+    "/proc/\u{ff11}\u{ff12}/root", // fullwidth digits
+    "/proc/self\u{200b}/root",     // zero-width space
+    "/pro\u{441}/self/root",       // Cyrillic es for `c`
+    "/proc/SELF/root",
+];
+
+#[test]
+fn lookalikes_are_not_parsed_as_boundaries() {
+    for path in LOOKALIKES {
+        assert!(parse_boundary(path).is_none(), "{path}");
+    }
+}
+
+#[test]
+fn lookalikes_resolve_as_ordinary_missing_paths() {
+    for path in LOOKALIKES {
+        let err = canonicalize(path).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::NotFound, "{path}");
+    }
+}
+
+#[test]
+fn lexical_canonicalization_keeps_both_forms() {
+    for name in [NFC, NFD] {
+        let input = format!("/proc/self/root/./{name}/x/..");
+        let lexical = canonicalize_lexical(&input).unwrap();
+
+        assert_eq!(
+            lexical.as_os_str().as_bytes(),
+            format!("/proc/self/root/{name}").as_bytes()
+        );
+    }
+}
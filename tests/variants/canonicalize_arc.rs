@@ -0,0 +1,54 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_arc`: the result of `canonicalize`, shared behind an `Arc`.
+
+use proc_canonicalize::{canonicalize, canonicalize_arc};
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+use std::sync::Arc;
+
+#[test]
+fn result_is_that_of_canonicalize() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", dir.path().join("container")).unwrap();
+
+    for input in [
+        dir.path().join("container/etc/."),
+        "/proc/self/root/usr/../etc".into(),
+        dir.path().to_path_buf(),
+    ] {
+        assert_eq!(
+            &*canonicalize_arc(&input).unwrap(),
+            canonicalize(&input).unwrap(),
+            "{input:?}"
+        );
+    }
+}
+
+#[test]
+fn clones_share_one_path_across_threads() {
+    let root = canonicalize_arc("/proc/self/root/etc").unwrap();
+
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let root = Arc::clone(&root);
+            std::thread::spawn(move || root.join("hosts"))
+        })
+        .collect();
+
+    for worker in workers {
+        assert_eq!(
+            worker.join().unwrap(),
+            Path::new("/proc/self/root/etc/hosts")
+        );
+    }
+    assert_eq!(Arc::strong_count(&root), 1);
+}
+
+#[test]
+fn missing_path_is_not_found() {
+    let err = canonicalize_arc("/proc/self/root/no/such/path").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
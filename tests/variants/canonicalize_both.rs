@@ -0,0 +1,99 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_both`: the preserved and host forms from a single resolution.
+
+use crate::common::cwd::CwdProcess;
+use proc_canonicalize::{canonicalize, canonicalize_both, to_host};
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+// ==========================================================================
+// BOTH FORMS MATCH THEIR SINGLE-FORM FUNCTIONS
+// ==========================================================================
+
+#[test]
+fn forms_match_canonicalize_and_to_host() {
+    let process = CwdProcess::spawn();
+    std::fs::create_dir(process.dir().join("data")).unwrap();
+    let path = process.cwd_boundary().join("data/../data");
+
+    let resolved = canonicalize_both(&path).unwrap();
+
+    assert_eq!(resolved.namespace(), canonicalize(&path).unwrap());
+    assert_eq!(resolved.host(), to_host(&path).unwrap());
+    assert_eq!(resolved.namespace(), process.cwd_boundary().join("data"));
+}
+
+#[test]
+fn root_subpath_keeps_prefix_only_in_namespace_form() {
+    let resolved = canonicalize_both("/proc/self/root/etc").unwrap();
+
+    assert_eq!(resolved.namespace(), Path::new("/proc/self/root/etc"));
+    assert_eq!(resolved.host(), std::fs::canonicalize("/etc").unwrap());
+}
+
+#[test]
+fn bare_boundary_host_form_is_its_target() {
+    let process = CwdProcess::spawn();
+
+    let (namespace, host) = canonicalize_both(process.cwd_boundary())
+        .unwrap()
+        .into_parts();
+
+    assert_eq!(namespace, process.cwd_boundary());
+    assert_eq!(host, std::fs::canonicalize(process.dir()).unwrap());
+}
+
+#[test]
+fn indirect_link_to_boundary_has_both_forms() {
+    let links = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", links.path().join("host_root")).unwrap();
+
+    let resolved = canonicalize_both(links.path().join("host_root/etc")).unwrap();
+
+    assert_eq!(resolved.namespace(), Path::new("/proc/self/root/etc"));
+    assert_eq!(resolved.host(), std::fs::canonicalize("/etc").unwrap());
+}
+
+// ==========================================================================
+// PATHS WITH ONLY ONE FORM
+// ==========================================================================
+
+#[test]
+fn plain_path_has_equal_forms() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let resolved = canonicalize_both(dir.path()).unwrap();
+
+    assert_eq!(resolved.namespace(), resolved.host());
+    assert_eq!(resolved.host(), std::fs::canonicalize(dir.path()).unwrap());
+}
+
+#[test]
+fn escape_to_host_has_equal_forms() {
+    let process = CwdProcess::spawn();
+    let path = process.cwd_boundary().join("..");
+
+    let resolved = canonicalize_both(path).unwrap();
+
+    let parent = std::fs::canonicalize(process.dir().join("..")).unwrap();
+    assert_eq!(resolved.namespace(), parent);
+    assert_eq!(resolved.host(), parent);
+}
+
+#[test]
+fn namespace_link_is_its_own_host_form() {
+    for path in ["/proc/self/ns/net", "/proc/self/task/../ns/mnt"] {
+        let resolved = canonicalize_both(path).unwrap();
+
+        assert_eq!(resolved.namespace(), canonicalize(path).unwrap(), "{path}");
+        assert_eq!(resolved.host(), resolved.namespace(), "{path}");
+    }
+}
+
+#[test]
+fn missing_target_fails_like_canonicalize() {
+    let err = canonicalize_both("/proc/self/root/no/such/path").unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
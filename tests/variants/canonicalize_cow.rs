@@ -0,0 +1,50 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_cow` agrees with `canonicalize` for borrowed and owned input,
+//! including scans that follow several symlinks before reaching `/proc`.
+
+use proc_canonicalize::{canonicalize, canonicalize_cow};
+use std::borrow::Cow;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+#[test]
+fn borrowed_plain_path_matches_canonicalize() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("./");
+
+    assert_eq!(
+        canonicalize_cow(Cow::Borrowed(&path)).unwrap(),
+        canonicalize(&path).unwrap()
+    );
+}
+
+#[test]
+fn owned_boundary_path_keeps_prefix() {
+    let resolved = canonicalize_cow(Cow::Owned("/proc/self/root/etc".into())).unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+}
+
+#[test]
+fn borrowed_path_through_chained_links_into_proc_keeps_prefix() {
+    let temp = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", temp.path().join("container")).unwrap();
+    symlink("container", temp.path().join("hop1")).unwrap();
+    symlink("hop1", temp.path().join("hop2")).unwrap();
+    let path = temp.path().join("hop2/etc");
+
+    let resolved = canonicalize_cow(Cow::Borrowed(&path)).unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+}
+
+#[test]
+fn relative_path_is_resolved_against_cwd() {
+    let cwd = std::env::current_dir().unwrap();
+
+    assert_eq!(
+        canonicalize_cow(Cow::Borrowed(Path::new("."))).unwrap(),
+        std::fs::canonicalize(cwd).unwrap()
+    );
+}
@@ -0,0 +1,136 @@
+//! `canonicalize_dir` and `canonicalize_file`: canonicalize plus a kind check.
+
+use proc_canonicalize::{canonicalize, canonicalize_dir, canonicalize_file};
+use std::io;
+
+#[test]
+fn dir_accepts_directory() {
+    let dir = tempfile::tempdir().unwrap();
+
+    assert_eq!(
+        canonicalize_dir(dir.path()).unwrap(),
+        canonicalize(dir.path()).unwrap()
+    );
+}
+
+#[test]
+fn dir_rejects_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("file");
+    std::fs::write(&file, "").unwrap();
+
+    let err = canonicalize_dir(&file).unwrap_err();
+
+    #[cfg(unix)]
+    assert_eq!(err.raw_os_error(), Some(20)); // ENOTDIR
+    #[cfg(not(unix))]
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn dir_reports_missing_path_as_not_found() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = canonicalize_dir(dir.path().join("missing")).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn dir_rejects_empty_path() {
+    assert!(canonicalize_dir("").is_err());
+}
+
+#[test]
+fn file_accepts_regular_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("file");
+    std::fs::write(&file, "").unwrap();
+
+    assert_eq!(
+        canonicalize_file(&file).unwrap(),
+        canonicalize(&file).unwrap()
+    );
+}
+
+#[test]
+fn file_rejects_directory() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = canonicalize_file(dir.path()).unwrap_err();
+
+    #[cfg(unix)]
+    assert_eq!(err.raw_os_error(), Some(21)); // EISDIR
+    #[cfg(not(unix))]
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use std::path::Path;
+
+    #[test]
+    fn dir_preserves_boundary_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let through_root = format!("/proc/self/root{}", dir.path().display());
+
+        assert_eq!(
+            canonicalize_dir(&through_root).unwrap(),
+            Path::new(&through_root)
+        );
+        assert_eq!(
+            canonicalize_dir("/proc/self/cwd").unwrap(),
+            Path::new("/proc/self/cwd")
+        );
+    }
+
+    #[test]
+    fn dir_rejects_file_through_boundary() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("file"), "").unwrap();
+        let through_root = format!("/proc/self/root{}/file", dir.path().display());
+
+        let err = canonicalize_dir(through_root).unwrap_err();
+
+        assert_eq!(err.raw_os_error(), Some(20)); // ENOTDIR
+    }
+
+    #[test]
+    fn dir_follows_indirect_symlink_to_boundary() {
+        let dir = tempfile::tempdir().unwrap();
+        let link = dir.path().join("container");
+        symlink("/proc/self/root", &link).unwrap();
+
+        assert_eq!(
+            canonicalize_dir(&link).unwrap(),
+            Path::new("/proc/self/root")
+        );
+    }
+
+    #[test]
+    fn namespace_link_counts_as_file() {
+        // nsfs inodes report as regular files, and opening one is how setns(2) is used.
+        assert!(canonicalize_dir("/proc/self/ns/net").is_err());
+        assert_eq!(
+            canonicalize_file("/proc/self/ns/net").unwrap(),
+            Path::new("/proc/self/ns/net")
+        );
+    }
+
+    #[test]
+    fn file_rejects_fifo() {
+        let dir = tempfile::tempdir().unwrap();
+        let fifo = dir.path().join("fifo");
+        let status = std::process::Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let err = canonicalize_file(&fifo).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
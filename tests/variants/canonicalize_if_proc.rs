@@ -0,0 +1,48 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_if_proc`: namespace-aware for direct boundary paths, plain
+//! `std::fs::canonicalize` for everything else.
+
+use proc_canonicalize::{canonicalize, canonicalize_if_proc};
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+#[test]
+fn direct_boundary_path_keeps_prefix() {
+    let resolved = canonicalize_if_proc("/proc/self/root/etc/../tmp").unwrap();
+
+    assert_eq!(
+        resolved,
+        canonicalize("/proc/self/root/etc/../tmp").unwrap()
+    );
+    assert!(resolved.starts_with("/proc/self/root"));
+}
+
+#[test]
+fn ordinary_path_matches_std() {
+    let temp = tempfile::tempdir().unwrap();
+    std::fs::create_dir(temp.path().join("dir")).unwrap();
+    let path = temp.path().join("dir/./");
+
+    assert_eq!(
+        canonicalize_if_proc(&path).unwrap(),
+        std::fs::canonicalize(&path).unwrap()
+    );
+}
+
+#[test]
+fn indirect_link_into_proc_is_not_detected() {
+    let temp = tempfile::tempdir().unwrap();
+    let link = temp.path().join("container");
+    symlink("/proc/self/root", &link).unwrap();
+
+    assert_eq!(canonicalize_if_proc(&link).unwrap(), Path::new("/"));
+    assert_eq!(canonicalize(&link).unwrap(), Path::new("/proc/self/root"));
+}
+
+#[test]
+fn missing_ordinary_path_reports_not_found() {
+    let err = canonicalize_if_proc("/nonexistent/proc-canonicalize/path").unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
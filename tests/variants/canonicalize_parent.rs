@@ -0,0 +1,69 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_parent`: the canonical directory around a path that may not exist yet.
+
+use crate::common::root::through_root;
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::canonicalize_parent;
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+#[test]
+fn missing_file_under_root_boundary_has_prefixed_parent() {
+    let (_temp, host) = canonical_tempdir();
+    let inside = through_root(&host);
+
+    let parent = canonicalize_parent(inside.join("new.conf")).unwrap();
+
+    assert_eq!(parent, inside);
+}
+
+#[test]
+fn parent_through_indirect_link_keeps_prefix() {
+    let temp = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", temp.path().join("container")).unwrap();
+
+    let parent = canonicalize_parent(temp.path().join("container/etc/new.conf")).unwrap();
+
+    assert_eq!(parent, Path::new("/proc/self/root/etc"));
+}
+
+#[test]
+fn bare_file_name_has_cwd_as_parent() {
+    let cwd = std::fs::canonicalize(std::env::current_dir().unwrap()).unwrap();
+
+    assert_eq!(canonicalize_parent("not-created-yet").unwrap(), cwd);
+}
+
+#[test]
+fn boundary_itself_has_plain_proc_parent() {
+    let parent = canonicalize_parent("/proc/self/root").unwrap();
+
+    assert_eq!(parent, std::fs::canonicalize("/proc/self").unwrap());
+}
+
+#[test]
+fn paths_without_final_name_are_rejected() {
+    for path in ["/", "/proc/self/root/etc/..", ".."] {
+        let err = canonicalize_parent(path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{path}");
+    }
+}
+
+#[test]
+fn missing_parent_is_not_found() {
+    let err = canonicalize_parent("/proc/self/root/nonexistent-dir/file").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn parent_that_is_a_file_is_rejected() {
+    let temp = tempfile::tempdir().unwrap();
+    std::fs::write(temp.path().join("file"), b"").unwrap();
+
+    let err = canonicalize_parent(temp.path().join("file/child")).unwrap_err();
+
+    assert_eq!(err.raw_os_error(), Some(20));
+}
@@ -0,0 +1,121 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_sanitized`: existence-revealing failures collapse into one opaque
+//! error, with the real one kept for the server.
+
+use proc_canonicalize::{canonicalize, canonicalize_sanitized, Inaccessible, ProcessGone};
+use std::io;
+use std::os::unix::fs::{symlink, PermissionsExt};
+use std::path::Path;
+
+fn inaccessible(err: &io::Error) -> &Inaccessible {
+    err.get_ref()
+        .and_then(|e| e.downcast_ref::<Inaccessible>())
+        .unwrap()
+}
+
+// ==========================================================================
+// COLLAPSED
+// ==========================================================================
+
+#[test]
+fn not_found_is_opaque() {
+    let err = canonicalize_sanitized("/proc/self/root/no/such/file").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    assert_eq!(err.to_string(), "path is not accessible");
+    assert_eq!(
+        inaccessible(&err).original().kind(),
+        io::ErrorKind::NotFound
+    );
+}
+
+#[test]
+fn permission_denied_is_opaque() {
+    let dir = tempfile::tempdir().unwrap();
+    let locked = dir.path().join("locked");
+    std::fs::create_dir_all(locked.join("secret")).unwrap();
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+    let path = format!("/proc/self/root{}/secret", locked.display());
+    let denied = canonicalize(&path).is_err();
+
+    let result = canonicalize_sanitized(&path);
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    if !denied {
+        // Running as root: mode bits are not enforced, so there is nothing to hide.
+        return;
+    }
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "path is not accessible");
+    assert_eq!(
+        inaccessible(&err).original().kind(),
+        io::ErrorKind::PermissionDenied
+    );
+}
+
+#[test]
+fn not_a_directory_is_opaque() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("file"), "").unwrap();
+    let path = format!("/proc/self/root{}/file/child", dir.path().display());
+
+    let err = canonicalize_sanitized(path).unwrap_err();
+
+    assert_eq!(err.to_string(), "path is not accessible");
+    assert_eq!(inaccessible(&err).original().raw_os_error(), Some(20));
+}
+
+#[test]
+fn dead_pid_is_opaque_but_kept_for_the_server() {
+    let err = canonicalize_sanitized("/proc/99999999/root/etc").unwrap_err();
+
+    let original = inaccessible(&err).original();
+    assert!(original
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<ProcessGone>())
+        .is_some());
+    assert!(!err.to_string().contains("99999999"));
+}
+
+#[test]
+fn different_causes_are_indistinguishable_to_the_tenant() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("file"), "").unwrap();
+
+    let missing = canonicalize_sanitized(dir.path().join("missing/child")).unwrap_err();
+    let not_dir = canonicalize_sanitized(dir.path().join("file/child")).unwrap_err();
+
+    assert_eq!(missing.kind(), not_dir.kind());
+    assert_eq!(missing.to_string(), not_dir.to_string());
+}
+
+// ==========================================================================
+// UNCHANGED
+// ==========================================================================
+
+#[test]
+fn success_matches_canonicalize() {
+    assert_eq!(
+        canonicalize_sanitized("/proc/self/root/etc").unwrap(),
+        Path::new("/proc/self/root/etc")
+    );
+}
+
+#[test]
+fn symlink_loop_is_returned_as_is() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("loop", dir.path().join("loop")).unwrap();
+
+    let err = canonicalize_sanitized(dir.path().join("loop")).unwrap_err();
+
+    assert!(err.get_ref().is_none());
+    assert_eq!(err.raw_os_error(), Some(40));
+}
+
+#[test]
+fn nul_byte_is_returned_as_is() {
+    let err = canonicalize_sanitized("/proc/self/root/a\0b").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
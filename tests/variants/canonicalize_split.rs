@@ -0,0 +1,111 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_split`: the boundary and a relative, symlink-free subpath below it.
+
+use crate::common::cwd::CwdProcess;
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::{canonicalize, canonicalize_split};
+use std::os::unix::fs::symlink;
+use std::path::{Component, Path};
+
+// ==========================================================================
+// UNDER A BOUNDARY
+// ==========================================================================
+
+#[test]
+fn subpath_is_relative_and_normalized() {
+    let (boundary, subpath) = canonicalize_split("/proc/self/root/./etc/../etc/").unwrap();
+
+    assert_eq!(boundary.as_deref(), Some(Path::new("/proc/self/root")));
+    assert_eq!(subpath, Path::new("etc"));
+    assert!(subpath.is_relative());
+}
+
+#[test]
+fn subpath_has_symlinks_resolved() {
+    let (_dir, host) = canonical_tempdir();
+    std::fs::create_dir(host.join("real")).unwrap();
+    symlink("real", host.join("alias")).unwrap();
+    let inside = host.strip_prefix("/").unwrap();
+
+    let (boundary, subpath) =
+        canonicalize_split(Path::new("/proc/self/root").join(inside).join("alias")).unwrap();
+
+    assert_eq!(boundary.as_deref(), Some(Path::new("/proc/self/root")));
+    assert_eq!(subpath, inside.join("real"));
+    assert!(subpath
+        .components()
+        .all(|component| matches!(component, Component::Normal(_))));
+}
+
+#[test]
+fn bare_boundary_has_an_empty_subpath() {
+    for (input, expected) in [
+        ("/proc/self/root", "/proc/self/root"),
+        ("/proc/self/root/etc/..", "/proc/self/root"),
+        ("/proc/self/ns/net", "/proc/self/ns/net"),
+    ] {
+        let (boundary, subpath) = canonicalize_split(input).unwrap();
+
+        assert_eq!(boundary.as_deref(), Some(Path::new(expected)), "{input}");
+        assert_eq!(subpath.as_os_str(), "", "{input}");
+    }
+}
+
+#[test]
+fn boundary_reached_through_a_symlink_is_split_off() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", dir.path().join("root")).unwrap();
+
+    let (boundary, subpath) = canonicalize_split(dir.path().join("root/etc")).unwrap();
+
+    assert_eq!(boundary.as_deref(), Some(Path::new("/proc/self/root")));
+    assert_eq!(subpath, Path::new("etc"));
+}
+
+#[test]
+fn parts_join_back_to_canonicalize() {
+    for input in [
+        "/proc/self/root/etc",
+        "/proc/self/root/usr/./lib/..",
+        "/proc/thread-self/root/tmp",
+        "/etc",
+    ] {
+        let joined = match canonicalize_split(input).unwrap() {
+            (Some(boundary), subpath) => boundary.join(subpath),
+            (None, host) => host,
+        };
+
+        assert_eq!(joined, canonicalize(input).unwrap(), "{input}");
+    }
+}
+
+// ==========================================================================
+// NO BOUNDARY
+// ==========================================================================
+
+#[test]
+fn path_without_a_boundary_is_the_host_path() {
+    let (boundary, host) = canonicalize_split("/etc/./").unwrap();
+
+    assert_eq!(boundary, None);
+    assert_eq!(host, std::fs::canonicalize("/etc").unwrap());
+}
+
+#[test]
+fn escaped_path_is_the_host_path() {
+    let process = CwdProcess::spawn();
+    let parent = std::fs::canonicalize(process.dir().parent().unwrap()).unwrap();
+
+    let (boundary, host) = canonicalize_split(process.cwd_boundary().join("..")).unwrap();
+
+    assert_eq!(boundary, None);
+    assert_eq!(host, parent);
+}
+
+#[test]
+fn errors_are_those_of_canonicalize() {
+    let err = canonicalize_split("/proc/self/root/no/such/entry").unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
@@ -0,0 +1,76 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_stable`: results with process and thread IDs replaced by placeholders.
+
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::canonicalize_stable;
+use std::os::unix::fs::symlink;
+
+#[test]
+fn every_selector_renders_the_same() {
+    let pid = std::process::id();
+    let inputs = [
+        "/proc/self/root/etc".to_string(),
+        "/proc/thread-self/root/etc".to_string(),
+        format!("/proc/{pid}/root/etc"),
+        format!("/proc/{pid}/root/usr/../etc/"),
+    ];
+
+    for input in &inputs {
+        assert_eq!(
+            canonicalize_stable(input).unwrap(),
+            "/proc/<PID>/root/etc",
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn task_boundary_renders_the_thread_placeholder() {
+    let pid = std::process::id();
+
+    assert_eq!(
+        canonicalize_stable(format!("/proc/{pid}/task/{pid}/root/etc")).unwrap(),
+        "/proc/<PID>/task/<TID>/root/etc"
+    );
+}
+
+#[test]
+fn bare_boundary_and_namespace_link_render_without_remainder() {
+    assert_eq!(
+        canonicalize_stable("/proc/self/root").unwrap(),
+        "/proc/<PID>/root"
+    );
+    assert_eq!(
+        canonicalize_stable("/proc/self/ns/net").unwrap(),
+        "/proc/<PID>/ns/net"
+    );
+}
+
+#[test]
+fn boundary_reached_through_a_symlink_is_rendered_too() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("/proc/self/root", dir.path().join("root")).unwrap();
+
+    assert_eq!(
+        canonicalize_stable(dir.path().join("root/etc")).unwrap(),
+        "/proc/<PID>/root/etc"
+    );
+}
+
+#[test]
+fn result_without_a_boundary_is_written_as_is() {
+    let (dir, host) = canonical_tempdir();
+
+    assert_eq!(
+        canonicalize_stable(dir.path()).unwrap(),
+        host.display().to_string()
+    );
+}
+
+#[test]
+fn errors_are_those_of_canonicalize() {
+    let err = canonicalize_stable("/proc/self/root/no/such/file").unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
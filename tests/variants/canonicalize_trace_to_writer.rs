@@ -0,0 +1,102 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_trace_to_writer`: same result as `canonicalize`, with the steps
+//! written out as text.
+
+use proc_canonicalize::{canonicalize, canonicalize_trace_to_writer, canonicalize_verbose};
+use std::io::{self, Write};
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+fn trace(path: impl AsRef<Path>) -> (io::Result<std::path::PathBuf>, String) {
+    let mut out = Vec::new();
+    let resolved = canonicalize_trace_to_writer(path, &mut out);
+    (resolved, String::from_utf8(out).unwrap())
+}
+
+/// A writer that refuses every write.
+struct Broken;
+
+impl Write for Broken {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::BrokenPipe.into())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Err(io::ErrorKind::BrokenPipe.into())
+    }
+}
+
+// ==========================================================================
+// OUTPUT
+// ==========================================================================
+
+#[test]
+fn boundary_path_lists_numbered_steps_and_result() {
+    let (resolved, text) = trace("/proc/self/root/etc");
+
+    assert_eq!(resolved.unwrap(), Path::new("/proc/self/root/etc"));
+    assert_eq!(
+        text,
+        "resolving /proc/self/root/etc\n\
+         \x20 1. detected boundary /proc/self/root\n\
+         \x20 2. called std::fs::canonicalize on /proc/self/root\n\
+         \x20 3. called std::fs::canonicalize on /proc/self/root/etc\n\
+         result: /proc/self/root/etc\n"
+    );
+}
+
+#[test]
+fn symlink_hops_are_shown() {
+    let dir = tempfile::tempdir().unwrap();
+    let link = dir.path().join("container");
+    symlink("/proc/self/root", &link).unwrap();
+
+    let (_, text) = trace(link.join("etc"));
+
+    assert!(
+        text.contains(&format!(
+            "followed symlink {} -> /proc/self/root",
+            link.display()
+        )),
+        "{text}"
+    );
+}
+
+#[test]
+fn one_line_per_verbose_step() {
+    let (_, steps) = canonicalize_verbose("/proc/self/cwd/..").unwrap();
+    let (_, text) = trace("/proc/self/cwd/..");
+
+    for (number, step) in steps.iter().enumerate() {
+        assert!(
+            text.contains(&format!("  {}. {step}\n", number + 1)),
+            "{text}"
+        );
+    }
+    assert_eq!(text.lines().count(), steps.len() + 2);
+}
+
+// ==========================================================================
+// ERRORS
+// ==========================================================================
+
+#[test]
+fn steps_are_written_before_a_failure() {
+    let (resolved, text) = trace("/proc/self/root/no/such/entry");
+
+    let err = resolved.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(
+        text.contains("1. detected boundary /proc/self/root\n"),
+        "{text}"
+    );
+    assert!(text.ends_with(&format!("error: {err}\n")), "{text}");
+}
+
+#[test]
+fn broken_writer_does_not_change_the_result() {
+    let resolved = canonicalize_trace_to_writer("/proc/self/root/etc", &mut Broken).unwrap();
+
+    assert_eq!(resolved, canonicalize("/proc/self/root/etc").unwrap());
+}
@@ -0,0 +1,91 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_verbose`: same result as `canonicalize`, plus the steps taken.
+
+use proc_canonicalize::{canonicalize, canonicalize_verbose, Step};
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+fn path(p: impl Into<PathBuf>) -> PathBuf {
+    p.into()
+}
+
+#[test]
+fn plain_path_is_a_single_std_call() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let (resolved, steps) = canonicalize_verbose(dir.path()).unwrap();
+
+    assert_eq!(resolved, canonicalize(dir.path()).unwrap());
+    assert_eq!(steps, [Step::CalledStdCanonicalize(path(dir.path()))]);
+}
+
+#[test]
+fn bare_boundary_is_detected_without_std_call() {
+    let (resolved, steps) = canonicalize_verbose("/proc/self/root").unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root"));
+    assert_eq!(steps, [Step::DetectedBoundary(path("/proc/self/root"))]);
+}
+
+#[test]
+fn boundary_subpath_lists_prefix_and_full_resolution() {
+    let (_, steps) = canonicalize_verbose("/proc/self/root/etc").unwrap();
+
+    assert_eq!(
+        steps,
+        [
+            Step::DetectedBoundary(path("/proc/self/root")),
+            Step::CalledStdCanonicalize(path("/proc/self/root")),
+            Step::CalledStdCanonicalize(path("/proc/self/root/etc")),
+        ]
+    );
+}
+
+#[test]
+fn indirect_symlink_is_followed_then_boundary_detected() {
+    let dir = tempfile::tempdir().unwrap();
+    let link = dir.path().join("container");
+    symlink("/proc/self/root", &link).unwrap();
+
+    let (resolved, steps) = canonicalize_verbose(&link).unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root"));
+    assert_eq!(
+        steps.last(),
+        Some(&Step::DetectedBoundary(path("/proc/self/root")))
+    );
+    assert!(steps.contains(&Step::FollowedSymlink {
+        from: link,
+        to: path("/proc/self/root"),
+    }));
+}
+
+#[test]
+fn dotdot_in_prefix_shows_normalization() {
+    let (resolved, steps) = canonicalize_verbose("/proc/self/../self/root").unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root"));
+    assert_eq!(
+        steps,
+        [
+            Step::Normalized {
+                before: path("/proc/self/../self/root"),
+                after: path("/proc/self/root"),
+            },
+            Step::DetectedBoundary(path("/proc/self/root")),
+        ]
+    );
+}
+
+#[test]
+fn error_matches_canonicalize() {
+    let err = canonicalize_verbose("/nonexistent/verbose/path").unwrap_err();
+
+    assert_eq!(
+        err.kind(),
+        canonicalize("/nonexistent/verbose/path")
+            .unwrap_err()
+            .kind()
+    );
+}
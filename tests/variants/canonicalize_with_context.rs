@@ -0,0 +1,78 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_with_context`: errors that say which input failed.
+
+use proc_canonicalize::{canonicalize, canonicalize_with_context, CanonicalizeFailed, ProcessGone};
+use std::error::Error;
+use std::io;
+use std::path::Path;
+
+fn failed(err: &io::Error) -> &CanonicalizeFailed {
+    err.get_ref()
+        .and_then(|inner| inner.downcast_ref::<CanonicalizeFailed>())
+        .unwrap()
+}
+
+#[test]
+fn success_is_the_same_as_canonicalize() {
+    for path in ["/proc/self/root/etc", "/etc/."] {
+        assert_eq!(
+            canonicalize_with_context(path).unwrap(),
+            canonicalize(path).unwrap()
+        );
+    }
+}
+
+#[test]
+fn error_names_the_input_as_given() {
+    let err = canonicalize_with_context("/proc/self/root/./no/such/x").unwrap_err();
+    let original = canonicalize("/proc/self/root/./no/such/x").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(
+        failed(&err).path(),
+        Path::new("/proc/self/root/./no/such/x")
+    );
+    assert_eq!(
+        err.to_string(),
+        format!("failed to canonicalize \"/proc/self/root/./no/such/x\": {original}")
+    );
+}
+
+#[test]
+fn kind_and_inner_payload_are_kept() {
+    let err = canonicalize_with_context("/proc/4294967295/root/x").unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    let source = failed(&err).source().unwrap();
+    assert!(source
+        .downcast_ref::<io::Error>()
+        .and_then(|inner| inner.get_ref())
+        .is_some_and(|payload| payload.is::<ProcessGone>()));
+}
+
+#[test]
+fn errors_from_a_batch_can_be_attributed() {
+    let inputs = ["/proc/self/root/etc", "/proc/self/root/nope", "", "/etc"];
+
+    let failures: Vec<_> = inputs
+        .iter()
+        .filter_map(|path| canonicalize_with_context(path).err())
+        .map(|err| failed(&err).path().to_path_buf())
+        .collect();
+
+    assert_eq!(failures, [Path::new("/proc/self/root/nope"), Path::new("")]);
+}
+
+#[test]
+fn into_original_gives_back_the_underlying_error() {
+    let err = canonicalize_with_context("").unwrap_err();
+    let inner = err.into_inner().unwrap();
+
+    let original = inner
+        .downcast::<CanonicalizeFailed>()
+        .unwrap()
+        .into_original();
+
+    assert_eq!(original.kind(), io::ErrorKind::NotFound);
+}
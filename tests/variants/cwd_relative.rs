@@ -0,0 +1,54 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_cwd_relative` snapshots the cwd into a plain host path, while
+//! `canonicalize("/proc/self/cwd/...")` keeps the live `/proc/self/cwd` prefix.
+
+use proc_canonicalize::{canonicalize, canonicalize_cwd_relative};
+use std::path::Path;
+
+#[test]
+fn relative_path_resolves_to_plain_host_path() {
+    let subdir = tempfile::tempdir_in(".").unwrap();
+    let subdir_name = subdir.path().file_name().unwrap();
+
+    let snapshot = canonicalize_cwd_relative(subdir_name).unwrap();
+
+    let host_subdir = std::fs::canonicalize(subdir.path()).unwrap();
+    assert_eq!(snapshot, host_subdir);
+    assert!(!snapshot.starts_with("/proc"));
+}
+
+#[test]
+fn same_file_through_proc_self_cwd_keeps_magic_prefix() {
+    let subdir = tempfile::tempdir_in(".").unwrap();
+    let subdir_name = subdir.path().file_name().unwrap().to_str().unwrap();
+
+    let snapshot = canonicalize_cwd_relative(subdir_name).unwrap();
+    let live_view = canonicalize(format!("/proc/self/cwd/{subdir_name}")).unwrap();
+
+    assert_eq!(live_view, Path::new("/proc/self/cwd").join(subdir_name));
+    assert_ne!(snapshot, live_view);
+}
+
+#[test]
+fn dot_resolves_to_current_directory() {
+    let cwd = std::fs::canonicalize(std::env::current_dir().unwrap()).unwrap();
+
+    assert_eq!(canonicalize_cwd_relative(".").unwrap(), cwd);
+}
+
+#[test]
+fn absolute_path_is_resolved_as_is() {
+    let temp = std::env::temp_dir();
+
+    let resolved = canonicalize_cwd_relative(&temp).unwrap();
+
+    assert_eq!(resolved, std::fs::canonicalize(&temp).unwrap());
+}
+
+#[test]
+fn absolute_boundary_path_is_still_preserved() {
+    let resolved = canonicalize_cwd_relative("/proc/self/root/etc").unwrap();
+
+    assert_eq!(resolved, Path::new("/proc/self/root/etc"));
+}
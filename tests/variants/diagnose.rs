@@ -0,0 +1,129 @@
+#![cfg(target_os = "linux")]
+
+//! `diagnose`: a status for every component, without stopping at the first problem.
+
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::{diagnose, ComponentStatus};
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+/// The statuses reported for components below `base`.
+fn statuses_below(base: &Path, path: &Path) -> Vec<(PathBuf, ComponentStatus)> {
+    diagnose(path)
+        .into_iter()
+        .filter(|(prefix, _)| prefix.starts_with(base) && prefix != base)
+        .collect()
+}
+
+#[test]
+fn existing_path_reports_every_component_exists() {
+    let (_temp, host) = canonical_tempdir();
+    std::fs::create_dir_all(host.join("a/b")).unwrap();
+
+    let report = diagnose(host.join("a/b"));
+
+    assert!(report
+        .iter()
+        .all(|(_, status)| *status == ComponentStatus::Exists));
+    assert_eq!(report.len(), host.join("a/b").components().count() - 1);
+}
+
+#[test]
+fn reporting_continues_past_missing_component() {
+    let (_temp, host) = canonical_tempdir();
+    std::fs::create_dir(host.join("a")).unwrap();
+
+    let report = statuses_below(&host, &host.join("a/missing/deeper"));
+
+    assert_eq!(
+        report,
+        [
+            (host.join("a"), ComponentStatus::Exists),
+            (host.join("a/missing"), ComponentStatus::Missing),
+            (host.join("a/missing/deeper"), ComponentStatus::Missing),
+        ]
+    );
+}
+
+#[test]
+fn symlinks_report_raw_targets_and_broken_ones_are_flagged() {
+    let (_temp, host) = canonical_tempdir();
+    std::fs::create_dir(host.join("real")).unwrap();
+    symlink("real", host.join("good")).unwrap();
+    symlink("nowhere", host.join("broken")).unwrap();
+
+    assert_eq!(
+        statuses_below(&host, &host.join("good")),
+        [(host.join("good"), ComponentStatus::Symlink("real".into()))]
+    );
+    assert_eq!(
+        statuses_below(&host, &host.join("broken")),
+        [(
+            host.join("broken"),
+            ComponentStatus::BrokenSymlink("nowhere".into())
+        )]
+    );
+}
+
+#[test]
+fn boundary_is_reported_as_magic() {
+    let report = diagnose("/proc/self/root/etc");
+
+    assert_eq!(
+        report,
+        [
+            (PathBuf::from("/proc"), ComponentStatus::Exists),
+            (
+                PathBuf::from("/proc/self"),
+                ComponentStatus::Symlink(std::process::id().to_string().into())
+            ),
+            (
+                PathBuf::from("/proc/self/root"),
+                ComponentStatus::MagicBoundary
+            ),
+            (
+                PathBuf::from("/proc/self/root/etc"),
+                ComponentStatus::Exists
+            ),
+        ]
+    );
+}
+
+#[test]
+fn namespace_link_is_reported_as_magic() {
+    let report = diagnose("/proc/self/ns/net");
+
+    assert_eq!(report.last().unwrap().1, ComponentStatus::MagicBoundary);
+}
+
+#[test]
+fn file_used_as_directory_is_an_error_not_missing() {
+    let (_temp, host) = canonical_tempdir();
+    std::fs::write(host.join("file"), b"").unwrap();
+
+    let report = statuses_below(&host, &host.join("file/child"));
+
+    assert_eq!(report[0].1, ComponentStatus::Exists);
+    assert!(matches!(report[1].1, ComponentStatus::Error(_)));
+}
+
+#[test]
+fn unsearchable_directory_reports_permission_denied() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (_temp, host) = canonical_tempdir();
+    std::fs::create_dir_all(host.join("locked/inner")).unwrap();
+    std::fs::set_permissions(host.join("locked"), std::fs::Permissions::from_mode(0o000)).unwrap();
+
+    // Root (CAP_DAC_OVERRIDE) ignores permission bits; only check where they apply.
+    let enforced = std::fs::symlink_metadata(host.join("locked/inner")).is_err();
+    let report = statuses_below(&host, &host.join("locked/inner"));
+    std::fs::set_permissions(host.join("locked"), std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    assert_eq!(report[0].1, ComponentStatus::Exists);
+    if enforced {
+        assert_eq!(report[1].1, ComponentStatus::PermissionDenied);
+    } else {
+        assert_eq!(report[1].1, ComponentStatus::Exists);
+    }
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "fs")]
+
+//! The `canonicalize_*` entry points: each module covers one variant and how it
+//! relates to `canonicalize`.
+
+#[cfg(target_os = "linux")]
+#[path = "../common"]
+mod common {
+    pub mod cwd;
+    pub mod root;
+    pub mod temp;
+}
+
+mod canonicalize_arc;
+mod canonicalize_both;
+mod canonicalize_cow;
+mod canonicalize_dir_file;
+mod canonicalize_if_proc;
+mod canonicalize_parent;
+mod canonicalize_sanitized;
+mod canonicalize_split;
+mod canonicalize_stable;
+mod canonicalize_trace_to_writer;
+mod canonicalize_verbose;
+mod canonicalize_with_context;
+mod cwd_relative;
+mod diagnose;
+mod reject_symlinks;
+mod scratch;
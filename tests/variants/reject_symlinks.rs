@@ -0,0 +1,116 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_reject_symlinks`: only the leading `/proc` boundary may be a link.
+
+use crate::common::root::through_root;
+use crate::common::temp::canonical_tempdir;
+use proc_canonicalize::canonicalize_reject_symlinks;
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+fn assert_rejected(path: impl AsRef<Path>) {
+    let err = canonicalize_reject_symlinks(path.as_ref()).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        io::ErrorKind::InvalidInput,
+        "{}",
+        path.as_ref().display()
+    );
+}
+
+// ==========================================================================
+// ACCEPTED
+// ==========================================================================
+
+#[test]
+fn plain_directories_resolve() {
+    let (_temp, host) = canonical_tempdir();
+    std::fs::create_dir_all(host.join("a/b")).unwrap();
+
+    let resolved = canonicalize_reject_symlinks(host.join("a/./b/../b")).unwrap();
+
+    assert_eq!(resolved, host.join("a/b"));
+}
+
+#[test]
+fn leading_boundary_links_are_allowed() {
+    for path in [
+        "/proc/self/root",
+        "/proc/self/root/etc",
+        "/proc/thread-self/cwd",
+    ] {
+        assert_eq!(
+            canonicalize_reject_symlinks(path).unwrap(),
+            proc_canonicalize::canonicalize(path).unwrap(),
+            "{path}"
+        );
+    }
+}
+
+// ==========================================================================
+// REJECTED
+// ==========================================================================
+
+#[test]
+fn final_component_symlink_is_rejected() {
+    let (_temp, host) = canonical_tempdir();
+    std::fs::create_dir(host.join("real")).unwrap();
+    symlink("real", host.join("alias")).unwrap();
+
+    assert_rejected(host.join("alias"));
+}
+
+#[test]
+fn intermediate_symlink_is_rejected() {
+    let (_temp, host) = canonical_tempdir();
+    std::fs::create_dir_all(host.join("real/sub")).unwrap();
+    symlink("real", host.join("alias")).unwrap();
+
+    assert_rejected(host.join("alias/sub"));
+}
+
+#[test]
+fn symlink_inside_boundary_remainder_is_rejected() {
+    let (_temp, host) = canonical_tempdir();
+    std::fs::create_dir(host.join("real")).unwrap();
+    symlink("real", host.join("alias")).unwrap();
+    let inside = through_root(&host);
+
+    assert_rejected(inside.join("alias"));
+    assert!(canonicalize_reject_symlinks(inside.join("real")).is_ok());
+}
+
+#[test]
+fn indirect_link_into_proc_is_rejected() {
+    let (_temp, host) = canonical_tempdir();
+    symlink("/proc/self/root", host.join("container")).unwrap();
+
+    assert_rejected(host.join("container/etc"));
+}
+
+#[test]
+fn nested_boundary_counts_as_a_symlink() {
+    assert_rejected("/proc/self/root/proc/self/root");
+}
+
+#[test]
+fn error_names_the_symlink() {
+    let (_temp, host) = canonical_tempdir();
+    symlink("/", host.join("up")).unwrap();
+
+    let err = canonicalize_reject_symlinks(host.join("up/etc")).unwrap_err();
+
+    assert!(err
+        .to_string()
+        .contains(&*host.join("up").to_string_lossy()));
+}
+
+#[test]
+fn missing_component_is_not_found() {
+    let (_temp, host) = canonical_tempdir();
+
+    let err = canonicalize_reject_symlinks(host.join("missing/file")).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
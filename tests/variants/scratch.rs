@@ -0,0 +1,73 @@
+#![cfg(target_os = "linux")]
+
+//! `canonicalize_with_scratch`: the results of `canonicalize`, with its scanner
+//! buffers reused across calls.
+
+use proc_canonicalize::{canonicalize, canonicalize_with_scratch, Scratch};
+use std::io;
+use std::os::unix::fs::symlink;
+
+#[test]
+fn one_scratch_gives_canonicalize_results_for_a_mix_of_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let container = dir.path().join("container");
+    symlink("/proc/self/root", &container).unwrap();
+    std::fs::create_dir(dir.path().join("real")).unwrap();
+    symlink("real", dir.path().join("alias")).unwrap();
+    let inputs = [
+        container.join("etc"),
+        dir.path().join("alias/."),
+        "/proc/self/root/usr/../etc".into(),
+        dir.path()
+            .join("a/very/long/path/that/grows/the/buffers/../../../.."),
+        "/etc".into(),
+        container.clone(),
+    ];
+
+    let mut scratch = Scratch::new();
+    for input in &inputs {
+        let with_scratch = canonicalize_with_scratch(input, &mut scratch);
+        match (with_scratch, canonicalize(input)) {
+            (Ok(got), Ok(expected)) => assert_eq!(got, expected, "{input:?}"),
+            (Err(got), Err(expected)) => assert_eq!(got.kind(), expected.kind(), "{input:?}"),
+            (got, expected) => panic!("{input:?}: {got:?} vs {expected:?}"),
+        }
+    }
+}
+
+#[test]
+fn failed_call_leaves_the_scratch_usable() {
+    let dir = tempfile::tempdir().unwrap();
+    symlink("loop", dir.path().join("loop")).unwrap();
+    let mut scratch = Scratch::new();
+
+    let err = canonicalize_with_scratch(dir.path().join("loop/x"), &mut scratch).unwrap_err();
+    let resolved = canonicalize_with_scratch("/proc/self/root/etc", &mut scratch).unwrap();
+
+    assert_eq!(
+        err.kind(),
+        canonicalize(dir.path().join("loop/x")).unwrap_err().kind()
+    );
+    assert_eq!(resolved, canonicalize("/proc/self/root/etc").unwrap());
+}
+
+#[test]
+fn empty_path_fails_as_with_canonicalize() {
+    let err = canonicalize_with_scratch("", &mut Scratch::new()).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn scratch_can_move_to_another_thread() {
+    let mut scratch = Scratch::new();
+    canonicalize_with_scratch("/proc/self/root/etc", &mut scratch).unwrap();
+
+    let resolved = std::thread::spawn(move || {
+        canonicalize_with_scratch("/proc/self/root/etc", &mut scratch).unwrap()
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(resolved, canonicalize("/proc/self/root/etc").unwrap());
+}